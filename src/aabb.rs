@@ -0,0 +1,254 @@
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use crate::{vector, Float, SqMatrix4, Vector};
+
+//a Aabb
+//tp Aabb
+/// An axis-aligned bounding box in D dimensions, as its minimum and
+/// maximum corners
+///
+/// The box is never empty; it always contains at least one point
+/// (its minimum corner is no greater than its maximum corner in every
+/// coordinate). Operations that may produce an empty box, such as
+/// [Aabb::from_points] and [Aabb::intersection], return an [Option].
+///
+/// In two dimensions this is a rectangle, such as the bounds of a user
+/// interface element; in three dimensions it is the bounds of an
+/// object in a scene.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{Aabb, FArray, Vector};
+/// type Vec2 = FArray<f64, 2>;
+/// let points = [[0., 1.], [2., -1.], [1., 3.]].map(Vec2::from_array);
+/// let bounds = Aabb::from_points(points.iter()).unwrap();
+/// assert_eq!(bounds.min().into_array(), [0., -1.]);
+/// assert_eq!(bounds.max().into_array(), [2., 3.]);
+/// assert_eq!(bounds.volume(), 8.);
+/// assert!(bounds.contains(&Vec2::from_array([1., 1.])));
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Aabb<F, V, const D: usize>
+where
+    F: Float,
+    V: Vector<F, D>,
+{
+    /// The minimum corner
+    min: V,
+    /// The maximum corner
+    max: V,
+    /// Marker for the float type
+    #[serde(skip)]
+    phantom: std::marker::PhantomData<F>,
+}
+
+//ip Display for Aabb
+impl<F, V, const D: usize> std::fmt::Display for Aabb<F, V, D>
+where
+    F: Float,
+    V: Vector<F, D>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "[{} - {}]", self.min, self.max)
+    }
+}
+
+//ip Aabb
+impl<F, V, const D: usize> Aabb<F, V, D>
+where
+    F: Float,
+    V: Vector<F, D>,
+{
+    //cp new
+    /// Create a box from two opposite corners, in any order
+    pub fn new(a: &V, b: &V) -> Self {
+        Self {
+            min: V::from_array(vector::comp_min(*a.as_ref(), b.as_ref())),
+            max: V::from_array(vector::comp_max(*a.as_ref(), b.as_ref())),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    //cp of_point
+    /// Create a box containing a single point
+    pub fn of_point(p: &V) -> Self {
+        Self::new(p, p)
+    }
+
+    //cp from_points
+    /// Create the smallest box containing all of the points, or None if
+    /// there are no points
+    pub fn from_points<'a, I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a V>,
+        V: 'a,
+    {
+        let mut points = points.into_iter();
+        let mut aabb = Self::of_point(points.next()?);
+        for p in points {
+            aabb.include(p);
+        }
+        Some(aabb)
+    }
+
+    //mp min
+    /// Get the minimum corner of the box
+    pub fn min(&self) -> V {
+        self.min
+    }
+
+    //mp max
+    /// Get the maximum corner of the box
+    pub fn max(&self) -> V {
+        self.max
+    }
+
+    //mp include
+    /// Expand the box, if required, to include a point
+    pub fn include(&mut self, p: &V) {
+        self.min = V::from_array(vector::comp_min(*self.min.as_ref(), p.as_ref()));
+        self.max = V::from_array(vector::comp_max(*self.max.as_ref(), p.as_ref()));
+    }
+
+    //mp union
+    /// Get the smallest box that contains this and another box
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: V::from_array(vector::comp_min(*self.min.as_ref(), other.min.as_ref())),
+            max: V::from_array(vector::comp_max(*self.max.as_ref(), other.max.as_ref())),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    //mp intersection
+    /// Get the box that is the overlap of this and another box, or
+    /// None if they do not overlap
+    ///
+    /// Boxes that touch (but do not overlap) have an intersection with
+    /// no volume
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = V::from_array(vector::comp_max(*self.min.as_ref(), other.min.as_ref()));
+        let max = V::from_array(vector::comp_min(*self.max.as_ref(), other.max.as_ref()));
+        if (0..D).any(|i| min[i] > max[i]) {
+            None
+        } else {
+            Some(Self {
+                min,
+                max,
+                phantom: std::marker::PhantomData,
+            })
+        }
+    }
+
+    //mp intersects
+    /// Return true if this box overlaps (or touches) another box
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..D).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+
+    //mp contains
+    /// Return true if a point is within the box (or on its boundary)
+    pub fn contains(&self, p: &V) -> bool {
+        (0..D).all(|i| self.min[i] <= p[i] && p[i] <= self.max[i])
+    }
+
+    //mp expand
+    /// Get the box with each face moved outwards by a distance; if the
+    /// distance is negative then the box shrinks, but by no more than
+    /// to its centre
+    #[must_use]
+    pub fn expand(&self, by: F) -> Self {
+        let centre = self.center();
+        Self {
+            min: V::from_array(vector::comp_min(*(self.min - by).as_ref(), centre.as_ref())),
+            max: V::from_array(vector::comp_max(*(self.max + by).as_ref(), centre.as_ref())),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    //mp center
+    /// Get the centre of the box
+    pub fn center(&self) -> V {
+        (self.min + self.max) * F::frac(1, 2)
+    }
+
+    //mp extent
+    /// Get the size of the box in each coordinate (its maximum corner
+    /// minus its minimum corner)
+    pub fn extent(&self) -> V {
+        self.max - self.min
+    }
+
+    //mp volume
+    /// Get the volume of the box (in two dimensions, its area)
+    pub fn volume(&self) -> F {
+        let extent = self.extent();
+        (0..D).fold(F::one(), |acc, i| acc * extent[i])
+    }
+
+    //mp closest_point
+    /// Get the point within the box that is closest to a point; if the
+    /// point is within the box then this is the point itself
+    pub fn closest_point(&self, p: &V) -> V {
+        let p = vector::comp_max(*p.as_ref(), self.min.as_ref());
+        V::from_array(vector::comp_min(p, self.max.as_ref()))
+    }
+}
+
+//ip Aabb in three dimensions
+impl<F, V> Aabb<F, V, 3>
+where
+    F: Float,
+    V: Vector<F, 3>,
+{
+    //mp transform
+    /// Get the bounds of this box transformed by a 4-by-4 matrix
+    ///
+    /// For an affine matrix this uses the method of Arvo, which is
+    /// exact; for a projective matrix the eight corners are
+    /// transformed, which requires them all to be in front of the
+    /// viewer
+    #[must_use]
+    pub fn transform<V4, M4>(&self, m: &M4) -> Self
+    where
+        V4: Vector<F, 4>,
+        M4: SqMatrix4<F, V, V4>,
+    {
+        let m: &[F; 16] = m.as_ref();
+        let zero = F::zero();
+        if m[12] == zero && m[13] == zero && m[14] == zero && m[15] == F::one() {
+            let mut min = [m[3], m[7], m[11]];
+            let mut max = min;
+            for r in 0..3 {
+                for c in 0..3 {
+                    let a = m[r * 4 + c] * self.min[c];
+                    let b = m[r * 4 + c] * self.max[c];
+                    min[r] += a.min(b);
+                    max[r] += a.max(b);
+                }
+            }
+            Self::new(&V::from_array(min), &V::from_array(max))
+        } else {
+            let corner = |i: usize| {
+                let x = if i & 1 == 0 { self.min[0] } else { self.max[0] };
+                let y = if i & 2 == 0 { self.min[1] } else { self.max[1] };
+                let z = if i & 4 == 0 { self.min[2] } else { self.max[2] };
+                let mut p = [zero; 3];
+                for (r, pr) in p.iter_mut().enumerate() {
+                    *pr = m[r * 4] * x + m[r * 4 + 1] * y + m[r * 4 + 2] * z + m[r * 4 + 3];
+                }
+                let w = m[12] * x + m[13] * y + m[14] * z + m[15];
+                V::from_array(vector::reduce(p, w))
+            };
+            let mut aabb = Self::of_point(&corner(0));
+            for i in 1..8 {
+                aabb.include(&corner(i));
+            }
+            aabb
+        }
+    }
+}
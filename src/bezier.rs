@@ -0,0 +1,607 @@
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use crate::{geo2d, Aabb, Float, SqMatrix, Vector};
+
+//a Bezier
+//tp Bezier
+/// A linear, quadratic or cubic Bezier curve in D dimensions
+///
+/// The curve is given by its control points; it starts at the first
+/// and ends at the last, and for quadratic and cubic curves the other
+/// points pull the curve towards them. A point on the curve is given
+/// by a parameter `t` from 0 (the start) to 1 (the end).
+///
+/// In two dimensions this may be (for example) part of the outline of
+/// a glyph of a font; in three dimensions it may be part of the path
+/// of a camera, which can be traversed at a constant speed using
+/// [Bezier::t_at_length].
+///
+/// It is serialized as the sequence of its control points.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{Bezier, FArray, Vector};
+/// type Vec2 = FArray<f64, 2>;
+/// let p = [[0., 0.], [1., 2.], [2., 0.]].map(Vec2::from_array);
+/// let b = Bezier::quadratic(&p[0], &p[1], &p[2]);
+/// assert_eq!(b.point_at(0.5).into_array(), [1., 1.]);
+/// assert_eq!(b.tangent_at(0.5).into_array(), [2., 0.]);
+/// let (first, second) = b.bisect();
+/// assert_eq!(first.end().into_array(), [1., 1.]);
+/// assert_eq!(second.start().into_array(), [1., 1.]);
+/// assert_eq!(b.bounds().max().into_array(), [2., 1.]);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Bezier<F, V, const D: usize>
+where
+    F: Float,
+    V: Vector<F, D>,
+{
+    /// The number of control points, from 2 to 4
+    num: usize,
+    /// The control points; only the first `num` are used
+    pts: [V; 4],
+    /// Marker for the float type
+    phantom: std::marker::PhantomData<F>,
+}
+
+//ip Display for Bezier
+impl<F, V, const D: usize> std::fmt::Display for Bezier<F, V, D>
+where
+    F: Float,
+    V: Vector<F, D>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Bezier[")?;
+        for (i, p) in self.control_points().iter().enumerate() {
+            if i > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{p}")?;
+        }
+        write!(f, "]")
+    }
+}
+
+//ip Serialize for Bezier
+impl<F, V, const D: usize> Serialize for Bezier<F, V, D>
+where
+    F: Float,
+    V: Vector<F, D> + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.num))?;
+        for p in self.control_points() {
+            seq.serialize_element(p)?;
+        }
+        seq.end()
+    }
+}
+
+//ip Deserialize for Bezier
+impl<'de, F, V, const D: usize> Deserialize<'de> for Bezier<F, V, D>
+where
+    F: Float,
+    V: Vector<F, D> + Deserialize<'de>,
+{
+    fn deserialize<DE>(deserializer: DE) -> Result<Self, DE::Error>
+    where
+        DE: serde::Deserializer<'de>,
+    {
+        let pts = Vec::<V>::deserialize(deserializer)?;
+        match pts[..] {
+            [p0, p1] => Ok(Self::line(&p0, &p1)),
+            [p0, c, p1] => Ok(Self::quadratic(&p0, &c, &p1)),
+            [p0, c0, c1, p1] => Ok(Self::cubic(&p0, &c0, &c1, &p1)),
+            _ => Err(serde::de::Error::invalid_length(
+                pts.len(),
+                &"2 to 4 control points",
+            )),
+        }
+    }
+}
+
+//ip Bezier
+impl<F, V, const D: usize> Bezier<F, V, D>
+where
+    F: Float,
+    V: Vector<F, D>,
+{
+    //fi of_points
+    fn of_points(num: usize, pts: [V; 4]) -> Self {
+        Self {
+            num,
+            pts,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    //cp line
+    /// Create a straight line from one point to another
+    pub fn line(p0: &V, p1: &V) -> Self {
+        Self::of_points(2, [*p0, *p1, *p1, *p1])
+    }
+
+    //cp quadratic
+    /// Create a quadratic Bezier from a start point, a control point,
+    /// and an end point
+    pub fn quadratic(p0: &V, c: &V, p1: &V) -> Self {
+        Self::of_points(3, [*p0, *c, *p1, *p1])
+    }
+
+    //cp cubic
+    /// Create a cubic Bezier from a start point, two control points,
+    /// and an end point
+    pub fn cubic(p0: &V, c0: &V, c1: &V, p1: &V) -> Self {
+        Self::of_points(4, [*p0, *c0, *c1, *p1])
+    }
+
+    //mp degree
+    /// Get the degree of the curve; 1 for a line, 2 for a quadratic,
+    /// and 3 for a cubic
+    pub fn degree(&self) -> usize {
+        self.num - 1
+    }
+
+    //mp control_points
+    /// Get the control points of the curve, from its start to its end
+    pub fn control_points(&self) -> &[V] {
+        &self.pts[0..self.num]
+    }
+
+    //mp start
+    /// Get the start point of the curve
+    pub fn start(&self) -> V {
+        self.pts[0]
+    }
+
+    //mp end
+    /// Get the end point of the curve
+    pub fn end(&self) -> V {
+        self.pts[self.num - 1]
+    }
+
+    //mp reversed
+    /// Get the same curve traversed from its end to its start
+    #[must_use]
+    pub fn reversed(&self) -> Self {
+        let mut pts = self.pts;
+        pts[0..self.num].reverse();
+        Self::of_points(self.num, pts)
+    }
+
+    //fi casteljau
+    /// Evaluate the Bezier with `n` control points at `t`, using the
+    /// algorithm of de Casteljau
+    fn casteljau(n: usize, mut pts: [V; 4], t: F) -> V {
+        for k in (1..n).rev() {
+            for i in 0..k {
+                pts[i] = pts[i].mix(&pts[i + 1], t);
+            }
+        }
+        pts[0]
+    }
+
+    //fi hodograph
+    /// Get the control points of the derivative of a Bezier with `n`
+    /// control points, of which there are `n-1`
+    fn hodograph(n: usize, pts: &[V; 4]) -> [V; 4] {
+        let mut h = [V::zero(); 4];
+        let d = F::int(n as isize - 1);
+        for i in 0..(n - 1) {
+            h[i] = (pts[i + 1] - pts[i]) * d;
+        }
+        h
+    }
+
+    //mp point_at
+    /// Get the point on the curve at parameter `t`, using the
+    /// algorithm of de Casteljau
+    pub fn point_at(&self, t: F) -> V {
+        Self::casteljau(self.num, self.pts, t)
+    }
+
+    //mp tangent_at
+    /// Get the derivative of the curve with respect to `t` at
+    /// parameter `t`
+    ///
+    /// This is zero where the curve has coincident control points at
+    /// its start or end, or at a cusp
+    pub fn tangent_at(&self, t: F) -> V {
+        let h = Self::hodograph(self.num, &self.pts);
+        Self::casteljau(self.num - 1, h, t)
+    }
+
+    //mp second_derivative_at
+    /// Get the second derivative of the curve with respect to `t` at
+    /// parameter `t`; this is zero for a line
+    pub fn second_derivative_at(&self, t: F) -> V {
+        if self.num < 3 {
+            return V::zero();
+        }
+        let h = Self::hodograph(self.num, &self.pts);
+        let h2 = Self::hodograph(self.num - 1, &h);
+        Self::casteljau(self.num - 2, h2, t)
+    }
+
+    //mp normal_at
+    /// Get the principal normal of the curve at parameter `t`; this is
+    /// the unit vector perpendicular to the tangent in the direction
+    /// in which the curve is turning
+    ///
+    /// This is None where the curve is not turning (such as along a
+    /// line), or where its tangent is zero. In two dimensions the
+    /// normal to one side of the curve is given by
+    /// [Bezier::perpendicular_at].
+    pub fn normal_at(&self, t: F) -> Option<V> {
+        let tangent = self.tangent_at(t);
+        let l2 = tangent.length_sq();
+        if l2 == F::zero() {
+            return None;
+        }
+        let a = self.second_derivative_at(t);
+        let n = a - tangent * (a.dot(&tangent) / l2);
+        let l = n.length();
+        if l <= F::epsilon() * a.length() || l == F::zero() {
+            None
+        } else {
+            Some(n / l)
+        }
+    }
+
+    //mp split
+    /// Split the curve at parameter `t` into two curves of the same
+    /// degree, the first from the start to the point at `t`, and the
+    /// second from there to the end
+    pub fn split(&self, t: F) -> (Self, Self) {
+        let n = self.num;
+        let mut pts = self.pts;
+        let mut first = self.pts;
+        let mut second = self.pts;
+        for k in 1..n {
+            for i in 0..(n - k) {
+                pts[i] = pts[i].mix(&pts[i + 1], t);
+            }
+            first[k] = pts[0];
+            second[n - 1 - k] = pts[n - 1 - k];
+        }
+        for i in n..4 {
+            first[i] = first[n - 1];
+            second[i] = second[n - 1];
+        }
+        (Self::of_points(n, first), Self::of_points(n, second))
+    }
+
+    //mp bisect
+    /// Split the curve in two at its midpoint parameter, `t` = 0.5
+    pub fn bisect(&self) -> (Self, Self) {
+        self.split(F::frac(1, 2))
+    }
+
+    //mp bounds
+    /// Get the tight bounds of the curve
+    ///
+    /// These are the bounds of its end points and of the points at
+    /// which the curve turns in each coordinate, which are usually
+    /// within the bounds of the control points
+    pub fn bounds(&self) -> Aabb<F, V, D> {
+        let mut bounds = Aabb::new(&self.start(), &self.end());
+        let [h0, h1, h2, _] = Self::hodograph(self.num, &self.pts);
+        let (zero, one) = (F::zero(), F::one());
+        for c in 0..D {
+            let mut roots = [zero; 2];
+            let num_roots = match self.num {
+                3 => Self::linear_roots(h0[c], h1[c], &mut roots),
+                4 => Self::quadratic_roots(h0[c], h1[c], h2[c], &mut roots),
+                _ => 0,
+            };
+            for t in &roots[0..num_roots] {
+                if *t > zero && *t < one {
+                    bounds.include(&self.point_at(*t));
+                }
+            }
+        }
+        bounds
+    }
+
+    //fi linear_roots
+    /// Find the root of the linear Bernstein polynomial a(1-t) + bt
+    fn linear_roots(a: F, b: F, roots: &mut [F; 2]) -> usize {
+        if a == b {
+            0
+        } else {
+            roots[0] = a / (a - b);
+            1
+        }
+    }
+
+    //fi quadratic_roots
+    /// Find the roots of the quadratic Bernstein polynomial
+    /// a(1-t)^2 + 2bt(1-t) + ct^2
+    fn quadratic_roots(a: F, b: F, c: F, roots: &mut [F; 2]) -> usize {
+        let two = F::int(2);
+        let qa = a - two * b + c;
+        let qb = two * (b - a);
+        if qa.abs() <= F::epsilon() * (a.abs() + b.abs() + c.abs()) {
+            return Self::linear_roots(a, a + qb, roots);
+        }
+        let disc = qb * qb - F::int(4) * qa * a;
+        if disc < F::zero() {
+            return 0;
+        }
+        let sq = disc.sqrt();
+        roots[0] = (-qb - sq) / (two * qa);
+        roots[1] = (-qb + sq) / (two * qa);
+        2
+    }
+
+    //fi gauss_length
+    /// Get the length of the curve between two parameters, by
+    /// five-point Gauss-Legendre quadrature of its speed
+    fn gauss_length(&self, t0: F, t1: F) -> F {
+        const NODES: [(f64, f64); 5] = [
+            (0.0, 0.568_888_888_888_888_9),
+            (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+            (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+            (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+            (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+        ];
+        let half = (t1 - t0) * F::frac(1, 2);
+        let mid = t0 + half;
+        let sum = NODES.iter().fold(F::zero(), |acc, (x, w)| {
+            let speed = self.tangent_at(mid + half * F::from(*x).unwrap()).length();
+            acc + speed * F::from(*w).unwrap()
+        });
+        sum * half
+    }
+
+    //fi length_between
+    /// Get the length of the curve between two parameters, given an
+    /// estimate of it, by bisecting the range until the estimate is
+    /// within the tolerance of the sum of those of its halves
+    fn length_between(&self, t0: F, t1: F, estimate: F, tolerance: F, depth: usize) -> F {
+        let mid = (t0 + t1) * F::frac(1, 2);
+        let a = self.gauss_length(t0, mid);
+        let b = self.gauss_length(mid, t1);
+        if (a + b - estimate).abs() <= tolerance || depth >= 24 {
+            a + b
+        } else {
+            let tolerance = tolerance * F::frac(1, 2);
+            self.length_between(t0, mid, a, tolerance, depth + 1)
+                + self.length_between(mid, t1, b, tolerance, depth + 1)
+        }
+    }
+
+    //fi nearest_t
+    /// Refine an estimate of the parameter of the point on the curve
+    /// nearest to a point, using Newton-Raphson iteration
+    fn nearest_t(&self, p: &V, mut t: F) -> F {
+        for _ in 0..8 {
+            let d = self.point_at(t) - *p;
+            let tangent = self.tangent_at(t);
+            let f = d.dot(&tangent);
+            let df = tangent.length_sq() + d.dot(&self.second_derivative_at(t));
+            if df <= F::zero() {
+                break;
+            }
+            t = (t - f / df).max(F::zero()).min(F::one());
+        }
+        t
+    }
+
+    //mp length
+    /// Get the length of the curve, to within a tolerance
+    ///
+    /// This uses adaptive Gauss-Legendre quadrature of the speed of
+    /// the curve (the length of its tangent)
+    pub fn length(&self, tolerance: F) -> F {
+        self.length_to(F::one(), tolerance)
+    }
+
+    //mp length_to
+    /// Get the length of the curve from its start to the point at
+    /// parameter `t`, to within a tolerance
+    pub fn length_to(&self, t: F, tolerance: F) -> F {
+        let t = t.max(F::zero()).min(F::one());
+        if t == F::zero() {
+            return F::zero();
+        }
+        let estimate = self.gauss_length(F::zero(), t);
+        self.length_between(F::zero(), t, estimate, tolerance, 0)
+    }
+
+    //mp t_at_length
+    /// Get the parameter `t` of the point at a length along the curve
+    /// from its start, to within a tolerance; this is the inverse of
+    /// [Bezier::length_to]
+    ///
+    /// Lengths beyond the ends of the curve give a parameter of zero
+    /// or one. Stepping the length evenly gives points that are evenly
+    /// spaced along the curve, such as for a constant speed along a
+    /// path.
+    ///
+    /// This uses Newton-Raphson iteration, falling back to bisection
+    pub fn t_at_length(&self, length: F, tolerance: F) -> F {
+        let (zero, one) = (F::zero(), F::one());
+        let total = self.length(tolerance);
+        if length <= zero || total == zero {
+            return zero;
+        }
+        if length >= total {
+            return one;
+        }
+        let (mut lo, mut hi) = (zero, one);
+        let mut t = length / total;
+        for _ in 0..64 {
+            let error = self.length_to(t, tolerance) - length;
+            if error.abs() <= tolerance {
+                break;
+            }
+            if error > zero {
+                hi = t;
+            } else {
+                lo = t;
+            }
+            let speed = self.tangent_at(t).length();
+            let next = if speed > zero { t - error / speed } else { lo };
+            t = if next > lo && next < hi {
+                next
+            } else {
+                (lo + hi) * F::frac(1, 2)
+            };
+        }
+        t
+    }
+}
+
+//ip Bezier in two dimensions
+impl<F, V> Bezier<F, V, 2>
+where
+    F: Float,
+    V: Vector<F, 2>,
+{
+    //fi perpendicular
+    #[inline]
+    fn perpendicular(v: &V) -> V {
+        V::from_array([-v[1], v[0]])
+    }
+
+    //fi direction_at
+    /// Get the unit direction of the curve at parameter `t`; where the
+    /// tangent is zero at the start or end of the curve this is the
+    /// direction to (or from) the nearest distinct control point
+    fn direction_at(&self, t: F) -> V {
+        let tangent = self.tangent_at(t);
+        if !tangent.is_zero() {
+            return tangent.normalize();
+        }
+        let pts = self.control_points();
+        let d = if t <= F::frac(1, 2) {
+            pts.iter().map(|p| *p - pts[0]).find(|d| !d.is_zero())
+        } else {
+            let end = pts[pts.len() - 1];
+            pts.iter().rev().map(|p| end - *p).find(|d| !d.is_zero())
+        };
+        d.map_or_else(V::zero, |d| d.normalize())
+    }
+
+    //mp perpendicular_at
+    /// Get the unit normal to the left of the curve at parameter `t`
+    /// (its direction rotated anticlockwise by a right angle)
+    ///
+    /// Unlike [Bezier::normal_at] this is always to the same side of
+    /// the curve; it is zero only if the curve is a single point.
+    pub fn perpendicular_at(&self, t: F) -> V {
+        Self::perpendicular(&self.direction_at(t))
+    }
+
+    //mp offset
+    /// Approximate the curve offset by a distance to its left (to its
+    /// right if the distance is negative), as a sequence of curves of
+    /// the same degree, each within a tolerance of the offset curve
+    ///
+    /// The end points of each curve are offset along the normals at
+    /// the ends, and its end tangents are adjusted for the curvature
+    /// there; the curve is bisected until the approximation is within
+    /// the tolerance.
+    pub fn offset(&self, distance: F, tolerance: F) -> Vec<Self> {
+        let mut curves = Vec::new();
+        self.offset_within(distance, tolerance, 0, &mut curves);
+        curves
+    }
+
+    //fi offset_within
+    fn offset_within(&self, distance: F, tolerance: F, depth: usize, curves: &mut Vec<Self>) {
+        let approx = self.offset_approximation(distance);
+        let within = [F::frac(1, 4), F::frac(1, 2), F::frac(3, 4)]
+            .iter()
+            .all(|t| {
+                let q = approx.point_at(*t);
+                let p = self.point_at(self.nearest_t(&q, *t));
+                (q.distance(&p) - distance.abs()).abs() <= tolerance
+            });
+        if within || depth >= 16 {
+            curves.push(approx);
+        } else {
+            let (a, b) = self.bisect();
+            a.offset_within(distance, tolerance, depth + 1, curves);
+            b.offset_within(distance, tolerance, depth + 1, curves);
+        }
+    }
+
+    //fi cross
+    #[inline]
+    fn cross(a: &V, b: &V) -> F {
+        a[0] * b[1] - a[1] * b[0]
+    }
+
+    //fi curvature_at
+    /// Get the signed curvature of the curve at parameter `t`, which
+    /// is positive where it turns anticlockwise, or zero where its
+    /// tangent is zero
+    fn curvature_at(&self, t: F) -> F {
+        let tangent = self.tangent_at(t);
+        let l = tangent.length();
+        if l == F::zero() {
+            F::zero()
+        } else {
+            Self::cross(&tangent, &self.second_derivative_at(t)) / (l * l * l)
+        }
+    }
+
+    //fi offset_approximation
+    /// Approximate the offset of the curve by a single curve of the
+    /// same degree
+    ///
+    /// The end points are offset along the normals at the ends; for a
+    /// cubic the end tangents are scaled by the change in the radius
+    /// of curvature at the ends (so that the offset of a circular arc
+    /// is a circular arc), and for a quadratic the control point is
+    /// the intersection of the offset end tangents
+    fn offset_approximation(&self, distance: F) -> Self {
+        let n = self.num;
+        let one = F::one();
+        let start = self.pts[0] + self.perpendicular_at(F::zero()) * distance;
+        let end = self.pts[n - 1] + self.perpendicular_at(one) * distance;
+        let mut pts = [start, end, end, end];
+        match n {
+            3 => {
+                let (u, v) = (self.direction_at(F::zero()), self.direction_at(one));
+                let cross = Self::cross(&u, &v);
+                pts[1] = if cross.abs() <= F::epsilon() {
+                    (start + end) * F::frac(1, 2)
+                } else {
+                    start + u * (Self::cross(&(end - start), &v) / cross)
+                };
+            }
+            4 => {
+                let k0 = one - distance * self.curvature_at(F::zero());
+                let k1 = one - distance * self.curvature_at(one);
+                pts[1] = start + (self.pts[1] - self.pts[0]) * k0;
+                pts[2] = end + (self.pts[2] - self.pts[3]) * k1;
+                pts[3] = end;
+            }
+            _ => (),
+        }
+        Self::of_points(n, pts)
+    }
+
+    //mp transform
+    /// Get the curve transformed by an affine [geo2d::Transform]
+    ///
+    /// This is exact, as an affine transformation of a Bezier curve is
+    /// the curve of the transformed control points
+    #[must_use]
+    pub fn transform<M2>(&self, t: &geo2d::Transform<F, V, M2>) -> Self
+    where
+        M2: SqMatrix<V, F, 2, 4>,
+    {
+        Self::of_points(self.num, self.pts.map(|p| t.apply(&p)))
+    }
+}
@@ -0,0 +1,53 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    decomposition.rs
+@brief   Decomposition of 4-by-4 matrices - part of geometry library
+ */
+
+//a MatrixDecomposition
+//tp MatrixDecomposition
+/// The parts of a 4-by-4 matrix, as produced by
+/// [crate::matrix::decompose4] or [crate::SqMatrix4::decompose]
+///
+/// The matrix is the product P . T . R . S . H, applied to column
+/// vectors, where:
+///
+/// * H is the shear, the identity matrix with the XY, XZ and YZ shear
+///   factors in its upper triangle (so the X coordinate has XY times
+///   the Y coordinate and XZ times the Z coordinate added to it, and
+///   the Y coordinate has YZ times the Z coordinate added to it)
+///
+/// * S is the scaling along each axis; if the matrix includes a
+///   reflection then the X scaling is negative
+///
+/// * R is the rotation, as a unit quaternion
+///
+/// * T is the translation
+///
+/// * P is the identity matrix with its last row replaced by the
+///   perspective row; for an affine matrix this is (0, 0, 0, 1)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MatrixDecomposition<V3, Q, V4> {
+    /// The translation
+    pub translation: V3,
+    /// The rotation, as a unit quaternion
+    pub rotation: Q,
+    /// The scaling along each of the X, Y and Z axes
+    pub scale: V3,
+    /// The XY, XZ and YZ shear factors
+    pub shear: V3,
+    /// The perspective row
+    pub perspective: V4,
+}
@@ -0,0 +1,127 @@
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use crate::{dquat, FArray, FQArrayTrans, QArray};
+use crate::{DualQuaternion, Float, Transform, Vector};
+
+//tp DQArray
+/// A dual quaternion, as a pair of [QArray] for its real and dual
+/// parts, describing a rigid transformation (a rotation followed by
+/// a translation)
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{DQArray, DualQuaternion, FArray, QArray, Quaternion, Vector};
+/// type Quat = QArray<f64, FArray<f64, 3>, FArray<f64, 4>>;
+/// let z = FArray::from_array([0., 0., 1.]);
+/// let a = DQArray::of_rotation_translation(&Quat::of_axis_angle(&z, 1.), &(z * 2.));
+/// let b = DQArray::of_rotation_translation(&Quat::of_axis_angle(&z, -1.), &(z * 4.));
+/// let ab = a * b;
+/// assert!(ab.translation().distance(&(z * 6.)) < 1E-12);
+/// assert!(ab.rotation().as_rijk().0 > 1. - 1E-12);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct DQArray<F, V3, V4>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+    V4: Vector<F, 4>,
+{
+    /// The real quaternion, which is the rotation
+    real: QArray<F, V3, V4>,
+    /// The dual quaternion, which is half the translation applied
+    /// after the rotation
+    dual: QArray<F, V3, V4>,
+}
+
+//ip Default for DQArray
+impl<F, V3, V4> std::default::Default for DQArray<F, V3, V4>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+    V4: Vector<F, 4>,
+{
+    /// The default is the identity transformation
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+//ip Display for DQArray
+impl<F, V3, V4> std::fmt::Display for DQArray<F, V3, V4>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+    V4: Vector<F, 4>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "dq[{} + e{}]", self.real, self.dual)
+    }
+}
+
+//ip std::ops::Mul<Self> for DQArray
+impl<F, V3, V4> std::ops::Mul<Self> for DQArray<F, V3, V4>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+    V4: Vector<F, 4>,
+{
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self::from_array(dquat::multiply(&self.into_array(), &other.into_array()))
+    }
+}
+
+//ip std::ops::MulAssign<Self> for DQArray
+impl<F, V3, V4> std::ops::MulAssign<Self> for DQArray<F, V3, V4>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+    V4: Vector<F, 4>,
+{
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+//ip DualQuaternion for DQArray
+impl<F, V3, V4> DualQuaternion<F, V3, V4, QArray<F, V3, V4>> for DQArray<F, V3, V4>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+    V4: Vector<F, 4>,
+{
+    fn of_real_dual(real: QArray<F, V3, V4>, dual: QArray<F, V3, V4>) -> Self {
+        Self { real, dual }
+    }
+    fn real(&self) -> QArray<F, V3, V4> {
+        self.real
+    }
+    fn dual(&self) -> QArray<F, V3, V4> {
+        self.dual
+    }
+}
+
+//ip From<FQArrayTrans> for DQArray
+impl<F> From<FQArrayTrans<F>> for DQArray<F, FArray<F, 3>, FArray<F, 4>>
+where
+    F: Float + Serialize,
+{
+    /// Convert the rotation and translation of an [FQArrayTrans] to a
+    /// dual quaternion; its scaling is discarded
+    fn from(t: FQArrayTrans<F>) -> Self {
+        Self::of_rotation_translation(&t.rotation(), &t.translation())
+    }
+}
+
+//ip From<DQArray> for FQArrayTrans
+impl<F> From<DQArray<F, FArray<F, 3>, FArray<F, 4>>> for FQArrayTrans<F>
+where
+    F: Float + Serialize,
+{
+    /// Convert a dual quaternion to an [FQArrayTrans] with a scale of one
+    fn from(dq: DQArray<F, FArray<F, 3>, FArray<F, 4>>) -> Self {
+        Self::of_trs(dq.translation(), dq.rotation(), F::one())
+    }
+}
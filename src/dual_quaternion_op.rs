@@ -0,0 +1,264 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    dual_quaternion_op.rs
+@brief   Dual quaternion operations - part of geometry library
+ */
+
+//a Imports
+use crate::quaternion_op as quat;
+use crate::vector_op as vector;
+use crate::Float;
+
+//a Notes on dual quaternions
+// A dual quaternion is real + e * dual, where e*e = 0, and real and
+// dual are quaternions; here it is stored as an array of eight
+// values, the real quaternion (i, j, k, r) followed by the dual
+// quaternion (i, j, k, r)
+//
+// A rigid transformation that rotates by the unit quaternion q and
+// then translates by t is the unit dual quaternion q + e * (t * q / 2),
+// where t is the pure quaternion (t, 0)
+//
+// For a unit dual quaternion, real . real = 1 and real . dual = 0
+
+//a Constructors and destructors
+//fi split
+/// Split a dual quaternion into its real and dual quaternions
+#[inline]
+fn split<V: Float>(dq: &[V; 8]) -> ([V; 4], [V; 4]) {
+    ([dq[0], dq[1], dq[2], dq[3]], [dq[4], dq[5], dq[6], dq[7]])
+}
+
+//fi join
+/// Join real and dual quaternions into a dual quaternion
+#[inline]
+fn join<V: Float>(real: &[V; 4], dual: &[V; 4]) -> [V; 8] {
+    [
+        real[0], real[1], real[2], real[3], dual[0], dual[1], dual[2], dual[3],
+    ]
+}
+
+//fp identity
+/// Create an identity dual quaternion
+#[must_use]
+#[inline]
+pub fn identity<V: Float>() -> [V; 8] {
+    join(&quat::identity(), &[V::zero(); 4])
+}
+
+//fp of_rotation_translation
+/// Create a dual quaternion for a rotation by a unit quaternion
+/// followed by a translation
+#[must_use]
+pub fn of_rotation_translation<V: Float>(q: &[V; 4], t: &[V; 3]) -> [V; 8] {
+    let t = [t[0], t[1], t[2], V::zero()];
+    let dual = vector::scale(quat::multiply(&t, q), V::frac(1, 2));
+    join(q, &dual)
+}
+
+//fp as_rotation_translation
+/// Find the rotation (as a unit quaternion) and the subsequent
+/// translation of a dual quaternion
+#[must_use]
+pub fn as_rotation_translation<V: Float>(dq: &[V; 8]) -> ([V; 4], [V; 3]) {
+    let (real, dual) = split(&normalize(*dq));
+    let t = quat::multiply(&dual, &quat::conjugate(&real));
+    let two = V::frac(2, 1);
+    (real, [t[0] * two, t[1] * two, t[2] * two])
+}
+
+//fp of_mat4
+/// Create a dual quaternion from a 4-by-4 matrix that is a rotation
+/// followed by a translation (in `m[3]`, `m[7]` and `m[11]`)
+#[must_use]
+pub fn of_mat4<V: Float>(m: &[V; 16]) -> [V; 8] {
+    let r = [m[0], m[1], m[2], m[4], m[5], m[6], m[8], m[9], m[10]];
+    of_rotation_translation(&quat::of_rotation(&r), &[m[3], m[7], m[11]])
+}
+
+//fp to_mat4
+/// Create the 4-by-4 matrix of the rotation and translation of a dual
+/// quaternion
+#[must_use]
+pub fn to_mat4<V: Float>(dq: &[V; 8]) -> [V; 16] {
+    let (q, t) = as_rotation_translation(dq);
+    let mut m = [V::zero(); 16];
+    quat::to_rotation4(&q, &mut m);
+    m[3] = t[0];
+    m[7] = t[1];
+    m[11] = t[2];
+    m
+}
+
+//a Mapping functions
+//cp multiply
+/// Multiply two dual quaternions; the result applies `b` and then `a`
+#[must_use]
+pub fn multiply<V: Float>(a: &[V; 8], b: &[V; 8]) -> [V; 8] {
+    let (ar, ad) = split(a);
+    let (br, bd) = split(b);
+    let real = quat::multiply(&ar, &br);
+    let dual = vector::add(
+        quat::multiply(&ar, &bd),
+        &quat::multiply(&ad, &br),
+        V::one(),
+    );
+    join(&real, &dual)
+}
+
+//cp conjugate
+/// Get the quaternion conjugate of a dual quaternion (both real and
+/// dual parts conjugated); for a unit dual quaternion this is its
+/// inverse
+#[must_use]
+pub fn conjugate<V: Float>(dq: &[V; 8]) -> [V; 8] {
+    let (real, dual) = split(dq);
+    join(&quat::conjugate(&real), &quat::conjugate(&dual))
+}
+
+//cp invert
+/// Get the inverse of a dual quaternion, which need not be a unit
+/// dual quaternion (but must have a nonzero real part)
+#[must_use]
+pub fn invert<V: Float>(dq: &[V; 8]) -> [V; 8] {
+    let (real, dual) = split(dq);
+    let r_inv = quat::invert(&real);
+    let d_inv = quat::multiply(&quat::multiply(&r_inv, &dual), &r_inv);
+    join(&r_inv, &vector::scale(d_inv, -V::one()))
+}
+
+//cp normalize
+/// Normalize a dual quaternion so that it is a unit dual quaternion,
+/// hence a rigid transformation; if its real part is (nearly) zero
+/// then the result is zero
+#[must_use]
+pub fn normalize<V: Float>(dq: [V; 8]) -> [V; 8] {
+    let (real, dual) = split(&dq);
+    let l = vector::length(&real);
+    if l < V::epsilon() {
+        return [V::zero(); 8];
+    }
+    let real = vector::reduce(real, l);
+    let dual = vector::reduce(dual, l);
+    let dual = vector::sub(dual, &real, vector::dot(&real, &dual));
+    join(&real, &dual)
+}
+
+//fp pow
+/// Raise a unit dual quaternion to a real power `t`, which scales
+/// both the angle and the distance of its screw motion by `t`
+///
+/// The screw motion used is the shorter of the two that the dual
+/// quaternion describes
+#[must_use]
+pub fn pow<V: Float>(dq: &[V; 8], t: V) -> [V; 8] {
+    let mut dq = normalize(*dq);
+    if dq[3] < V::zero() {
+        dq = vector::scale(dq, -V::one());
+    }
+    let (real, dual) = split(&dq);
+    let s = vector::length(&[real[0], real[1], real[2]]);
+    if s < V::epsilon() {
+        // (Nearly) a pure translation
+        let real = quat::pow(&real, t);
+        return join(&real, &vector::scale(dual, t));
+    }
+    let r_s = V::one() / s;
+    let half_angle = s.atan2(real[3]) * t;
+    let axis = [real[0] * r_s, real[1] * r_s, real[2] * r_s];
+    let half_pitch = -dual[3] * r_s;
+    let moment = vector::scale(
+        vector::sub([dual[0], dual[1], dual[2]], &axis, half_pitch * real[3]),
+        r_s,
+    );
+    let half_pitch = half_pitch * t;
+    let (sin, cos) = half_angle.sin_cos();
+    let real = vector::scale(axis, sin);
+    let dual = vector::add(vector::scale(moment, sin), &axis, half_pitch * cos);
+    [
+        real[0],
+        real[1],
+        real[2],
+        cos,
+        dual[0],
+        dual[1],
+        dual[2],
+        -half_pitch * sin,
+    ]
+}
+
+//fp sclerp
+/// Screw linear interpolation from one unit dual quaternion to another
+/// with parameter `t` from zero to one
+///
+/// This interpolates the rotation with a constant angular velocity
+/// and the translation with a constant velocity along the screw axis
+/// of the motion between the two, taking the shorter path
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{dquat, quat};
+/// let z = [0., 0., 1.];
+/// let a = dquat::of_rotation_translation(&quat::identity(), &[0., 0., 0.]);
+/// let b = dquat::of_rotation_translation(&quat::of_axis_angle(&z, 1.), &[0., 0., 4.]);
+/// let (q, t) = dquat::as_rotation_translation(&dquat::sclerp(0.5, &a, &b));
+/// assert!(quat::distance_sq(&q, &quat::of_axis_angle(&z, 0.5)) < 1E-12);
+/// assert!((t[2] - 2.0_f64).abs() < 1E-12);
+/// ```
+#[must_use]
+pub fn sclerp<V: Float>(t: V, a: &[V; 8], b: &[V; 8]) -> [V; 8] {
+    let diff = multiply(&invert(a), b);
+    multiply(a, &pow(&diff, t))
+}
+
+//fp apply3
+/// Apply a dual quaternion to a point, rotating and then translating it
+#[must_use]
+pub fn apply3<V: Float>(dq: &[V; 8], v: &[V; 3]) -> [V; 3] {
+    let (q, t) = as_rotation_translation(dq);
+    vector::add(quat::apply3(&q, v), &t, V::one())
+}
+
+//fp blend
+/// Blend many weighted dual quaternions using dual-quaternion linear
+/// blending (DLB), as used for skinning
+///
+/// Each dual quaternion is negated if required to be in the same
+/// hemisphere as the first, and the weighted sum is normalized.
+///
+/// Returns None if there are no dual quaternions, or if the sum has
+/// a zero real part
+#[must_use]
+pub fn blend<I: Iterator<Item = (V, [V; 8])>, V: Float>(iter: I) -> Option<[V; 8]> {
+    let mut first: Option<[V; 4]> = None;
+    let mut sum = [V::zero(); 8];
+    for (w, dq) in iter {
+        let (real, _) = split(&dq);
+        let pivot = *first.get_or_insert(real);
+        let w = if vector::dot(&pivot, &real) < V::zero() {
+            -w
+        } else {
+            w
+        };
+        sum = vector::add(sum, &dq, w);
+    }
+    let (real, _) = split(&sum);
+    if vector::length(&real) < V::epsilon() {
+        None
+    } else {
+        Some(normalize(sum))
+    }
+}
@@ -0,0 +1,49 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    error.rs
+@brief   Error types - part of geometry library
+ */
+
+//a Imports
+use crate::Num;
+
+//a SingularMatrix
+//tp SingularMatrix
+/// The error returned by the `try_inverse` functions and methods when
+/// the matrix (or transformation) has no useful inverse, because the
+/// magnitude of its determinant is no more than the epsilon supplied
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix;
+/// let err = matrix::try_inverse2(&[1., 2., 2., 4.], 1E-10).unwrap_err();
+/// assert_eq!(err.determinant, 0.);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SingularMatrix<F: Num> {
+    /// The determinant of the matrix
+    pub determinant: F,
+}
+
+//ip Display for SingularMatrix
+impl<F: Num> std::fmt::Display for SingularMatrix<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "matrix is singular (determinant {})", self.determinant)
+    }
+}
+
+//ip Error for SingularMatrix
+impl<F: Num> std::error::Error for SingularMatrix<F> {}
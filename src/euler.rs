@@ -0,0 +1,163 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    euler.rs
+@brief   Euler angle orders - part of geometry library
+ */
+
+//a EulerAxes
+//tp EulerAxes
+/// The sequence of axes about which the three rotations of a set of
+/// Euler angles are applied
+///
+/// The first six are Tait-Bryan angles, using all three axes; the
+/// last six are proper Euler angles, with the first and last axes
+/// the same
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EulerAxes {
+    /// X, then Y, then Z
+    Xyz,
+    /// X, then Z, then Y
+    Xzy,
+    /// Y, then X, then Z
+    Yxz,
+    /// Y, then Z, then X
+    Yzx,
+    /// Z, then X, then Y
+    Zxy,
+    /// Z, then Y, then X
+    Zyx,
+    /// X, then Y, then X
+    Xyx,
+    /// X, then Z, then X
+    Xzx,
+    /// Y, then X, then Y
+    Yxy,
+    /// Y, then Z, then Y
+    Yzy,
+    /// Z, then X, then Z
+    Zxz,
+    /// Z, then Y, then Z
+    Zyz,
+}
+
+//ip EulerAxes
+impl EulerAxes {
+    //mp indices
+    /// Get the indices (0 for X, 1 for Y, 2 for Z) of the three axes in order
+    #[must_use]
+    pub const fn indices(self) -> [usize; 3] {
+        match self {
+            Self::Xyz => [0, 1, 2],
+            Self::Xzy => [0, 2, 1],
+            Self::Yxz => [1, 0, 2],
+            Self::Yzx => [1, 2, 0],
+            Self::Zxy => [2, 0, 1],
+            Self::Zyx => [2, 1, 0],
+            Self::Xyx => [0, 1, 0],
+            Self::Xzx => [0, 2, 0],
+            Self::Yxy => [1, 0, 1],
+            Self::Yzy => [1, 2, 1],
+            Self::Zxz => [2, 0, 2],
+            Self::Zyz => [2, 1, 2],
+        }
+    }
+
+    //mp is_proper
+    /// Return true if these are proper Euler angles (the first and
+    /// last axes being the same), rather than Tait-Bryan angles
+    #[must_use]
+    pub const fn is_proper(self) -> bool {
+        let [a, _, c] = self.indices();
+        a == c
+    }
+}
+
+//a EulerOrder
+//tp EulerOrder
+/// The order and the frame of reference in which the three angles
+/// of a set of Euler angles are applied
+///
+/// The angles are always given in the order of the axes. With
+/// *intrinsic* rotations each rotation is about an axis of the frame
+/// that has been rotated by the previous rotations, so the rotation
+/// matrix for [EulerAxes::Xyz] is Rx(a0) . Ry(a1) . Rz(a2); with
+/// *extrinsic* rotations each is about a fixed axis, so the matrix is
+/// Rz(a2) . Ry(a1) . Rx(a0).
+///
+/// Common conventions are:
+///
+/// * aerospace yaw, pitch and roll: intrinsic [EulerAxes::Zyx]
+///
+/// * Blender and FBX 'XYZ' rotation orders: extrinsic [EulerAxes::Xyz]
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{EulerAxes, EulerOrder};
+/// let yaw_pitch_roll = EulerOrder::intrinsic(EulerAxes::Zyx);
+/// assert_eq!(yaw_pitch_roll.reversed(), EulerOrder::extrinsic(EulerAxes::Xyz));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EulerOrder {
+    /// The axes of the rotations, in the order the angles are given
+    pub axes: EulerAxes,
+    /// True if the rotations are intrinsic (about the rotated axes),
+    /// false if extrinsic (about the fixed axes)
+    pub intrinsic: bool,
+}
+
+//ip EulerOrder
+impl EulerOrder {
+    //cp intrinsic
+    /// Create an order of intrinsic rotations, about the rotated axes
+    #[must_use]
+    pub const fn intrinsic(axes: EulerAxes) -> Self {
+        Self {
+            axes,
+            intrinsic: true,
+        }
+    }
+
+    //cp extrinsic
+    /// Create an order of extrinsic rotations, about the fixed axes
+    #[must_use]
+    pub const fn extrinsic(axes: EulerAxes) -> Self {
+        Self {
+            axes,
+            intrinsic: false,
+        }
+    }
+
+    //mp reversed
+    /// Get the equivalent order with the axes reversed and the frame
+    /// of reference switched; the same rotation is described by this
+    /// order with angles in the reverse order
+    #[must_use]
+    pub const fn reversed(self) -> Self {
+        let axes = match self.axes {
+            EulerAxes::Xyz => EulerAxes::Zyx,
+            EulerAxes::Xzy => EulerAxes::Yzx,
+            EulerAxes::Yxz => EulerAxes::Zxy,
+            EulerAxes::Yzx => EulerAxes::Xzy,
+            EulerAxes::Zxy => EulerAxes::Yxz,
+            EulerAxes::Zyx => EulerAxes::Xyz,
+            axes => axes,
+        };
+        Self {
+            axes,
+            intrinsic: !self.intrinsic,
+        }
+    }
+}
@@ -2,7 +2,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{matrix, vector, FArray};
-use crate::{Float, SqMatrix, SqMatrix3, SqMatrix4, Vector};
+use crate::{Float, SingularMatrix, SqMatrix, SqMatrix3, SqMatrix4, Vector};
 
 //a Macros
 //mi index_ops!
@@ -93,6 +93,54 @@ macro_rules! binary_op_by_f {
     }
 }
 
+//mi sq_matrix_lu!
+/// Implement [SqMatrix] for an [FArray2] of a size for which there
+/// is no specific determinant and inverse, using the LU decomposition
+macro_rules! sq_matrix_lu {
+    { $d:expr, $d2:expr } => {
+        impl<F: Float> SqMatrix<FArray<F, $d>, F, $d, $d2> for FArray2<F, $d, $d2> {
+            fn from_array(data: [F; $d2]) -> Self {
+                Self { data }
+            }
+            fn into_array(self) -> [F; $d2] {
+                self.data
+            }
+            fn zero() -> Self {
+                Self {
+                    data: vector::zero(),
+                }
+            }
+            fn identity() -> Self {
+                Self::from_array(matrix::identity::<F, $d2, $d>())
+            }
+            fn is_zero(&self) -> bool {
+                vector::is_zero(&self.data)
+            }
+            fn set_zero(&mut self) {
+                vector::set_zero(&mut self.data)
+            }
+            fn transpose(&self) -> Self {
+                Self::from_array(matrix::transpose::<F, $d2, $d, $d>(self.data))
+            }
+            fn determinant(&self) -> F {
+                matrix::determinant::<F, $d2, $d>(&self.data)
+            }
+            fn inverse(&self) -> Self {
+                Self::from_array(matrix::inverse::<F, $d2, $d>(&self.data))
+            }
+            fn try_inverse(&self, epsilon: F) -> Result<Self, SingularMatrix<F>> {
+                matrix::try_inverse::<F, $d2, $d>(&self.data, epsilon).map(Self::from_array)
+            }
+            fn transform(&self, v: &FArray<F, $d>) -> FArray<F, $d> {
+                FArray::from_array(matrix::transform_vec::<F, $d2, $d, $d>(
+                    &self.data,
+                    v.as_ref(),
+                ))
+            }
+        }
+    }
+}
+
 //a FArray2
 //tp FArray2
 /// The [FArray2] is a wrapper around a `D2 = D`^2` sized array of [Float]s.
@@ -206,6 +254,9 @@ impl<F: Float> SqMatrix<FArray<F, 2>, F, 2, 4> for FArray2<F, 2, 4> {
     fn inverse(&self) -> Self {
         Self::from_array(matrix::inverse2(&self.data))
     }
+    fn try_inverse(&self, epsilon: F) -> Result<Self, SingularMatrix<F>> {
+        matrix::try_inverse2(&self.data, epsilon).map(Self::from_array)
+    }
     fn transform(&self, v: &FArray<F, 2>) -> FArray<F, 2> {
         FArray::from_array(matrix::multiply::<F, 4, 2, 2, 2, 2, 1>(
             &self.data,
@@ -256,6 +307,9 @@ impl<F: Float> SqMatrix<FArray<F, 3>, F, 3, 9> for FArray2<F, 3, 9> {
     fn inverse(&self) -> Self {
         Self::from_array(matrix::inverse3(&self.data))
     }
+    fn try_inverse(&self, epsilon: F) -> Result<Self, SingularMatrix<F>> {
+        matrix::try_inverse3(&self.data, epsilon).map(Self::from_array)
+    }
     fn transform(&self, v: &FArray<F, 3>) -> FArray<F, 3> {
         FArray::from_array(matrix::multiply::<F, 9, 3, 3, 3, 3, 1>(
             &self.data,
@@ -316,6 +370,9 @@ impl<F: Float> SqMatrix<FArray<F, 4>, F, 4, 16> for FArray2<F, 4, 16> {
     fn inverse(&self) -> Self {
         Self::from_array(matrix::inverse4(&self.data))
     }
+    fn try_inverse(&self, epsilon: F) -> Result<Self, SingularMatrix<F>> {
+        matrix::try_inverse4(&self.data, epsilon).map(Self::from_array)
+    }
     fn transform(&self, v: &FArray<F, 4>) -> FArray<F, 4> {
         FArray::from_array(matrix::multiply::<F, 16, 4, 4, 4, 4, 1>(
             &self.data,
@@ -343,3 +400,10 @@ impl<F: Float> SqMatrix4<F, FArray<F, 3>, FArray<F, 4>> for FArray2<F, 4, 16> {
         self.data[11] += by[2];
     }
 }
+
+//ip SqMatrix for FArray2 of sizes 5 to 9
+sq_matrix_lu! { 5, 25 }
+sq_matrix_lu! { 6, 36 }
+sq_matrix_lu! { 7, 49 }
+sq_matrix_lu! { 8, 64 }
+sq_matrix_lu! { 9, 81 }
@@ -0,0 +1,319 @@
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use crate::{matrix, vector, FArray, FArray2};
+use crate::{Float, Matrix};
+
+//a FArrayRC
+//tp FArrayRC
+/// The [FArrayRC] is a wrapper around an `RC = R*C` sized array of
+/// [Float]s, as a row-major R-by-C matrix.
+///
+/// It provides implementations of the traits required for a [Matrix]
+/// trait; matrix multiplication, with the `*` operator, is provided
+/// for all shapes up to 4-by-4 (the product of an R-by-X matrix and
+/// an X-by-C matrix being an R-by-C matrix), and by the
+/// [FArrayRC::multiply] method for any shape.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{FArray, FArrayRC, Matrix, Vector};
+/// // A 2-by-3 affine transformation that translates by (5, 6)
+/// let affine = FArrayRC::<f64, 2, 3, 6>::from_array([1., 0., 5., 0., 1., 6.]);
+/// let p = FArrayRC::<f64, 3, 1, 3>::from_array([1., 2., 1.]);
+/// let q: FArrayRC<f64, 2, 1, 2> = affine * p;
+/// assert_eq!(q.into_array(), [6., 8.]);
+/// assert_eq!((affine * FArray::from_array([1., 2., 1.])).into_array(), [6., 8.]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FArrayRC<F: Float, const R: usize, const C: usize, const RC: usize> {
+    data: [F; RC],
+}
+
+//ip FArrayRC
+impl<F: Float, const R: usize, const C: usize, const RC: usize> FArrayRC<F, R, C, RC> {
+    //mp multiply
+    /// Multiply by a C-by-X matrix to produce an R-by-X matrix, for any
+    /// shape
+    ///
+    /// # Panics
+    ///
+    /// Panics if CX is not C*X, or if RX is not R*X
+    pub fn multiply<const X: usize, const CX: usize, const RX: usize>(
+        &self,
+        other: &FArrayRC<F, C, X, CX>,
+    ) -> FArrayRC<F, R, X, RX> {
+        FArrayRC::from_array(matrix::multiply::<F, RC, CX, RX, R, C, X>(
+            &self.data,
+            &other.data,
+        ))
+    }
+}
+
+//ip Index, IndexMut for FArrayRC
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::Index<usize>
+    for FArrayRC<F, R, C, RC>
+{
+    type Output = F;
+    fn index(&self, index: usize) -> &F {
+        &self.data[index]
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::IndexMut<usize>
+    for FArrayRC<F, R, C, RC>
+{
+    fn index_mut(&mut self, index: usize) -> &mut F {
+        &mut self.data[index]
+    }
+}
+
+//ip AsRef, AsMut for FArrayRC
+impl<F: Float, const R: usize, const C: usize, const RC: usize> AsRef<[F; RC]>
+    for FArrayRC<F, R, C, RC>
+{
+    fn as_ref(&self) -> &[F; RC] {
+        &self.data
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> AsRef<[F]>
+    for FArrayRC<F, R, C, RC>
+{
+    fn as_ref(&self) -> &[F] {
+        &self.data
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> AsMut<[F; RC]>
+    for FArrayRC<F, R, C, RC>
+{
+    fn as_mut(&mut self) -> &mut [F; RC] {
+        &mut self.data
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> AsMut<[F]>
+    for FArrayRC<F, R, C, RC>
+{
+    fn as_mut(&mut self) -> &mut [F] {
+        &mut self.data
+    }
+}
+
+//ip Add, Sub for FArrayRC
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::Add<Self>
+    for FArrayRC<F, R, C, RC>
+{
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        vector::add(self.data, &other.data, F::one()).into()
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::AddAssign<Self>
+    for FArrayRC<F, R, C, RC>
+{
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::Sub<Self>
+    for FArrayRC<F, R, C, RC>
+{
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        vector::sub(self.data, &other.data, F::one()).into()
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::SubAssign<Self>
+    for FArrayRC<F, R, C, RC>
+{
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+//ip Mul, Div by F for FArrayRC
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::Mul<F>
+    for FArrayRC<F, R, C, RC>
+{
+    type Output = Self;
+    fn mul(self, other: F) -> Self {
+        vector::scale(self.data, other).into()
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::MulAssign<F>
+    for FArrayRC<F, R, C, RC>
+{
+    fn mul_assign(&mut self, other: F) {
+        *self = *self * other;
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::Div<F>
+    for FArrayRC<F, R, C, RC>
+{
+    type Output = Self;
+    fn div(self, other: F) -> Self {
+        vector::reduce(self.data, other).into()
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::DivAssign<F>
+    for FArrayRC<F, R, C, RC>
+{
+    fn div_assign(&mut self, other: F) {
+        *self = *self / other;
+    }
+}
+
+//ip Neg for FArrayRC
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::Neg
+    for FArrayRC<F, R, C, RC>
+{
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.data.map(|f| -f).into()
+    }
+}
+
+//ip Mul<FArray> for FArrayRC
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::ops::Mul<FArray<F, C>>
+    for FArrayRC<F, R, C, RC>
+{
+    type Output = FArray<F, R>;
+    fn mul(self, other: FArray<F, C>) -> FArray<F, R> {
+        self.transform_vec(other.as_ref()).into()
+    }
+}
+
+//mi mul_shape!
+/// Implement Mul for an R-by-X matrix by an X-by-C matrix for all of
+/// the listed values of R, X and C
+macro_rules! mul_shape {
+    { [$($r:literal)*], $xs:tt, $cs:tt } => {
+        $( mul_shape!{ @r $r, $xs, $cs } )*
+    };
+    { @r $r:literal, [$($x:literal)*], $cs:tt } => {
+        $( mul_shape!{ @x $r, $x, $cs } )*
+    };
+    { @x $r:literal, $x:literal, [$($c:literal)*] } => {
+        $(
+            impl<F: Float> std::ops::Mul<FArrayRC<F, $x, $c, { $x * $c }>>
+                for FArrayRC<F, $r, $x, { $r * $x }>
+            {
+                type Output = FArrayRC<F, $r, $c, { $r * $c }>;
+                fn mul(self, other: FArrayRC<F, $x, $c, { $x * $c }>) -> Self::Output {
+                    self.multiply(&other)
+                }
+            }
+        )*
+    };
+}
+
+//ip Mul<FArrayRC> for FArrayRC
+mul_shape! { [1 2 3 4], [1 2 3 4], [1 2 3 4] }
+
+//ip Default for FArrayRC
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::default::Default
+    for FArrayRC<F, R, C, RC>
+{
+    fn default() -> Self {
+        Self {
+            data: vector::zero(),
+        }
+    }
+}
+
+//ip Display for FArrayRC
+impl<F: Float, const R: usize, const C: usize, const RC: usize> std::fmt::Display
+    for FArrayRC<F, R, C, RC>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        matrix::fmt::<F, C>(f, &self.data)
+    }
+}
+
+//ip Serialize for FArrayRC
+impl<F: Float + serde::Serialize, const R: usize, const C: usize, const RC: usize> Serialize
+    for FArrayRC<F, R, C, RC>
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeTuple;
+        let mut seq = serializer.serialize_tuple(RC)?;
+        for e in self.data.iter() {
+            seq.serialize_element(e)?;
+        }
+        seq.end()
+    }
+}
+
+//ip Deserialize for FArrayRC
+impl<'de, F: Float + serde::Deserialize<'de>, const R: usize, const C: usize, const RC: usize>
+    Deserialize<'de> for FArrayRC<F, R, C, RC>
+{
+    fn deserialize<DE>(deserializer: DE) -> Result<Self, DE::Error>
+    where
+        DE: serde::Deserializer<'de>,
+    {
+        let array = Vec::<F>::deserialize(deserializer)?;
+        if array.len() != RC {
+            return Err(serde::de::Error::invalid_length(
+                array.len(),
+                &"<RC> floats",
+            ));
+        }
+        let mut data = Self::default();
+        for (i, d) in array.into_iter().enumerate() {
+            data[i] = d;
+        }
+        Ok(data)
+    }
+}
+
+//ip From<[F;RC]> for FArrayRC, and From<FArrayRC> for [F;RC]
+impl<F: Float, const R: usize, const C: usize, const RC: usize> From<[F; RC]>
+    for FArrayRC<F, R, C, RC>
+{
+    fn from(data: [F; RC]) -> Self {
+        Self { data }
+    }
+}
+impl<F: Float, const R: usize, const C: usize, const RC: usize> From<FArrayRC<F, R, C, RC>>
+    for [F; RC]
+{
+    fn from(m: FArrayRC<F, R, C, RC>) -> [F; RC] {
+        m.data
+    }
+}
+
+//ip From<FArray2> for FArrayRC, and From<FArrayRC> for FArray2
+impl<F: Float, const D: usize, const D2: usize> From<FArray2<F, D, D2>> for FArrayRC<F, D, D, D2> {
+    fn from(m: FArray2<F, D, D2>) -> Self {
+        Self { data: *m.as_ref() }
+    }
+}
+impl<F: Float, const D: usize, const D2: usize> From<FArrayRC<F, D, D, D2>> for FArray2<F, D, D2> {
+    fn from(m: FArrayRC<F, D, D, D2>) -> Self {
+        m.data.into()
+    }
+}
+
+//ip Matrix for FArrayRC
+impl<F: Float, const R: usize, const C: usize, const RC: usize> Matrix<F, R, C, RC>
+    for FArrayRC<F, R, C, RC>
+{
+    type Transpose = FArrayRC<F, C, R, RC>;
+    fn from_array(data: [F; RC]) -> Self {
+        Self { data }
+    }
+    fn into_array(self) -> [F; RC] {
+        self.data
+    }
+    fn zero() -> Self {
+        Self {
+            data: vector::zero(),
+        }
+    }
+    fn is_zero(&self) -> bool {
+        vector::is_zero(&self.data)
+    }
+}
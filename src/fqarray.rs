@@ -1,8 +1,8 @@
 //a Imports
 use serde::{Deserialize, Serialize};
 
-use crate::{FArray, FArray2};
-use crate::{Float, QArray, Quaternion, SqMatrix, Transform, Vector};
+use crate::{matrix, quat, vector, FArray, FArray2};
+use crate::{Float, QArray, Quaternion, SingularMatrix, SqMatrix, Transform, Vector};
 
 //tp FQArrayTrans
 /// A transformation that is a translation . scaling . rotation
@@ -28,6 +28,127 @@ impl<F: Float + Serialize> std::default::Default for FQArrayTrans<F> {
     }
 }
 
+//ip FQArrayTrans
+impl<F: Float + Serialize> FQArrayTrans<F> {
+    //cp fit_points
+    /// Find the transformation that best maps the `src` points onto
+    /// the corresponding `dst` points, in the (weighted) least-squares
+    /// sense, returning it with the root-mean-square residual distance
+    ///
+    /// This is the Kabsch algorithm, with Umeyama's extension to find
+    /// the scale if `with_scale` is true; if it is false then the scale
+    /// is locked to 1 and the result is a rigid transformation. The
+    /// rotation is always a proper rotation, never a reflection.
+    ///
+    /// If `weights` is None then all points are equally weighted.
+    ///
+    /// None is returned if the total weight is not positive, or if a
+    /// scale is to be found but the `src` points all coincide.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` (or `weights`, if given) is not the same length
+    /// as `src`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_nd::{FArray, FQArrayTrans, QArray, Quaternion, Transform, Vector};
+    /// let q = QArray::of_axis_angle(&FArray::from_array([0., 0., 1.]), 0.5);
+    /// let t = FQArrayTrans::of_trs(FArray::from_array([1., 2., 3.]), q, 2.);
+    /// let src: Vec<FArray<f64, 3>> = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]
+    ///     .into_iter()
+    ///     .map(FArray::from_array)
+    ///     .collect();
+    /// let dst: Vec<FArray<f64, 3>> = src
+    ///     .iter()
+    ///     .map(|p| q.apply3(p) * 2. + t.translation())
+    ///     .collect();
+    /// let (fit, rms) = FQArrayTrans::fit_points(&src, &dst, None, true).unwrap();
+    /// assert!(rms < 1E-12);
+    /// assert!((fit.scale() - 2.).abs() < 1E-12);
+    /// assert!(fit.translation().distance(&t.translation()) < 1E-12);
+    /// ```
+    pub fn fit_points(
+        src: &[FArray<F, 3>],
+        dst: &[FArray<F, 3>],
+        weights: Option<&[F]>,
+        with_scale: bool,
+    ) -> Option<(Self, F)> {
+        assert_eq!(src.len(), dst.len(), "Point sets must be the same length");
+        if let Some(weights) = weights {
+            assert_eq!(src.len(), weights.len(), "Weights must match the points");
+        }
+        let weight = |i: usize| weights.map_or(F::one(), |w| w[i]);
+
+        let mut total = F::zero();
+        let mut src_mean = [F::zero(); 3];
+        let mut dst_mean = [F::zero(); 3];
+        for (i, (s, d)) in src.iter().zip(dst.iter()).enumerate() {
+            let w = weight(i);
+            total += w;
+            src_mean = vector::add(src_mean, s.as_ref(), w);
+            dst_mean = vector::add(dst_mean, d.as_ref(), w);
+        }
+        if total <= F::zero() {
+            return None;
+        }
+        let src_mean = vector::reduce(src_mean, total);
+        let dst_mean = vector::reduce(dst_mean, total);
+
+        // Cross-covariance of the centred points, dst . transpose(src),
+        // and the variance of the centred source points
+        let mut covariance = [F::zero(); 9];
+        let mut src_variance = F::zero();
+        for (i, (s, d)) in src.iter().zip(dst.iter()).enumerate() {
+            let w = weight(i);
+            let s = vector::sub(*s.as_ref(), &src_mean, F::one());
+            let d = vector::sub(*d.as_ref(), &dst_mean, F::one());
+            for r in 0..3 {
+                for c in 0..3 {
+                    covariance[r * 3 + c] += w * d[r] * s[c];
+                }
+            }
+            src_variance += w * vector::length_sq(&s);
+        }
+
+        // The rotation is U . diag(1, 1, +-1) . transpose(V), the sign
+        // making it a rotation rather than a reflection
+        let (mut u, sv, v) = matrix::svd3(&covariance);
+        let mut trace = sv[0] + sv[1] + sv[2];
+        if matrix::determinant3(&u) * matrix::determinant3(&v) < F::zero() {
+            u[2] = -u[2];
+            u[5] = -u[5];
+            u[8] = -u[8];
+            trace -= sv[2] + sv[2];
+        }
+        let rotation = matrix::multiply3(&u, &matrix::transpose::<F, 9, 3, 3>(v));
+
+        let scale = {
+            if with_scale {
+                if src_variance <= F::epsilon() * total {
+                    return None;
+                }
+                trace / src_variance
+            } else {
+                F::one()
+            }
+        };
+        let rotated_mean = matrix::transform_vec3(&rotation, &src_mean);
+        let translation = vector::sub(dst_mean, &rotated_mean, scale);
+        let quat = QArray::from_array(quat::of_rotation(&rotation));
+        let trans = Self::of_trs(translation.into(), quat, scale);
+
+        let mut residual = F::zero();
+        for (i, (s, d)) in src.iter().zip(dst.iter()).enumerate() {
+            let p = matrix::transform_vec3(&rotation, s.as_ref());
+            let p = vector::add(translation, &p, scale);
+            residual += weight(i) * vector::distance_sq(&p, d.as_ref());
+        }
+        Some((trans, (residual / total).sqrt()))
+    }
+}
+
 //ip Display for FQArrayTrans<F>
 impl<F> std::fmt::Display for FQArrayTrans<F>
 where
@@ -90,22 +211,32 @@ impl<F: Float + Serialize>
 
     //cp inverse
     /// Get a transformation that is the inverse of this
-    #[must_use]
+    ///
+    /// If the scale is (nearly) zero then this returns the identity
+    /// transformation; use [Transform::try_inverse] to detect this
     fn inverse(&self) -> Self {
+        self.try_inverse(F::epsilon()).unwrap_or_default()
+    }
+
+    //cp try_inverse
+    /// Get a transformation that is the inverse of this, or an error
+    /// if the magnitude of the scale is no more than `epsilon`
+    fn try_inverse(&self, epsilon: F) -> Result<Self, SingularMatrix<F>> {
         let scale = self.trans_scale[3];
-        if scale.abs() < F::epsilon() {
-            Self::default()
-        } else {
-            let scale = F::one() / scale;
-            let trans = FArray::from_array([
-                self.trans_scale[0],
-                self.trans_scale[1],
-                self.trans_scale[2],
-            ]);
-            let iquat = self.quat.conjugate();
-            let trans = -(iquat.apply3(&trans)) * scale;
-            Self::of_trs(trans, iquat, scale)
+        if scale.abs() <= epsilon {
+            return Err(SingularMatrix {
+                determinant: scale * scale * scale,
+            });
         }
+        let scale = F::one() / scale;
+        let trans = FArray::from_array([
+            self.trans_scale[0],
+            self.trans_scale[1],
+            self.trans_scale[2],
+        ]);
+        let iquat = self.quat.conjugate();
+        let trans = -(iquat.apply3(&trans)) * scale;
+        Ok(Self::of_trs(trans, iquat, scale))
     }
 
     //mp invert
@@ -120,9 +251,81 @@ impl<F: Float + Serialize>
         let mut m = FArray2::<F, 4, 16>::zero();
         self.quat.set_rotation4(&mut m);
         m *= self.trans_scale[3];
+        m[15] = F::one();
         m[3] = self.trans_scale[0];
         m[7] = self.trans_scale[1];
         m[11] = self.trans_scale[2];
         m
     }
 }
+
+//ip Mul<Self> for FQArrayTrans
+impl<F: Float + Serialize> std::ops::Mul<Self> for FQArrayTrans<F> {
+    type Output = Self;
+    /// Compose two transformations; the result applies `other` and
+    /// then `self`
+    fn mul(self, other: Self) -> Self {
+        let translation = self.transform_point(&other.translation());
+        Self::of_trs(
+            translation,
+            self.quat * other.quat,
+            self.scale() * other.scale(),
+        )
+    }
+}
+impl<F: Float + Serialize> std::ops::MulAssign<Self> for FQArrayTrans<F> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+//ip Mul<QArray> for FQArrayTrans
+impl<F: Float + Serialize> std::ops::Mul<QArray<F, FArray<F, 3>, FArray<F, 4>>>
+    for FQArrayTrans<F>
+{
+    type Output = Self;
+    /// Apply a rotation before the transformation
+    fn mul(mut self, other: QArray<F, FArray<F, 3>, FArray<F, 4>>) -> Self {
+        self.quat *= other;
+        self
+    }
+}
+impl<F: Float + Serialize> std::ops::MulAssign<QArray<F, FArray<F, 3>, FArray<F, 4>>>
+    for FQArrayTrans<F>
+{
+    fn mul_assign(&mut self, other: QArray<F, FArray<F, 3>, FArray<F, 4>>) {
+        *self = *self * other;
+    }
+}
+
+//ip Mul<F> for FQArrayTrans
+impl<F: Float + Serialize> std::ops::Mul<F> for FQArrayTrans<F> {
+    type Output = Self;
+    /// Apply a scaling before the transformation
+    fn mul(mut self, other: F) -> Self {
+        self.trans_scale[3] *= other;
+        self
+    }
+}
+impl<F: Float + Serialize> std::ops::MulAssign<F> for FQArrayTrans<F> {
+    fn mul_assign(&mut self, other: F) {
+        *self = *self * other;
+    }
+}
+
+//ip Add<FArray> for FQArrayTrans
+impl<F: Float + Serialize> std::ops::Add<FArray<F, 3>> for FQArrayTrans<F> {
+    type Output = Self;
+    /// Apply a translation after the transformation
+    fn add(mut self, other: FArray<F, 3>) -> Self {
+        for i in 0..3 {
+            self.trans_scale[i] += other[i];
+        }
+        self
+    }
+}
+impl<F: Float + Serialize> std::ops::AddAssign<FArray<F, 3>> for FQArrayTrans<F> {
+    fn add_assign(&mut self, other: FArray<F, 3>) {
+        *self = *self + other;
+    }
+}
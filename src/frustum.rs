@@ -0,0 +1,238 @@
+//a Imports
+use crate::{vector, DepthRange, Float, SqMatrix4, Vector};
+
+//a Containment
+//tp Containment
+/// The result of testing whether an object lies within a region such
+/// as a [Frustum]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Containment {
+    /// The object lies wholly outside the region
+    Outside,
+    /// The object may cross the boundary of the region
+    Intersecting,
+    /// The object lies wholly inside the region
+    Inside,
+}
+
+//a Frustum
+//tp Frustum
+/// A view frustum, as the six planes that bound it, extracted from a
+/// view-projection matrix using the method of Gribb and Hartmann
+///
+/// Each plane is a 4-vector (a, b, c, d) with (a, b, c) the unit
+/// normal pointing into the frustum, so that a point p is inside the
+/// plane if a.px + b.py + c.pz + d >= 0. The planes are in the order
+/// left, right, bottom, top, near, far.
+///
+/// The frustum is generic over the vector types, so that it may be
+/// used with the types of any [crate::Geometry3D].
+///
+/// The sphere and box tests are conservative: an object that is
+/// reported as [Containment::Outside] is certainly outside, but an
+/// object close to an edge or corner of the frustum that is reported
+/// as [Containment::Intersecting] may in fact be outside.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{ClipSpace, Containment, FArray, FArray2, Frustum, SqMatrix4, Vector};
+/// type Vec3 = FArray<f64, 3>;
+/// let clip = ClipSpace::opengl();
+/// let proj: FArray2<f64, 4, 16> = SqMatrix4::perspective_fov(clip, 1.0, 1.0, 1.0, 100.0);
+/// let frustum = Frustum::of_matrix(&proj, clip.depth);
+/// let ahead = Vec3::from_array([0., 0., -10.]);
+/// let behind = Vec3::from_array([0., 0., 10.]);
+/// assert!(frustum.contains_point(&ahead));
+/// assert_eq!(frustum.test_sphere(&behind, 1.0), Containment::Outside);
+/// assert_eq!(frustum.test_sphere(&ahead, 1.0), Containment::Inside);
+/// assert_eq!(frustum.test_sphere(&ahead, 20.0), Containment::Intersecting);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum<F, V3, V4>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+    V4: Vector<F, 4>,
+{
+    /// The left, right, bottom, top, near and far planes
+    planes: [V4; 6],
+    /// The corners of the near plane then the far plane
+    corners: [V3; 8],
+    /// Marker for the float type
+    phantom: std::marker::PhantomData<F>,
+}
+
+//ip Frustum
+impl<F, V3, V4> Frustum<F, V3, V4>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+    V4: Vector<F, 4>,
+{
+    //cp of_matrix
+    /// Create the frustum of a view-projection (or
+    /// model-view-projection) matrix, for its depth range
+    ///
+    /// If the far plane is at infinity (as with
+    /// [crate::matrix::infinite_perspective4]) then it excludes no
+    /// points, and the corners on it are not finite
+    pub fn of_matrix<M4: SqMatrix4<F, V3, V4>>(m: &M4, depth: DepthRange) -> Self {
+        let m: &[F; 16] = m.as_ref();
+        let row = |r: usize| [m[r * 4], m[r * 4 + 1], m[r * 4 + 2], m[r * 4 + 3]];
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+        let one = F::one();
+        let (near, far) = match depth {
+            DepthRange::NegOneToOne => (vector::add(r3, &r2, one), vector::sub(r3, &r2, one)),
+            DepthRange::ZeroToOne => (r2, vector::sub(r3, &r2, one)),
+            DepthRange::OneToZero => (vector::sub(r3, &r2, one), r2),
+        };
+        let planes = [
+            vector::add(r3, &r0, one),
+            vector::sub(r3, &r0, one),
+            vector::add(r3, &r1, one),
+            vector::sub(r3, &r1, one),
+            near,
+            far,
+        ]
+        .map(|p| V4::from_array(Self::normalize_plane(p)));
+        let corner = |z: usize, y: usize, x: usize| {
+            V3::from_array(Self::intersect_planes(
+                planes[x].as_ref(),
+                planes[2 + y].as_ref(),
+                planes[4 + z].as_ref(),
+            ))
+        };
+        let corners = [
+            corner(0, 0, 0),
+            corner(0, 0, 1),
+            corner(0, 1, 0),
+            corner(0, 1, 1),
+            corner(1, 0, 0),
+            corner(1, 0, 1),
+            corner(1, 1, 0),
+            corner(1, 1, 1),
+        ];
+        Self {
+            planes,
+            corners,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    //fi normalize_plane
+    /// Scale a plane so that its normal is a unit vector; a plane
+    /// with no normal (such as a far plane at infinity) is made to
+    /// include all points
+    fn normalize_plane(p: [F; 4]) -> [F; 4] {
+        let l = vector::length(&[p[0], p[1], p[2]]);
+        if l <= F::epsilon() * p[3].abs() || l == F::zero() {
+            [F::zero(), F::zero(), F::zero(), F::one()]
+        } else {
+            vector::reduce(p, l)
+        }
+    }
+
+    //fi intersect_planes
+    /// Find the point at which three planes intersect
+    fn intersect_planes(a: &[F; 4], b: &[F; 4], c: &[F; 4]) -> [F; 3] {
+        let na = [a[0], a[1], a[2]];
+        let nb = [b[0], b[1], b[2]];
+        let nc = [c[0], c[1], c[2]];
+        let bc = vector::cross_product3(&nb, &nc);
+        let ca = vector::cross_product3(&nc, &na);
+        let ab = vector::cross_product3(&na, &nb);
+        let p = vector::add(vector::scale(bc, a[3]), &ca, b[3]);
+        let p = vector::add(p, &ab, c[3]);
+        vector::scale(p, -F::one() / vector::dot(&na, &bc))
+    }
+
+    //fi distance
+    /// Get the signed distance of a point from a plane, positive on
+    /// the inside
+    #[inline]
+    fn distance(plane: &V4, p: &V3) -> F {
+        plane[0] * p[0] + plane[1] * p[1] + plane[2] * p[2] + plane[3]
+    }
+
+    //mp planes
+    /// Get the left, right, bottom, top, near and far planes of the
+    /// frustum, with normals pointing inwards
+    pub fn planes(&self) -> &[V4; 6] {
+        &self.planes
+    }
+
+    //mp corners
+    /// Get the eight corners of the frustum; the four corners of the
+    /// near plane followed by those of the far plane, each as
+    /// left-bottom, right-bottom, left-top and right-top
+    ///
+    /// These are useful for fitting the frustum (or a slice of it, for
+    /// a shadow cascade) in the space of a light
+    pub fn corners(&self) -> &[V3; 8] {
+        &self.corners
+    }
+
+    //mp contains_point
+    /// Return true if a point is within the frustum (or on its
+    /// boundary)
+    pub fn contains_point(&self, p: &V3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| Self::distance(plane, p) >= F::zero())
+    }
+
+    //mp test_point
+    /// Test whether a point is inside the frustum (or on its boundary)
+    /// or outside it
+    pub fn test_point(&self, p: &V3) -> Containment {
+        if self.contains_point(p) {
+            Containment::Inside
+        } else {
+            Containment::Outside
+        }
+    }
+
+    //mp test_sphere
+    /// Test whether a sphere is inside, outside, or intersecting the
+    /// frustum
+    pub fn test_sphere(&self, centre: &V3, radius: F) -> Containment {
+        let mut result = Containment::Inside;
+        for plane in &self.planes {
+            let d = Self::distance(plane, centre);
+            if d < -radius {
+                return Containment::Outside;
+            }
+            if d < radius {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+
+    //mp test_aabb
+    /// Test whether an axis-aligned box, given by its minimum and
+    /// maximum corners, is inside, outside, or intersecting the
+    /// frustum
+    pub fn test_aabb(&self, min: &V3, max: &V3) -> Containment {
+        let mut result = Containment::Inside;
+        for plane in &self.planes {
+            // The corners furthest along and against the plane normal
+            let mut p_vertex = *max;
+            let mut n_vertex = *min;
+            for i in 0..3 {
+                if plane[i] < F::zero() {
+                    p_vertex[i] = min[i];
+                    n_vertex[i] = max[i];
+                }
+            }
+            if Self::distance(plane, &p_vertex) < F::zero() {
+                return Containment::Outside;
+            }
+            if Self::distance(plane, &n_vertex) < F::zero() {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,306 @@
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use crate::{Float, Vector};
+
+//a Point
+//tp Point
+/// A position in two dimensions
+///
+/// This differs from a vector in that the difference of two points is
+/// a vector, and a vector may be added to (or subtracted from) a
+/// point to give another point; points may not be added together.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Point<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    /// The position as a vector from the origin
+    v: V2,
+    /// Marker for the float type
+    #[serde(skip)]
+    phantom: std::marker::PhantomData<F>,
+}
+
+//ip Display for Point
+impl<F, V2> std::fmt::Display for Point<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.v, f)
+    }
+}
+
+//ip From<V2> for Point
+impl<F, V2> From<V2> for Point<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    fn from(v: V2) -> Self {
+        Self::of_vec(v)
+    }
+}
+
+//ip Point
+impl<F, V2> Point<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    //cp new
+    /// Create a point from its coordinates
+    pub fn new(x: F, y: F) -> Self {
+        Self::of_vec(V2::from_array([x, y]))
+    }
+
+    //cp origin
+    /// Create a point at the origin
+    pub fn origin() -> Self {
+        Self::of_vec(V2::zero())
+    }
+
+    //cp of_vec
+    /// Create a point from the vector from the origin to it
+    pub fn of_vec(v: V2) -> Self {
+        Self {
+            v,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    //mp as_vec
+    /// Get the vector from the origin to the point
+    pub fn as_vec(&self) -> V2 {
+        self.v
+    }
+
+    //mp x
+    /// Get the X coordinate of the point
+    pub fn x(&self) -> F {
+        self.v[0]
+    }
+
+    //mp y
+    /// Get the Y coordinate of the point
+    pub fn y(&self) -> F {
+        self.v[1]
+    }
+
+    //mp is_origin
+    /// Return true if the point is at the origin
+    pub fn is_origin(&self) -> bool {
+        self.v.is_zero()
+    }
+
+    //mp distance
+    /// Get the distance between this point and another
+    pub fn distance(&self, other: &Self) -> F {
+        self.v.distance(&other.v)
+    }
+
+    //mp mix
+    /// Get the point a fraction `t` of the way from this point to
+    /// another
+    #[must_use]
+    pub fn mix(&self, other: &Self, t: F) -> Self {
+        Self::of_vec(self.v.mix(&other.v, t))
+    }
+
+    //mp rotate_around
+    /// Get this point rotated anticlockwise around a centre by an
+    /// angle in radians
+    #[must_use]
+    pub fn rotate_around(&self, centre: &Self, angle: F) -> Self {
+        Self::of_vec(self.v.rotate_around(&centre.v, angle, 0, 1))
+    }
+}
+
+//ip Add<V2> for Point
+impl<F, V2> std::ops::Add<V2> for Point<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    type Output = Self;
+    fn add(self, v: V2) -> Self {
+        Self::of_vec(self.v + v)
+    }
+}
+
+//ip AddAssign<V2> for Point
+impl<F, V2> std::ops::AddAssign<V2> for Point<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    fn add_assign(&mut self, v: V2) {
+        self.v = self.v + v;
+    }
+}
+
+//ip Sub<V2> for Point
+impl<F, V2> std::ops::Sub<V2> for Point<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    type Output = Self;
+    fn sub(self, v: V2) -> Self {
+        Self::of_vec(self.v - v)
+    }
+}
+
+//ip SubAssign<V2> for Point
+impl<F, V2> std::ops::SubAssign<V2> for Point<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    fn sub_assign(&mut self, v: V2) {
+        self.v = self.v - v;
+    }
+}
+
+//ip Sub<Point> for Point
+impl<F, V2> std::ops::Sub<Self> for Point<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    type Output = V2;
+    fn sub(self, other: Self) -> V2 {
+        self.v - other.v
+    }
+}
+
+//a Range
+//tp Range
+/// A closed range of values, from a minimum to a maximum
+///
+/// The range is never empty; it always contains at least one value.
+/// Operations that may produce an empty range, such as
+/// [Range::intersection], return an [Option].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Range<F: Float> {
+    /// The minimum of the range
+    min: F,
+    /// The maximum of the range
+    max: F,
+}
+
+//ip Display for Range
+impl<F: Float> std::fmt::Display for Range<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "[{},{}]", self.min, self.max)
+    }
+}
+
+//ip Range
+impl<F: Float> Range<F> {
+    //cp new
+    /// Create a range between two values, in any order
+    pub fn new(a: F, b: F) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    //cp of_value
+    /// Create a range containing a single value
+    pub fn of_value(v: F) -> Self {
+        Self { min: v, max: v }
+    }
+
+    //mp min
+    /// Get the minimum of the range
+    pub fn min(&self) -> F {
+        self.min
+    }
+
+    //mp max
+    /// Get the maximum of the range
+    pub fn max(&self) -> F {
+        self.max
+    }
+
+    //mp size
+    /// Get the size of the range (its maximum minus its minimum)
+    pub fn size(&self) -> F {
+        self.max - self.min
+    }
+
+    //mp center
+    /// Get the centre of the range
+    pub fn center(&self) -> F {
+        (self.min + self.max) * F::frac(1, 2)
+    }
+
+    //mp at
+    /// Get the value a fraction `t` of the way from the minimum to the
+    /// maximum of the range
+    pub fn at(&self, t: F) -> F {
+        self.min + self.size() * t
+    }
+
+    //mp contains
+    /// Return true if a value is within the range (or at one of its
+    /// ends)
+    pub fn contains(&self, v: F) -> bool {
+        self.min <= v && v <= self.max
+    }
+
+    //mp include
+    /// Expand the range, if required, to include a value
+    pub fn include(&mut self, v: F) {
+        self.min = self.min.min(v);
+        self.max = self.max.max(v);
+    }
+
+    //mp union
+    /// Get the smallest range that contains this and another range
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    //mp intersection
+    /// Get the range that is the overlap of this and another range, or
+    /// None if they do not overlap
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = self.min.max(other.min);
+        let max = self.max.min(other.max);
+        if min > max {
+            None
+        } else {
+            Some(Self { min, max })
+        }
+    }
+
+    //mp intersects
+    /// Return true if this range overlaps (or touches) another range
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min <= other.max && other.min <= self.max
+    }
+
+    //mp expand
+    /// Get the range with each end moved outwards by a distance; if
+    /// the distance is negative then the range shrinks, but by no more
+    /// than to its centre
+    #[must_use]
+    pub fn expand(&self, by: F) -> Self {
+        let centre = self.center();
+        Self {
+            min: (self.min - by).min(centre),
+            max: (self.max + by).max(centre),
+        }
+    }
+}
@@ -0,0 +1,494 @@
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use super::{Bezier, Rectangle, Transform};
+use crate::{Float, SqMatrix, Vector};
+
+//a Winding, FillRule
+//tp Winding
+/// The order of the vertices of a [Polygon]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Winding {
+    /// The vertices are anticlockwise (with Y up), and the signed area
+    /// is positive
+    Anticlockwise,
+    /// The vertices are clockwise (with Y up), and the signed area is
+    /// negative
+    Clockwise,
+}
+
+//tp FillRule
+/// The rule for whether a point is inside a [Polygon] whose edges may
+/// cross, as used by SVG and PostScript
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    /// A point is inside if the polygon winds around it a nonzero
+    /// number of times
+    #[default]
+    NonZero,
+    /// A point is inside if a ray from it crosses the edges of the
+    /// polygon an odd number of times
+    EvenOdd,
+}
+
+//a Polygon
+//tp Polygon
+/// A closed polygon in two dimensions, given by its vertices in order
+///
+/// The last vertex is joined to the first to close the polygon; the
+/// vertices are not repeated.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::geo2d::{FillRule, Polygon};
+/// use geo_nd::{FArray, Vector};
+/// type Vec2 = FArray<f64, 2>;
+/// let square: Polygon<f64, Vec2> = Polygon::regular(&Vec2::zero(), 4, 2.0_f64.sqrt(), 0.);
+/// assert_eq!(square.len(), 4);
+/// assert!((square.perimeter() - 8.).abs() < 1E-12);
+/// let bounds = square.bounds().unwrap();
+/// assert!((bounds.width() - 2.0_f64.sqrt() * 2.).abs() < 1E-12);
+/// assert!((square.area() - 4.).abs() < 1E-12);
+/// assert!(square.is_convex());
+/// assert!(square.contains(&Vec2::zero(), FillRule::NonZero));
+/// assert_eq!(square.triangulate().len(), 2);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Polygon<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    /// The vertices of the polygon
+    vertices: Vec<V2>,
+    /// Marker for the float type
+    #[serde(skip)]
+    phantom: std::marker::PhantomData<F>,
+}
+
+//ip Display for Polygon
+impl<F, V2> std::fmt::Display for Polygon<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Polygon[")?;
+        for (i, v) in self.vertices.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{v}")?;
+        }
+        write!(f, "]")
+    }
+}
+
+//ip Polygon
+impl<F, V2> Polygon<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    //cp new
+    /// Create a polygon from its vertices
+    pub fn new(vertices: Vec<V2>) -> Self {
+        Self {
+            vertices,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    //cp regular
+    /// Create a regular polygon with a number of sides, whose vertices
+    /// are at a radius from its centre, anticlockwise from the first
+    /// which is at an angle in radians from the X axis
+    pub fn regular(centre: &V2, sides: usize, radius: F, rotation: F) -> Self {
+        let step = F::tau() / F::int(sides as isize);
+        Self::new(
+            (0..sides)
+                .map(|i| Self::at_angle(centre, radius, rotation + step * F::int(i as isize)))
+                .collect(),
+        )
+    }
+
+    //cp star
+    /// Create a star with a number of points, whose vertices
+    /// alternate between an outer and an inner radius from its centre,
+    /// anticlockwise from the first point which is at an angle in
+    /// radians from the X axis
+    pub fn star(centre: &V2, points: usize, outer: F, inner: F, rotation: F) -> Self {
+        let step = F::pi() / F::int(points as isize);
+        Self::new(
+            (0..2 * points)
+                .map(|i| {
+                    let radius = if i % 2 == 0 { outer } else { inner };
+                    Self::at_angle(centre, radius, rotation + step * F::int(i as isize))
+                })
+                .collect(),
+        )
+    }
+
+    //cp of_rectangle
+    /// Create a polygon of the corners of a rectangle, anticlockwise
+    pub fn of_rectangle(r: &Rectangle<F, V2>) -> Self {
+        Self::new(r.corners().to_vec())
+    }
+
+    //fi at_angle
+    fn at_angle(centre: &V2, radius: F, angle: F) -> V2 {
+        let (s, c) = angle.sin_cos();
+        *centre + V2::from_array([c, s]) * radius
+    }
+
+    //mp vertices
+    /// Get the vertices of the polygon
+    pub fn vertices(&self) -> &[V2] {
+        &self.vertices
+    }
+
+    //mp len
+    /// Get the number of vertices of the polygon
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    //mp is_empty
+    /// Return true if the polygon has no vertices
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    //mp edges
+    /// Iterate over the edges of the polygon, as pairs of vertices,
+    /// including that from the last vertex to the first
+    pub fn edges(&self) -> impl Iterator<Item = (V2, V2)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    //mp perimeter
+    /// Get the length of the perimeter of the polygon
+    pub fn perimeter(&self) -> F {
+        self.edges()
+            .fold(F::zero(), |acc, (a, b)| acc + a.distance(&b))
+    }
+
+    //mp bounds
+    /// Get the bounds of the polygon, or None if it has no vertices
+    pub fn bounds(&self) -> Option<Rectangle<F, V2>> {
+        Rectangle::from_points(self.vertices.iter())
+    }
+
+    //mp as_beziers
+    /// Get the edges of the polygon as straight [Bezier]s
+    pub fn as_beziers(&self) -> Vec<Bezier<F, V2, 2>> {
+        self.edges().map(|(a, b)| Bezier::line(&a, &b)).collect()
+    }
+
+    //mp translate
+    /// Get the polygon moved by a vector
+    #[must_use]
+    pub fn translate(&self, by: &V2) -> Self {
+        Self::new(self.vertices.iter().map(|v| *v + *by).collect())
+    }
+
+    //mp transform
+    /// Get the polygon transformed by an affine [Transform]
+    #[must_use]
+    pub fn transform<M2>(&self, t: &Transform<F, V2, M2>) -> Self
+    where
+        M2: SqMatrix<V2, F, 2, 4>,
+    {
+        Self::new(self.vertices.iter().map(|v| t.apply(v)).collect())
+    }
+
+    //mp reversed
+    /// Get the polygon with its vertices in the opposite order, and
+    /// hence the opposite winding
+    #[must_use]
+    pub fn reversed(&self) -> Self {
+        Self::new(self.vertices.iter().rev().copied().collect())
+    }
+
+    //fi cross
+    /// Get the cross product of the vectors from `o` to `a` and to `b`;
+    /// this is positive if `o`, `a` and `b` turn anticlockwise
+    fn cross(o: &V2, a: &V2, b: &V2) -> F {
+        (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+    }
+
+    //mp signed_area
+    /// Get the signed area of the polygon, using the shoelace formula;
+    /// this is positive if the vertices are anticlockwise, and negative
+    /// if they are clockwise
+    ///
+    /// For a polygon whose edges cross, regions are counted with the
+    /// number of times the polygon winds around them
+    pub fn signed_area(&self) -> F {
+        self.edges()
+            .fold(F::zero(), |acc, (a, b)| acc + a[0] * b[1] - a[1] * b[0])
+            * F::frac(1, 2)
+    }
+
+    //mp area
+    /// Get the area of the polygon, ignoring its winding
+    pub fn area(&self) -> F {
+        self.signed_area().abs()
+    }
+
+    //mp centroid
+    /// Get the centroid (centre of mass) of the area of the polygon, or
+    /// None if its area is zero
+    pub fn centroid(&self) -> Option<V2> {
+        let area = self.signed_area();
+        if area == F::zero() {
+            return None;
+        }
+        let sum = self.edges().fold(V2::zero(), |acc, (a, b)| {
+            acc + (a + b) * (a[0] * b[1] - a[1] * b[0])
+        });
+        Some(sum / (F::int(6) * area))
+    }
+
+    //mp winding
+    /// Get the winding order of the vertices of the polygon, from the
+    /// sign of its area, or None if the area is zero
+    pub fn winding(&self) -> Option<Winding> {
+        let area = self.signed_area();
+        if area > F::zero() {
+            Some(Winding::Anticlockwise)
+        } else if area < F::zero() {
+            Some(Winding::Clockwise)
+        } else {
+            None
+        }
+    }
+
+    //mp winding_number
+    /// Get the number of times the polygon winds anticlockwise around
+    /// a point; this is negative if it winds clockwise
+    ///
+    /// Points on the boundary of the polygon may be counted as inside
+    /// or outside
+    pub fn winding_number(&self, p: &V2) -> isize {
+        self.edges().fold(0, |acc, (a, b)| {
+            if a[1] <= p[1] {
+                if b[1] > p[1] && Self::cross(&a, &b, p) > F::zero() {
+                    return acc + 1;
+                }
+            } else if b[1] <= p[1] && Self::cross(&a, &b, p) < F::zero() {
+                return acc - 1;
+            }
+            acc
+        })
+    }
+
+    //mp contains
+    /// Return true if a point is inside the polygon, using a fill rule
+    /// for polygons whose edges cross
+    ///
+    /// Points on the boundary of the polygon may be counted as inside
+    /// or outside
+    pub fn contains(&self, p: &V2, rule: FillRule) -> bool {
+        match rule {
+            FillRule::NonZero => self.winding_number(p) != 0,
+            FillRule::EvenOdd => {
+                let crossings = self
+                    .edges()
+                    .filter(|(a, b)| {
+                        (a[1] > p[1]) != (b[1] > p[1])
+                            && p[0] < a[0] + (b[0] - a[0]) * (p[1] - a[1]) / (b[1] - a[1])
+                    })
+                    .count();
+                crossings % 2 == 1
+            }
+        }
+    }
+
+    //mp is_convex
+    /// Return true if the polygon is convex, with its vertices in either
+    /// winding
+    ///
+    /// Consecutive collinear edges are permitted, but the polygon must
+    /// turn in the same direction at every other vertex and only wind
+    /// once around its interior; polygons with fewer than three
+    /// vertices, or with zero area, are not convex
+    pub fn is_convex(&self) -> bool {
+        let n = self.vertices.len();
+        if n < 3 {
+            return false;
+        }
+        let mut sign = F::zero();
+        let mut turning = F::zero();
+        for i in 0..n {
+            let a = self.vertices[i];
+            let b = self.vertices[(i + 1) % n];
+            let c = self.vertices[(i + 2) % n];
+            let (ab, bc) = (b - a, c - b);
+            let cross = ab[0] * bc[1] - ab[1] * bc[0];
+            if cross != F::zero() {
+                if sign * cross < F::zero() {
+                    return false;
+                }
+                sign = cross;
+            }
+            turning += cross.atan2(ab.dot(&bc));
+        }
+        // The total turning is a multiple of a full turn; a convex
+        // polygon turns exactly once
+        sign != F::zero() && (turning.abs() - F::tau()).abs() < F::pi()
+    }
+
+    //mp triangulate
+    /// Triangulate the polygon by ear clipping, returning the indices
+    /// of the vertices of each triangle, which have the same winding as
+    /// the polygon
+    ///
+    /// A simple polygon (whose edges do not cross) with n vertices
+    /// gives n - 2 triangles, less any that are degenerate. Polygons
+    /// with crossing edges still give a set of triangles, but these
+    /// may not cover the polygon.
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        let mut triangles = Vec::new();
+        let mut remaining: Vec<usize> = (0..self.vertices.len()).collect();
+        let sign = if self.signed_area() < F::zero() {
+            -F::one()
+        } else {
+            F::one()
+        };
+        while remaining.len() > 3 {
+            let n = remaining.len();
+            let corner = |i: usize| {
+                [
+                    remaining[(i + n - 1) % n],
+                    remaining[i],
+                    remaining[(i + 1) % n],
+                ]
+            };
+            let ear = (0..n).find(|i| self.is_ear(&corner(*i), &remaining, sign));
+            let i = match ear {
+                Some(i) => i,
+                None => {
+                    // No ears remain, as the polygon is degenerate or
+                    // its edges cross; drop a vertex where it does not
+                    // turn, if there is one, else clip the first corner
+                    if let Some(i) = (0..n).find(|i| {
+                        let [a, b, c] = corner(*i);
+                        let v = &self.vertices;
+                        Self::cross(&v[a], &v[b], &v[c]) == F::zero()
+                    }) {
+                        remaining.remove(i);
+                        continue;
+                    }
+                    0
+                }
+            };
+            triangles.push(corner(i));
+            remaining.remove(i);
+        }
+        if remaining.len() == 3 {
+            let [a, b, c] = [remaining[0], remaining[1], remaining[2]];
+            let v = &self.vertices;
+            if Self::cross(&v[a], &v[b], &v[c]) != F::zero() {
+                triangles.push([a, b, c]);
+            }
+        }
+        triangles
+    }
+
+    //fi is_ear
+    /// Return true if a corner of the remaining polygon turns in the
+    /// direction of its winding and contains none of its other vertices
+    fn is_ear(&self, corner: &[usize; 3], remaining: &[usize], sign: F) -> bool {
+        let v = &self.vertices;
+        let [a, b, c] = corner.map(|i| v[i]);
+        if Self::cross(&a, &b, &c) * sign <= F::zero() {
+            return false;
+        }
+        !remaining.iter().any(|i| {
+            let p = &v[*i];
+            if corner.contains(i) || [a, b, c].iter().any(|q| q.distance_sq(p) == F::zero()) {
+                return false;
+            }
+            Self::cross(&a, &b, p) * sign >= F::zero()
+                && Self::cross(&b, &c, p) * sign >= F::zero()
+                && Self::cross(&c, &a, p) * sign >= F::zero()
+        })
+    }
+
+    //mp simplify
+    /// Simplify the polygon using the Ramer-Douglas-Peucker algorithm,
+    /// removing vertices that are within a tolerance of the edges of
+    /// the simplified polygon
+    ///
+    /// The first vertex, and the vertex furthest from it, are always
+    /// kept
+    #[must_use]
+    pub fn simplify(&self, tolerance: F) -> Self {
+        let n = self.vertices.len();
+        if n < 3 {
+            return self.clone();
+        }
+        let first = self.vertices[0];
+        let far = (1..n)
+            .max_by(|a, b| {
+                let da = self.vertices[*a].distance_sq(&first);
+                let db = self.vertices[*b].distance_sq(&first);
+                da.partial_cmp(&db).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap();
+        let mut keep = vec![false; n];
+        keep[0] = true;
+        keep[far] = true;
+        self.rdp(0, far, tolerance, &mut keep);
+        self.rdp(far, n, tolerance, &mut keep);
+        Self::new(
+            self.vertices
+                .iter()
+                .zip(keep)
+                .filter_map(|(v, k)| k.then_some(*v))
+                .collect(),
+        )
+    }
+
+    //fi rdp
+    /// Mark the vertices to keep between two vertices, by splitting at
+    /// the vertex furthest from the line between them if it is beyond
+    /// the tolerance; the end index may be the number of vertices, for
+    /// the first vertex
+    fn rdp(&self, start: usize, end: usize, tolerance: F, keep: &mut [bool]) {
+        if end <= start + 1 {
+            return;
+        }
+        let n = self.vertices.len();
+        let a = self.vertices[start];
+        let b = self.vertices[end % n];
+        let mut furthest = (start, F::zero());
+        for i in (start + 1)..end {
+            let d = Self::distance_to_segment(&self.vertices[i], &a, &b);
+            if d > furthest.1 {
+                furthest = (i, d);
+            }
+        }
+        if furthest.1 > tolerance {
+            keep[furthest.0] = true;
+            self.rdp(start, furthest.0, tolerance, keep);
+            self.rdp(furthest.0, end, tolerance, keep);
+        }
+    }
+
+    //fi distance_to_segment
+    /// Get the distance from a point to the line segment between two
+    /// points
+    fn distance_to_segment(p: &V2, a: &V2, b: &V2) -> F {
+        let ab = *b - *a;
+        let len_sq = ab.length_sq();
+        if len_sq == F::zero() {
+            return p.distance(a);
+        }
+        let t = ((*p - *a).dot(&ab) / len_sq).max(F::zero()).min(F::one());
+        p.distance(&(*a + ab * t))
+    }
+}
@@ -0,0 +1,287 @@
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use super::{Range, Transform};
+use crate::{Aabb, Float, SqMatrix, Vector};
+
+//a Float4
+//tp Float4
+/// Four values, one for each side of a [Rectangle], such as the
+/// margins or padding around a user interface element
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Float4<F: Float> {
+    /// The value for the side at the minimum X
+    pub x0: F,
+    /// The value for the side at the minimum Y
+    pub y0: F,
+    /// The value for the side at the maximum X
+    pub x1: F,
+    /// The value for the side at the maximum Y
+    pub y1: F,
+}
+
+//ip Float4
+impl<F: Float> Float4<F> {
+    //cp new
+    /// Create a [Float4] from its values
+    pub fn new(x0: F, y0: F, x1: F, y1: F) -> Self {
+        Self { x0, y0, x1, y1 }
+    }
+
+    //cp uniform
+    /// Create a [Float4] with the same value for every side
+    pub fn uniform(v: F) -> Self {
+        Self::new(v, v, v, v)
+    }
+}
+
+//ip Neg for Float4
+impl<F: Float> std::ops::Neg for Float4<F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.x0, -self.y0, -self.x1, -self.y1)
+    }
+}
+
+//a Rectangle
+//tp Rectangle
+/// An axis-aligned rectangle, such as the bounds of a user interface
+/// element
+///
+/// This is the two-dimensional [Aabb], with methods for the width and
+/// height, ranges of X and Y, and corners of the rectangle, and to
+/// expand or shrink it by margins.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Rectangle<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    /// The bounds of the rectangle
+    aabb: Aabb<F, V2, 2>,
+}
+
+//ip Display for Rectangle
+impl<F, V2> std::fmt::Display for Rectangle<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.aabb, f)
+    }
+}
+
+//ip From<Aabb> for Rectangle
+impl<F, V2> From<Aabb<F, V2, 2>> for Rectangle<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    fn from(aabb: Aabb<F, V2, 2>) -> Self {
+        Self { aabb }
+    }
+}
+
+//ip From<Rectangle> for Aabb
+impl<F, V2> From<Rectangle<F, V2>> for Aabb<F, V2, 2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    fn from(r: Rectangle<F, V2>) -> Self {
+        r.aabb
+    }
+}
+
+//ip Rectangle
+impl<F, V2> Rectangle<F, V2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    //cp new
+    /// Create a rectangle from two opposite corners, in any order
+    pub fn new(a: &V2, b: &V2) -> Self {
+        Aabb::new(a, b).into()
+    }
+
+    //cp of_cwh
+    /// Create a rectangle from its centre, width and height
+    pub fn of_cwh(centre: &V2, width: F, height: F) -> Self {
+        let half = V2::from_array([width.abs(), height.abs()]) * F::frac(1, 2);
+        Self::new(&(*centre - half), &(*centre + half))
+    }
+
+    //cp of_ranges
+    /// Create a rectangle from its ranges of X and Y
+    pub fn of_ranges(x: &Range<F>, y: &Range<F>) -> Self {
+        Self::new(
+            &V2::from_array([x.min(), y.min()]),
+            &V2::from_array([x.max(), y.max()]),
+        )
+    }
+
+    //cp from_points
+    /// Create the smallest rectangle containing all of the points, or
+    /// None if there are no points
+    pub fn from_points<'a, I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a V2>,
+        V2: 'a,
+    {
+        Aabb::from_points(points).map(|aabb| aabb.into())
+    }
+
+    //mp as_aabb
+    /// Get the rectangle as an [Aabb]
+    pub fn as_aabb(&self) -> &Aabb<F, V2, 2> {
+        &self.aabb
+    }
+
+    //mp min
+    /// Get the corner of the rectangle with the minimum X and Y
+    pub fn min(&self) -> V2 {
+        self.aabb.min()
+    }
+
+    //mp max
+    /// Get the corner of the rectangle with the maximum X and Y
+    pub fn max(&self) -> V2 {
+        self.aabb.max()
+    }
+
+    //mp x_range
+    /// Get the range of X of the rectangle
+    pub fn x_range(&self) -> Range<F> {
+        Range::new(self.min()[0], self.max()[0])
+    }
+
+    //mp y_range
+    /// Get the range of Y of the rectangle
+    pub fn y_range(&self) -> Range<F> {
+        Range::new(self.min()[1], self.max()[1])
+    }
+
+    //mp width
+    /// Get the width of the rectangle
+    pub fn width(&self) -> F {
+        self.max()[0] - self.min()[0]
+    }
+
+    //mp height
+    /// Get the height of the rectangle
+    pub fn height(&self) -> F {
+        self.max()[1] - self.min()[1]
+    }
+
+    //mp size
+    /// Get the width and height of the rectangle
+    pub fn size(&self) -> V2 {
+        self.aabb.extent()
+    }
+
+    //mp area
+    /// Get the area of the rectangle
+    pub fn area(&self) -> F {
+        self.aabb.volume()
+    }
+
+    //mp center
+    /// Get the centre of the rectangle
+    pub fn center(&self) -> V2 {
+        self.aabb.center()
+    }
+
+    //mp corners
+    /// Get the corners of the rectangle, anticlockwise from that with
+    /// the minimum X and Y
+    pub fn corners(&self) -> [V2; 4] {
+        let (min, max) = (self.min(), self.max());
+        [
+            min,
+            V2::from_array([max[0], min[1]]),
+            max,
+            V2::from_array([min[0], max[1]]),
+        ]
+    }
+
+    //mp contains
+    /// Return true if a point is within the rectangle (or on its
+    /// boundary)
+    pub fn contains(&self, p: &V2) -> bool {
+        self.aabb.contains(p)
+    }
+
+    //mp intersects
+    /// Return true if this rectangle overlaps (or touches) another
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.aabb.intersects(&other.aabb)
+    }
+
+    //mp include
+    /// Expand the rectangle, if required, to include a point
+    pub fn include(&mut self, p: &V2) {
+        self.aabb.include(p);
+    }
+
+    //mp union
+    /// Get the smallest rectangle that contains this and another
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.aabb.union(&other.aabb).into()
+    }
+
+    //mp intersection
+    /// Get the rectangle that is the overlap of this and another, or
+    /// None if they do not overlap
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.aabb.intersection(&other.aabb).map(|aabb| aabb.into())
+    }
+
+    //mp expand
+    /// Get the rectangle with each side moved outwards by its margin;
+    /// if the margins are negative then the rectangle shrinks, but by
+    /// no more than to its centre
+    #[must_use]
+    pub fn expand(&self, margins: &Float4<F>) -> Self {
+        let x = self.x_range();
+        let y = self.y_range();
+        let x0 = (x.min() - margins.x0).min(x.center());
+        let x1 = (x.max() + margins.x1).max(x.center());
+        let y0 = (y.min() - margins.y0).min(y.center());
+        let y1 = (y.max() + margins.y1).max(y.center());
+        Self::new(&V2::from_array([x0, y0]), &V2::from_array([x1, y1]))
+    }
+
+    //mp shrink
+    /// Get the rectangle with each side moved inwards by its margin,
+    /// but by no more than to its centre
+    #[must_use]
+    pub fn shrink(&self, margins: &Float4<F>) -> Self {
+        self.expand(&-*margins)
+    }
+
+    //mp translate
+    /// Get the rectangle moved by a vector
+    #[must_use]
+    pub fn translate(&self, by: &V2) -> Self {
+        Self::new(&(self.min() + *by), &(self.max() + *by))
+    }
+
+    //mp transform
+    /// Get the bounds of the rectangle transformed by an affine
+    /// [Transform]
+    #[must_use]
+    pub fn transform<M2>(&self, t: &Transform<F, V2, M2>) -> Self
+    where
+        M2: SqMatrix<V2, F, 2, 4>,
+    {
+        let corners = self.corners().map(|c| t.apply(&c));
+        let mut r = Self::new(&corners[0], &corners[1]);
+        r.include(&corners[2]);
+        r.include(&corners[3]);
+        r
+    }
+}
@@ -0,0 +1,212 @@
+//a Imports
+use crate::{Float, SingularMatrix, SqMatrix, Vector};
+
+//a Transform
+//tp Transform
+/// An affine transformation in two dimensions, as a 2-by-2 matrix
+/// (for rotation, scaling and shear) followed by a translation
+///
+/// A point p is transformed to M.p + t; a vector is transformed only
+/// by the matrix. The transformations compose with `*`, where `a * b`
+/// applies `b` then `a`, as for matrices.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::geo2d::Transform;
+/// use geo_nd::{FArray, FArray2, Vector};
+/// type Vec2 = FArray<f64, 2>;
+/// let t: Transform<f64, Vec2, FArray2<f64, 2, 4>> =
+///     Transform::of_trs(&Vec2::from_array([1., 2.]), std::f64::consts::FRAC_PI_2, 2.);
+/// let p = t.apply(&Vec2::from_array([1., 0.]));
+/// assert!(p.distance(&Vec2::from_array([1., 4.])) < 1E-12);
+/// let q = t.inverse().apply(&p);
+/// assert!(q.distance(&Vec2::from_array([1., 0.])) < 1E-12);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Transform<F, V2, M2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+    M2: SqMatrix<V2, F, 2, 4>,
+{
+    /// The matrix applied first
+    matrix: M2,
+    /// The translation applied after the matrix
+    translation: V2,
+    /// Marker for the float type
+    phantom: std::marker::PhantomData<F>,
+}
+
+//ip Default for Transform
+impl<F, V2, M2> std::default::Default for Transform<F, V2, M2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+    M2: SqMatrix<V2, F, 2, 4>,
+{
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+//ip Display for Transform
+impl<F, V2, M2> std::fmt::Display for Transform<F, V2, M2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+    M2: SqMatrix<V2, F, 2, 4>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let m: &[F; 4] = self.matrix.as_ref();
+        write!(
+            f,
+            "[{},{},{},{}] + {}",
+            m[0], m[1], m[2], m[3], self.translation
+        )
+    }
+}
+
+//ip Transform
+impl<F, V2, M2> Transform<F, V2, M2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+    M2: SqMatrix<V2, F, 2, 4>,
+{
+    //cp of_matrix
+    /// Create a transformation from a matrix and a translation applied
+    /// after it
+    pub fn of_matrix(matrix: M2, translation: &V2) -> Self {
+        Self {
+            matrix,
+            translation: *translation,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    //cp identity
+    /// Create the identity transformation
+    pub fn identity() -> Self {
+        Self::of_matrix(M2::identity(), &V2::zero())
+    }
+
+    //cp of_translation
+    /// Create a transformation that translates by a vector
+    pub fn of_translation(translation: &V2) -> Self {
+        Self::of_matrix(M2::identity(), translation)
+    }
+
+    //cp of_rotation
+    /// Create a transformation that rotates anticlockwise around the
+    /// origin by an angle in radians
+    pub fn of_rotation(angle: F) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self::of_matrix(M2::from_array([c, -s, s, c]), &V2::zero())
+    }
+
+    //cp of_scale
+    /// Create a transformation that scales by different amounts in X
+    /// and Y
+    pub fn of_scale(x: F, y: F) -> Self {
+        let zero = F::zero();
+        Self::of_matrix(M2::from_array([x, zero, zero, y]), &V2::zero())
+    }
+
+    //cp of_trs
+    /// Create a transformation that scales uniformly, then rotates
+    /// anticlockwise by an angle in radians, then translates
+    pub fn of_trs(translation: &V2, angle: F, scale: F) -> Self {
+        let (s, c) = angle.sin_cos();
+        let (s, c) = (s * scale, c * scale);
+        Self::of_matrix(M2::from_array([c, -s, s, c]), translation)
+    }
+
+    //mp matrix
+    /// Get the matrix of the transformation
+    pub fn matrix(&self) -> M2 {
+        self.matrix
+    }
+
+    //mp translation
+    /// Get the translation of the transformation
+    pub fn translation(&self) -> V2 {
+        self.translation
+    }
+
+    //mp determinant
+    /// Get the determinant of the matrix of the transformation, which
+    /// is the factor by which it scales areas; it is negative if the
+    /// transformation is a reflection
+    pub fn determinant(&self) -> F {
+        self.matrix.determinant()
+    }
+
+    //mp apply
+    /// Apply the transformation to a point
+    pub fn apply(&self, p: &V2) -> V2 {
+        self.matrix.transform(p) + self.translation
+    }
+
+    //mp apply_vector
+    /// Apply the transformation to a vector, which (unlike a point) is
+    /// not translated
+    pub fn apply_vector(&self, v: &V2) -> V2 {
+        self.matrix.transform(v)
+    }
+
+    //mp inverse
+    /// Get the inverse transformation
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        let matrix = self.matrix.inverse();
+        Self::of_matrix(matrix, &-matrix.transform(&self.translation))
+    }
+
+    //mp try_inverse
+    /// Get the inverse transformation, or return an error if the
+    /// magnitude of the determinant of its matrix is no more than
+    /// `epsilon`
+    pub fn try_inverse(&self, epsilon: F) -> Result<Self, SingularMatrix<F>> {
+        let matrix = self.matrix.try_inverse(epsilon)?;
+        Ok(Self::of_matrix(
+            matrix,
+            &-matrix.transform(&self.translation),
+        ))
+    }
+
+    //mp as_mat3
+    /// Get the 3-by-3 row-major matrix of the transformation, which
+    /// applies it to a point (x, y, 1)
+    pub fn as_mat3(&self) -> [F; 9] {
+        let m: &[F; 4] = self.matrix.as_ref();
+        let t = self.translation;
+        let (zero, one) = (F::zero(), F::one());
+        [m[0], m[1], t[0], m[2], m[3], t[1], zero, zero, one]
+    }
+}
+
+//ip Mul<Self> for Transform
+impl<F, V2, M2> std::ops::Mul<Self> for Transform<F, V2, M2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+    M2: SqMatrix<V2, F, 2, 4>,
+{
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self::of_matrix(self.matrix * other.matrix, &self.apply(&other.translation))
+    }
+}
+
+//ip MulAssign<Self> for Transform
+impl<F, V2, M2> std::ops::MulAssign<Self> for Transform<F, V2, M2>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+    M2: SqMatrix<V2, F, 2, 4>,
+{
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
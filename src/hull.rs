@@ -0,0 +1,426 @@
+//a Imports
+use std::collections::HashMap;
+
+use crate::{vector, FArray, Float, Vector};
+
+//a HullFace
+//tp HullFace
+/// A triangular face of a convex hull in three dimensions, as returned
+/// by [hull3]
+#[derive(Clone, Copy, Debug)]
+pub struct HullFace<F, V3>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+{
+    /// The indices of the points that are the vertices of the face,
+    /// anticlockwise when viewed from outside the hull
+    pub vertices: [usize; 3],
+    /// The outward unit normal of the face
+    pub normal: V3,
+    /// The distance of the plane of the face from the origin along its
+    /// normal; points p within the hull have normal.p <= offset
+    pub offset: F,
+}
+
+//a hull2
+//fi is_finite
+/// Return true if all of the coordinates of a point are finite
+fn is_finite<F, V, const D: usize>(p: &V) -> bool
+where
+    F: Float,
+    V: Vector<F, D>,
+{
+    (0..D).all(|c| p[c].is_finite())
+}
+
+//fi turns_left
+/// Return true if `o`, `a` and `b` turn anticlockwise, by more than
+/// the rounding error of their cross product
+fn turns_left<F, V2>(o: &V2, a: &V2, b: &V2) -> bool
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    let (oa, ob) = (*a - *o, *b - *o);
+    let cross = oa[0] * ob[1] - oa[1] * ob[0];
+    let size = (oa[0].abs() + oa[1].abs()) * (ob[0].abs() + ob[1].abs());
+    cross > size * F::epsilon() * F::int(4)
+}
+
+//fp hull2
+/// Find the convex hull of a set of points in two dimensions, using
+/// Andrew's monotone chain algorithm
+///
+/// The hull is returned as the indices of the points at its vertices,
+/// anticlockwise from that with the least X (and then Y). Points on
+/// the edges of the hull are not vertices, and of coincident points
+/// only the first is used. If the points are all collinear then the
+/// hull is the indices of the two ends of the line, and if they all
+/// coincide it is the index of the first. Points with a coordinate
+/// that is not finite (infinite or NaN) are ignored.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{hull2, FArray, Vector};
+/// type Vec2 = FArray<f64, 2>;
+/// let points = [[0., 0.], [1., 1.], [2., 0.], [2., 2.], [1., 0.], [0., 2.]]
+///     .map(Vec2::from_array);
+/// assert_eq!(hull2(&points), vec![0, 2, 3, 5]);
+/// ```
+pub fn hull2<F, V2>(points: &[V2]) -> Vec<usize>
+where
+    F: Float,
+    V2: Vector<F, 2>,
+{
+    // Only finite points are used, so that the comparison is a total
+    // order
+    let mut order: Vec<usize> = (0..points.len())
+        .filter(|i| is_finite(&points[*i]))
+        .collect();
+    order.sort_by(|a, b| {
+        let (pa, pb) = (&points[*a], &points[*b]);
+        let x = pa[0].partial_cmp(&pb[0]).unwrap();
+        x.then(pa[1].partial_cmp(&pb[1]).unwrap())
+    });
+    order.dedup_by(|a, b| points[*a][0] == points[*b][0] && points[*a][1] == points[*b][1]);
+    if order.len() < 3 {
+        return order;
+    }
+
+    // The lower hull, from the least to the greatest X, and then the
+    // upper hull back again; the last point is the first of the lower
+    let mut hull: Vec<usize> = Vec::with_capacity(order.len() + 1);
+    for i in &order {
+        while hull.len() >= 2
+            && !turns_left(
+                &points[hull[hull.len() - 2]],
+                &points[hull[hull.len() - 1]],
+                &points[*i],
+            )
+        {
+            hull.pop();
+        }
+        hull.push(*i);
+    }
+    let lower = hull.len() + 1;
+    for i in order.iter().rev().skip(1) {
+        while hull.len() >= lower
+            && !turns_left(
+                &points[hull[hull.len() - 2]],
+                &points[hull[hull.len() - 1]],
+                &points[*i],
+            )
+        {
+            hull.pop();
+        }
+        hull.push(*i);
+    }
+    hull.pop();
+    hull
+}
+
+//a hull3
+//ti Face
+/// A face of a hull being built by [hull3], with the points outside it
+/// that are yet to be added to the hull
+struct Face<F, V3>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+{
+    /// The face itself
+    face: HullFace<F, V3>,
+    /// The indices of the points outside the face
+    outside: Vec<usize>,
+    /// False if the face has been replaced
+    alive: bool,
+}
+
+//ii Face
+impl<F, V3> Face<F, V3>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+{
+    //cp new
+    fn new(points: &[V3], vertices: [usize; 3]) -> Self {
+        let [a, b, c] = vertices.map(|i| points[i]);
+        let normal = cross(&(b - a), &(c - a)).normalize();
+        let offset = normal.dot(&a);
+        Self {
+            face: HullFace {
+                vertices,
+                normal,
+                offset,
+            },
+            outside: Vec::new(),
+            alive: true,
+        }
+    }
+
+    //mp distance
+    /// Get the distance of a point outside the face
+    fn distance(&self, p: &V3) -> F {
+        self.face.normal.dot(p) - self.face.offset
+    }
+}
+
+//fi cross
+#[inline]
+fn cross<F, V3>(a: &V3, b: &V3) -> V3
+where
+    F: Float,
+    V3: Vector<F, 3>,
+{
+    V3::from_array(vector::cross_product3(a.as_ref(), b.as_ref()))
+}
+
+//fi furthest
+/// Find the index of the point that is furthest by some measure, and
+/// that measure
+fn furthest<F, V3, M>(points: &[V3], measure: M) -> (usize, F)
+where
+    F: Float,
+    V3: Vector<F, 3>,
+    M: Fn(&V3) -> F,
+{
+    points
+        .iter()
+        .enumerate()
+        .fold((0, F::zero()), |(best, far), (i, p)| {
+            let d = measure(p);
+            if d.abs() > far.abs() {
+                (i, d)
+            } else {
+                (best, far)
+            }
+        })
+}
+
+//fi flat_hull
+/// Get the hull of a set of coplanar points, as the polygon that is
+/// their hull in the plane triangulated on each side
+///
+/// The front is a fan from the first vertex of the polygon, and the
+/// back is a fan from the second, so that no diagonal is shared by
+/// more than two faces
+fn flat_hull<F, V3>(points: &[V3], origin: &V3, u: &V3, normal: &V3) -> Vec<HullFace<F, V3>>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+{
+    let v = cross(normal, u);
+    let projected: Vec<FArray<F, 2>> = points
+        .iter()
+        .map(|p| {
+            let d = *p - *origin;
+            FArray::from_array([d.dot(u), d.dot(&v)])
+        })
+        .collect();
+    let outline = hull2(&projected);
+    let n = outline.len();
+    let offset = normal.dot(origin);
+    let mut faces = Vec::new();
+    for i in 2..n {
+        faces.push(HullFace {
+            vertices: [outline[0], outline[i - 1], outline[i]],
+            normal: *normal,
+            offset,
+        });
+        faces.push(HullFace {
+            vertices: [outline[1], outline[(i + 1) % n], outline[i]],
+            normal: -*normal,
+            offset: -offset,
+        });
+    }
+    faces
+}
+
+//fp hull3
+/// Find the convex hull of a set of points in three dimensions, using
+/// the quickhull algorithm
+///
+/// The hull is returned as triangular faces, each with the indices of
+/// its vertices (anticlockwise when viewed from outside) and its
+/// outward normal. Each edge of the hull is shared by exactly two
+/// faces.
+///
+/// Points within a small tolerance (relative to the size of the
+/// coordinates) of the hull are not vertices, so that coplanar points
+/// do not give degenerate faces, though a flat face of the hull is
+/// still split into triangles. If all of the points are coplanar then
+/// the hull is flat, the polygon that is their hull in the plane
+/// triangulated on each side; if they are all collinear (or there are
+/// fewer than three) then there are no faces. Points with a
+/// coordinate that is not finite (infinite or NaN) are ignored.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{hull3, FArray, Vector};
+/// type Vec3 = FArray<f64, 3>;
+/// let points = [
+///     [0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.], [0.1, 0.1, 0.1],
+/// ]
+/// .map(Vec3::from_array);
+/// let faces = hull3(&points);
+/// assert_eq!(faces.len(), 4);
+/// for f in &faces {
+///     assert!(!f.vertices.contains(&4));
+///     assert!(f.normal.dot(&points[4]) < f.offset);
+/// }
+/// ```
+pub fn hull3<F, V3>(points: &[V3]) -> Vec<HullFace<F, V3>>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+{
+    if !points.iter().all(is_finite) {
+        let finite: Vec<usize> = (0..points.len())
+            .filter(|i| is_finite(&points[*i]))
+            .collect();
+        let subset: Vec<V3> = finite.iter().map(|i| points[*i]).collect();
+        let mut faces = hull3(&subset);
+        for f in faces.iter_mut() {
+            f.vertices = f.vertices.map(|i| finite[i]);
+        }
+        return faces;
+    }
+    if points.len() < 3 {
+        return Vec::new();
+    }
+    let scale = (0..3).fold(F::zero(), |acc, c| {
+        acc + points.iter().fold(F::zero(), |m, p| m.max(p[c].abs()))
+    });
+    let tolerance = scale * F::epsilon() * F::int(16);
+
+    // The initial tetrahedron is the two furthest apart of the extreme
+    // points in each axis, the point furthest from the line between
+    // them, and then that furthest from the plane of all three
+    let mut extremes = [0; 6];
+    for (i, p) in points.iter().enumerate() {
+        for c in 0..3 {
+            if p[c] < points[extremes[2 * c]][c] {
+                extremes[2 * c] = i;
+            }
+            if p[c] > points[extremes[2 * c + 1]][c] {
+                extremes[2 * c + 1] = i;
+            }
+        }
+    }
+    let (mut a, mut b, mut length) = (0, 0, F::zero());
+    for i in extremes {
+        for j in extremes {
+            let d = points[i].distance(&points[j]);
+            if d > length {
+                (a, b, length) = (i, j, d);
+            }
+        }
+    }
+    if length <= tolerance {
+        return Vec::new();
+    }
+    let u = (points[b] - points[a]).normalize();
+    let (c, width) = furthest(points, |p| cross(&(*p - points[a]), &u).length());
+    if width <= tolerance {
+        return Vec::new();
+    }
+    let normal = cross(&u, &(points[c] - points[a])).normalize();
+    let (d, height) = furthest(points, |p| normal.dot(&(*p - points[a])));
+    if height.abs() <= tolerance {
+        return flat_hull(points, &points[a], &u, &normal);
+    }
+    let (b, c) = if height > F::zero() { (c, b) } else { (b, c) };
+    let mut faces: Vec<Face<F, V3>> = [[a, b, c], [b, a, d], [c, b, d], [a, c, d]]
+        .into_iter()
+        .map(|v| Face::new(points, v))
+        .collect();
+
+    // Each directed edge of the hull maps to the face that it is an
+    // edge of; its reverse is that of the neighbouring face
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+    for (i, f) in faces.iter().enumerate() {
+        let v = f.face.vertices;
+        for k in 0..3 {
+            edges.insert((v[k], v[(k + 1) % 3]), i);
+        }
+    }
+    let assign = |faces: &mut [Face<F, V3>], first: usize, p: usize| {
+        if let Some(f) = faces[first..]
+            .iter_mut()
+            .find(|f| f.distance(&points[p]) > tolerance)
+        {
+            f.outside.push(p);
+        }
+    };
+    for p in 0..points.len() {
+        if ![a, b, c, d].contains(&p) {
+            assign(&mut faces, 0, p);
+        }
+    }
+
+    // Repeatedly add the point furthest outside a face, replacing the
+    // faces that it can see with a cone from their horizon to it
+    while let Some(start) = faces.iter().position(|f| f.alive && !f.outside.is_empty()) {
+        let face = &faces[start];
+        let eye = *face
+            .outside
+            .iter()
+            .max_by(|p, q| {
+                let (dp, dq) = (face.distance(&points[**p]), face.distance(&points[**q]));
+                dp.partial_cmp(&dq).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap();
+
+        let mut visible: HashMap<usize, bool> = HashMap::new();
+        visible.insert(start, true);
+        let mut stack = vec![start];
+        let mut horizon = Vec::new();
+        while let Some(f) = stack.pop() {
+            let v = faces[f].face.vertices;
+            for k in 0..3 {
+                let (p, q) = (v[k], v[(k + 1) % 3]);
+                let g = edges[&(q, p)];
+                let g_visible = *visible
+                    .entry(g)
+                    .or_insert_with(|| faces[g].distance(&points[eye]) > tolerance);
+                if !g_visible {
+                    horizon.push((p, q));
+                } else if faces[g].alive {
+                    faces[g].alive = false;
+                    stack.push(g);
+                }
+            }
+            faces[f].alive = false;
+        }
+
+        let mut orphans = Vec::new();
+        for (f, _) in visible.iter().filter(|(_, v)| **v) {
+            let v = faces[*f].face.vertices;
+            for k in 0..3 {
+                edges.remove(&(v[k], v[(k + 1) % 3]));
+            }
+            orphans.append(&mut faces[*f].outside);
+        }
+        let first = faces.len();
+        for (p, q) in horizon {
+            let i = faces.len();
+            faces.push(Face::new(points, [p, q, eye]));
+            edges.insert((p, q), i);
+            edges.insert((q, eye), i);
+            edges.insert((eye, p), i);
+        }
+        for p in orphans {
+            if p != eye {
+                assign(&mut faces, first, p);
+            }
+        }
+    }
+    faces
+        .into_iter()
+        .filter(|f| f.alive)
+        .map(|f| f.face)
+        .collect()
+}
@@ -20,7 +20,7 @@ limitations under the License.
 
 remove indexing from quaternions
 
-Add matrix trait which takes out most of the sqmatrix traits
+Make SqMatrix build on the Matrix trait, which takes out most of the sqmatrix traits
 
 Document and get transform to work
 
@@ -146,7 +146,11 @@ last element the distance scaling.
 
 They provide [SqMatrix] and additionally support graphically-useful
 constructors 'perspective' and 'look_at', and support translation by
-vectors.
+vectors. Projections ('frustum', 'orthographic', 'perspective_fov' and
+'infinite_perspective') may be created for the clip space of OpenGL,
+Vulkan or Direct3D, or with reversed-Z, as given by a [ClipSpace].
+They may be decomposed into (and composed from) their translation,
+rotation, scaling, shear and perspective as a [MatrixDecomposition].
 
 ## Quaternion
 
@@ -218,11 +222,54 @@ and it supports the [Vector] trait.
 The [FArray2] type is a wrapper around an N-by-N-element array of floats,
 and it supports the [SqMatrix] trait.
 
+## Matrix types
+
+The [FArrayRC] type is a wrapper around an R-by-C-element array of
+floats, and it supports the [Matrix] trait; it may be multiplied by
+other [FArrayRC] of a suitable shape, and by [FArray] vectors.
+
 ## Quaternion types
 
 The [QArray] type is a wrapper around an 4-element array of floats,
 and it supports the [Quaternion] trait.
 
+The [DQArray] type is a pair of [QArray], and it supports the
+[DualQuaternion] trait for rigid transformations that can be
+blended, such as for skinning.
+
+## Transform types
+
+The [FQArrayTrans] type is a translation, rotation and uniform
+scaling, and the [TrsTransform] type is a translation, rotation and
+scaling along each axis (as used in glTF); both support the
+[Transform] trait.
+
+## Culling and intersection types
+
+The [Frustum] type is the six planes of a view frustum, extracted
+from a view-projection matrix, with tests of points, spheres and
+boxes that give a [Containment].
+
+The [Aabb] type is an axis-aligned bounding box in any number of
+dimensions, which may be transformed by a 4-by-4 matrix in three
+dimensions.
+
+The [Ray] type is an origin and direction, with intersection tests
+against planes, spheres, triangles and axis-aligned or oriented boxes
+that give a [RayHit].
+
+The [hull2] and [hull3] functions find the convex hull of a set of
+points, in two dimensions as the indices of its vertices in order,
+and in three dimensions as triangular [HullFace]s with outward
+normals.
+
+## Curve types
+
+The [Bezier] type is a linear, quadratic or cubic Bezier curve in any
+number of dimensions, with its tangent and normal, splitting, tight
+bounds, and arc length (and the parameter at an arc length); in two
+dimensions it may also be offset.
+
 # Examples
 
 ## Two dimensions
@@ -318,26 +365,51 @@ assert!((m.transform(&z) - x).length() < 1.0E-8);
 !*/
 
 //a Imports
+mod decomposition;
+mod dual_quaternion_op;
+mod error;
+mod euler;
 mod matrix_op;
 mod matrixr_op;
+mod projection;
 mod quaternion_op;
 mod traits;
 mod vector_op;
 
+mod aabb;
+mod bezier;
+mod dqarray;
 mod farray;
 mod farray2;
+mod farrayrc;
 mod fqarray;
+mod frustum;
+mod hull;
 mod qarray;
+mod ray;
+mod trs;
 
 //a Exports
+pub use aabb::Aabb;
+pub use bezier::Bezier;
+pub use decomposition::MatrixDecomposition;
+pub use dqarray::DQArray;
+pub use error::SingularMatrix;
+pub use euler::{EulerAxes, EulerOrder};
 pub use farray::FArray;
 pub use farray2::FArray2;
+pub use farrayrc::FArrayRC;
 pub use fqarray::FQArrayTrans;
+pub use frustum::{Containment, Frustum};
+pub use hull::{hull2, hull3, HullFace};
+pub use projection::{ClipSpace, DepthRange, Handedness};
 pub use qarray::QArray;
+pub use ray::{Ray, RayHit};
 pub use traits::{
-    Float, Geometry2D, Geometry3D, Num, Quaternion, SqMatrix, SqMatrix3, SqMatrix4, Transform,
-    Vector, Vector3, Vector3D,
+    DualQuaternion, Float, Geometry2D, Geometry3D, Matrix, Num, Quaternion, SqMatrix, SqMatrix3,
+    SqMatrix4, Transform, Vector, Vector3, Vector3D,
 };
+pub use trs::TrsTransform;
 
 /// Vector functions module
 ///
@@ -351,29 +423,115 @@ pub mod quat {
     pub use super::quaternion_op::*;
 }
 
+/// Dual quaternion module
+///
+/// The dual quaternions are [Float; 8], the real quaternion followed
+/// by the dual quaternion, each in the order i, j, k, r
+pub mod dquat {
+    pub use super::dual_quaternion_op::*;
+}
+
 /// Matrix library
 pub mod matrix {
     pub use super::matrix_op::*;
     pub use super::matrixr_op::*;
 }
 
+//a 2D geometry
+/// Two-dimensional geometry, built on the vector and matrix types of
+/// a [Geometry2D]
+///
+/// The types are generic over the vector type `V2` (and, for a
+/// [geo2d::Transform], the matrix type `M2`), which are usually the
+/// `Vec2` and `Mat2` of a [Geometry2D]:
+///
+/// * [geo2d::Point] is a position, which differs from a vector in that
+///   the difference of two points is a vector
+///
+/// * [geo2d::Range] is a closed range of values, such as the extent of
+///   a [geo2d::Rectangle] in X or Y
+///
+/// * [geo2d::Rectangle] is an axis-aligned rectangle, such as the
+///   bounds of a user interface element, which may be expanded or
+///   shrunk by a [geo2d::Float4] of margins
+///
+/// * [geo2d::Bezier] is the [Bezier] curve, in two dimensions
+///
+/// * [geo2d::Polygon] is a closed polygon, given by its vertices, with
+///   its area, centroid and [geo2d::Winding], point containment with a
+///   [geo2d::FillRule], convexity, triangulation and simplification
+///
+/// * [geo2d::Transform] is an affine transformation, as a 2-by-2
+///   matrix and a translation
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::geo2d::{Point, Rectangle, Transform};
+/// use geo_nd::{Geometry2D, Vector};
+/// type Vec2 = <f64 as Geometry2D<f64>>::Vec2;
+/// type Mat2 = <f64 as Geometry2D<f64>>::Mat2;
+/// let r: Rectangle<f64, Vec2> = Rectangle::new(&Vec2::from_array([0., 0.]), &Vec2::from_array([2., 1.]));
+/// let t: Transform<f64, Vec2, Mat2> = Transform::of_translation(&Vec2::from_array([1., 1.]));
+/// assert_eq!(r.transform(&t).max().into_array(), [3., 2.]);
+/// let p: Point<f64, Vec2> = Point::new(1., 2.);
+/// assert_eq!((p - Point::origin()).into_array(), [1., 2.]);
+/// ```
+pub mod geo2d {
+    mod point;
+    mod polygon;
+    mod rectangle;
+    mod transform;
+
+    pub use self::point::{Point, Range};
+    pub use self::polygon::{FillRule, Polygon, Winding};
+    pub use self::rectangle::{Float4, Rectangle};
+    pub use self::transform::Transform;
+    pub use super::Bezier;
+}
+
 //a SIMD configuration
-#[cfg(feature = "simd")]
-extern crate core_simd;
+/// SIMD vector types, enabled with the `simd` feature
+///
+/// The vectors are held in 16-byte aligned registers, and use the
+/// stable `core::arch` intrinsics where the target supports them
+/// (SSE on x86 and x86_64), falling back to scalar code otherwise.
+///
+/// The 3D types are bundled together in [simd::VecF32A16], which
+/// provides [Vector3D] and [Geometry3D].
 #[cfg(feature = "simd")]
 pub mod simd {
+    mod simd_f32;
+    mod simd_mat;
+    mod simd_trans;
     mod simd_vec;
+    pub use self::simd_mat::{F32Mat3, F32x4Mat4};
+    pub use self::simd_trans::{F32Trans, F32x4Quat};
     pub use self::simd_vec::{F32x2Vec2, F32x4Vec2, F32x4Vec3, F32x4Vec4};
 
-    //tp SimdVecF32A16 - empty struct that provides a wrapper for the associated types
+    //tp VecF32A16
+    /// An empty struct that provides the 16-byte aligned SIMD types as
+    /// the associated types of [Vector3D](crate::Vector3D) and
+    /// [Geometry3D](crate::Geometry3D)
     pub struct VecF32A16 {}
     impl crate::Vector3D<f32> for VecF32A16 {
         type Vec2 = F32x4Vec2;
         type Vec3 = F32x4Vec3;
         type Vec4 = F32x4Vec4;
     }
+    impl crate::Geometry3D<f32> for VecF32A16 {
+        type Vec3 = F32x4Vec3;
+        type Vec4 = F32x4Vec4;
+        type Mat3 = F32Mat3;
+        type Mat4 = F32x4Mat4;
+        type Quat = F32x4Quat;
+        type Trans = F32Trans;
+    }
 
-    //tp SimdVecF32A8 - empty struct that provides a wrapper for the associated types
+    //tp VecF32A8
+    /// An empty struct that provides the SIMD types as the associated
+    /// types of [Vector3D](crate::Vector3D), with an 8-byte aligned
+    /// 2D vector
     pub struct VecF32A8 {}
     impl crate::Vector3D<f32> for VecF32A8 {
         type Vec2 = F32x2Vec2;
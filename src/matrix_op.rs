@@ -17,8 +17,8 @@ limitations under the License.
  */
 
 //a Imports
-use super::{matrixr_op, vector_op};
-use crate::{Float, Num};
+use super::{matrixr_op, quaternion_op, vector_op};
+use crate::{ClipSpace, DepthRange, EulerOrder, Float, MatrixDecomposition, Num, SingularMatrix};
 
 //fp identity
 /// Create an identity square matrix of a given dimension
@@ -73,16 +73,28 @@ pub fn determinant2<V: Num>(m: &[V; 4]) -> V {
 /// ```
 ///
 pub fn inverse2<V: Float>(m: &[V; 4]) -> [V; 4] {
-    let d = determinant2(m);
-    let r_d = {
-        if V::abs(d) > V::epsilon() {
-            V::one() / d
-        } else {
-            V::zero()
-        }
-    };
+    try_inverse2(m, V::epsilon()).unwrap_or([V::zero(); 4])
+}
 
-    [m[3] * r_d, -m[1] * r_d, -m[2] * r_d, m[0] * r_d]
+//fp try_inverse2
+/// Find the inverse of a 2-by-2 matrix, or return an error if the
+/// magnitude of its determinant is no more than `epsilon`
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::try_inverse2;
+/// assert_eq!( try_inverse2(&[2.,0., 0.,4.], 1E-6), Ok([0.5,0., 0.,0.25]) );
+/// assert_eq!( try_inverse2(&[1.,2., 2.,4.], 1E-6).unwrap_err().determinant, 0. );
+/// ```
+///
+pub fn try_inverse2<V: Float>(m: &[V; 4], epsilon: V) -> Result<[V; 4], SingularMatrix<V>> {
+    let d = determinant2(m);
+    if V::abs(d) <= epsilon {
+        return Err(SingularMatrix { determinant: d });
+    }
+    let r_d = V::one() / d;
+    Ok([m[3] * r_d, -m[1] * r_d, -m[2] * r_d, m[0] * r_d])
 }
 
 //fp determinant3
@@ -112,15 +124,28 @@ pub fn determinant3<V: Num>(m: &[V; 9]) -> V {
 /// ```
 ///
 pub fn inverse3<V: Float>(m: &[V; 9]) -> [V; 9] {
+    try_inverse3(m, V::epsilon()).unwrap_or([V::zero(); 9])
+}
+
+//fp try_inverse3
+/// Find the inverse of a 3-by-3 matrix, or return an error if the
+/// magnitude of its determinant is no more than `epsilon`
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::try_inverse3;
+/// assert!( try_inverse3(&[1.,3.,2., 0.,2.,3., -1.,2.,3.], 1E-6).is_ok() );
+/// assert!( try_inverse3(&[1.,3.,2., 0.,2.,3., 1.,5.,5.], 1E-6).is_err() );
+/// ```
+///
+pub fn try_inverse3<V: Float>(m: &[V; 9], epsilon: V) -> Result<[V; 9], SingularMatrix<V>> {
     let mut r = [V::zero(); 9];
     let d = determinant3(m);
-    let r_d = {
-        if V::abs(d) > V::epsilon() {
-            V::one() / d
-        } else {
-            V::zero()
-        }
-    };
+    if V::abs(d) <= epsilon {
+        return Err(SingularMatrix { determinant: d });
+    }
+    let r_d = V::one() / d;
 
     // Allow clippy identity lint for <n>+0 to keep matrix clarity
     #[allow(clippy::identity_op)]
@@ -137,7 +162,7 @@ pub fn inverse3<V: Float>(m: &[V; 9]) -> [V; 9] {
         r[5] = (m[0 + 2] * m[3 + 0] - m[0 + 0] * m[3 + 2]) * r_d;
         r[8] = (m[0 + 0] * m[3 + 1] - m[0 + 1] * m[3 + 0]) * r_d;
     }
-    r
+    Ok(r)
 }
 
 //fp from_quat3
@@ -213,33 +238,426 @@ pub fn determinant4<V: Num>(m: &[V; 16]) -> V {
 /// ```
 ///
 pub fn inverse4<V: Float>(m: &[V; 16]) -> [V; 16] {
+    try_inverse4(m, V::epsilon()).unwrap_or([V::zero(); 16])
+}
+
+//fp try_inverse4
+/// Find the inverse of a 4-by-4 matrix, or return an error if the
+/// magnitude of its determinant is no more than `epsilon`
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::{identity4, try_inverse4};
+/// let i: [f64; 16] = identity4();
+/// assert_eq!( try_inverse4(&i, 1E-6), Ok(i) );
+/// let a = [1.,3.,2.,1., 0.,2.,3.,3., -1.,2.,3.,2., 1.,5.,5.,4.];
+/// assert!( try_inverse4(&a, 1E-6).is_err() );
+/// ```
+///
+pub fn try_inverse4<V: Float>(m: &[V; 16], epsilon: V) -> Result<[V; 16], SingularMatrix<V>> {
     let d = determinant4(m);
+    if V::abs(d) <= epsilon {
+        return Err(SingularMatrix { determinant: d });
+    }
+    let r_d = V::one() / d;
     let mut r = [V::zero(); 16];
-    if V::abs(d) > V::epsilon() {
-        let r_d = V::one() / d;
-
-        for j in 0..4 {
-            let a = ((j + 1) & 3) * 4;
-            let b = ((j + 2) & 3) * 4;
-            let c = ((j + 3) & 3) * 4;
-            for i in 0..4 {
-                let x = (i + 1) & 3;
-                let y = (i + 2) & 3;
-                let z = (i + 3) & 3;
-                let sc = if (i + j) & 1 == 0 {
-                    V::one()
-                } else {
-                    -V::one()
-                };
-                r[i * 4 + j] = ((m[a + x] * m[b + y] - m[b + x] * m[a + y]) * m[c + z]
-                    + (m[a + y] * m[b + z] - m[b + y] * m[a + z]) * m[c + x]
-                    + (m[a + z] * m[b + x] - m[b + z] * m[a + x]) * m[c + y])
-                    * sc
-                    * r_d;
+    for j in 0..4 {
+        let a = ((j + 1) & 3) * 4;
+        let b = ((j + 2) & 3) * 4;
+        let c = ((j + 3) & 3) * 4;
+        for i in 0..4 {
+            let x = (i + 1) & 3;
+            let y = (i + 2) & 3;
+            let z = (i + 3) & 3;
+            let sc = if (i + j) & 1 == 0 {
+                V::one()
+            } else {
+                -V::one()
+            };
+            r[i * 4 + j] = ((m[a + x] * m[b + y] - m[b + x] * m[a + y]) * m[c + z]
+                + (m[a + y] * m[b + z] - m[b + y] * m[a + z]) * m[c + x]
+                + (m[a + z] * m[b + x] - m[b + z] * m[a + x]) * m[c + y])
+                * sc
+                * r_d;
+        }
+    }
+    Ok(r)
+}
+
+//fp lu_decompose
+/// Find the LU decomposition, with partial pivoting, of a D-by-D
+/// matrix (with D2 = D*D)
+///
+/// The result is the packed decomposition, the row permutation, and
+/// the sign of the permutation (one or minus one).
+///
+/// The packed decomposition holds U on and above the diagonal, and L
+/// (whose diagonal is all ones) below the diagonal, such that L.U is
+/// the matrix with its rows permuted; row `i` of L.U is row `perm[i]`
+/// of the matrix.
+///
+/// If the matrix is singular then U has a zero on its diagonal; it
+/// is not an error to decompose such a matrix, but it cannot be used
+/// with [lu_substitute]. A pivot is taken to be zero if its magnitude
+/// is no more than D times epsilon relative to the largest magnitude
+/// in its row of the matrix, so that nearly singular matrices are
+/// treated as singular.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::lu_decompose;
+/// let (lu, perm, sign) = lu_decompose::<f64, 4, 2>(&[2., 1., 4., 3.]);
+/// assert_eq!( perm, [1, 0] );
+/// assert_eq!( sign, -1. );
+/// assert_eq!( lu, [4., 3., 0.5, -0.5] );
+/// ```
+pub fn lu_decompose<V: Float, const D2: usize, const D: usize>(
+    m: &[V; D2],
+) -> ([V; D2], [usize; D], V) {
+    assert_eq!(D2, D * D);
+    let mut lu = *m;
+    let mut perm = [0; D];
+    let mut tiny = [V::zero(); D];
+    for (i, p) in perm.iter_mut().enumerate() {
+        *p = i;
+        let row_max = (0..D).fold(V::zero(), |acc, c| acc.max(m[i * D + c].abs()));
+        tiny[i] = row_max * V::epsilon() * V::int(D as isize);
+    }
+    let mut sign = V::one();
+    for k in 0..D {
+        let mut pivot = k;
+        for r in k + 1..D {
+            if lu[r * D + k].abs() > lu[pivot * D + k].abs() {
+                pivot = r;
+            }
+        }
+        if pivot != k {
+            for c in 0..D {
+                lu.swap(k * D + c, pivot * D + c);
+            }
+            perm.swap(k, pivot);
+            sign = -sign;
+        }
+        let p = lu[k * D + k];
+        if p.abs() <= tiny[perm[k]] {
+            lu[k * D + k] = V::zero();
+            continue;
+        }
+        for r in k + 1..D {
+            let l = lu[r * D + k] / p;
+            lu[r * D + k] = l;
+            for c in k + 1..D {
+                let u = lu[k * D + c];
+                lu[r * D + c] -= l * u;
             }
         }
     }
-    r
+    (lu, perm, sign)
+}
+
+//fp lu_substitute
+/// Solve m.x = b for x, given the LU decomposition of m from
+/// [lu_decompose]
+///
+/// The decomposition must be of a non-singular matrix
+pub fn lu_substitute<V: Float, const D2: usize, const D: usize>(
+    lu: &[V; D2],
+    perm: &[usize; D],
+    b: &[V; D],
+) -> [V; D] {
+    let mut x = [V::zero(); D];
+    for i in 0..D {
+        let mut v = b[perm[i]];
+        for j in 0..i {
+            v -= lu[i * D + j] * x[j];
+        }
+        x[i] = v;
+    }
+    for i in (0..D).rev() {
+        let mut v = x[i];
+        for j in i + 1..D {
+            v -= lu[i * D + j] * x[j];
+        }
+        x[i] = v / lu[i * D + i];
+    }
+    x
+}
+
+//fp lu_solve
+/// Solve m.x = b for x for a D-by-D matrix m, using its LU
+/// decomposition
+///
+/// Returns None if the matrix is singular, or nearly so (as described
+/// for [lu_decompose])
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::vector::{length, sub};
+/// use geo_nd::matrix::lu_solve;
+/// let m = [2., 1., 1.,  1., 3., 2.,  1., 0., 0.];
+/// let x = lu_solve::<f64, 9, 3>(&m, &[4., 5., 6.]).unwrap();
+/// assert!( length(&sub(x, &[6., 15., -23.], 1.)) < 1E-12 );
+/// assert_eq!( lu_solve::<f64, 4, 2>(&[1., 2., 2., 4.], &[1., 1.]), None );
+/// ```
+pub fn lu_solve<V: Float, const D2: usize, const D: usize>(
+    m: &[V; D2],
+    b: &[V; D],
+) -> Option<[V; D]> {
+    let (lu, perm, _) = lu_decompose::<V, D2, D>(m);
+    if (0..D).any(|i| lu[i * D + i] == V::zero()) {
+        None
+    } else {
+        Some(lu_substitute(&lu, &perm, b))
+    }
+}
+
+//fp determinant
+/// Find the determinant of a D-by-D matrix, using its LU
+/// decomposition
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::{determinant, determinant3};
+/// let m = [1., 3., 2., 0., 2., 3., -1., 2., 3.];
+/// assert!( (determinant::<f64, 9, 3>(&m) - determinant3(&m)).abs() < 1E-12 );
+/// ```
+pub fn determinant<V: Float, const D2: usize, const D: usize>(m: &[V; D2]) -> V {
+    let (lu, _, sign) = lu_decompose::<V, D2, D>(m);
+    (0..D).fold(sign, |acc, i| acc * lu[i * D + i])
+}
+
+//fp inverse
+/// Find the inverse of a D-by-D matrix, using its LU decomposition
+///
+/// If the matrix is singular then the result is all zeros, as with
+/// [inverse4]
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::vector::{length, sub};
+/// use geo_nd::matrix::{identity, inverse, multiply};
+/// let i = identity::<f64, 36, 6>();
+/// let mut a = i;
+/// for (n, e) in a.iter_mut().enumerate() { *e += ((n * 7) % 5) as f64 * 0.1; }
+/// let a_inv = inverse::<f64, 36, 6>(&a);
+/// assert!( length(&sub(multiply::<f64, 36, 36, 36, 6, 6, 6>(&a_inv, &a), &i, 1.)) < 1E-12 );
+/// ```
+pub fn inverse<V: Float, const D2: usize, const D: usize>(m: &[V; D2]) -> [V; D2] {
+    try_inverse::<V, D2, D>(m, V::epsilon()).unwrap_or([V::zero(); D2])
+}
+
+//fp try_inverse
+/// Find the inverse of a D-by-D matrix, using its LU decomposition,
+/// or return an error if the magnitude of its determinant is no more
+/// than `epsilon`
+pub fn try_inverse<V: Float, const D2: usize, const D: usize>(
+    m: &[V; D2],
+    epsilon: V,
+) -> Result<[V; D2], SingularMatrix<V>> {
+    let (lu, perm, sign) = lu_decompose::<V, D2, D>(m);
+    let d = (0..D).fold(sign, |acc, i| acc * lu[i * D + i]);
+    if V::abs(d) <= epsilon {
+        return Err(SingularMatrix { determinant: d });
+    }
+    let mut r = [V::zero(); D2];
+    for c in 0..D {
+        let mut e = [V::zero(); D];
+        e[c] = V::one();
+        let x = lu_substitute(&lu, &perm, &e);
+        for (i, x) in x.iter().enumerate() {
+            r[i * D + c] = *x;
+        }
+    }
+    Ok(r)
+}
+
+//fp symmetric_eigen
+/// Find the eigenvalues and eigenvectors of a symmetric D-by-D
+/// matrix, using the cyclic Jacobi method
+///
+/// The result is the eigenvalues, in decreasing order, and a matrix
+/// whose columns are the corresponding (orthonormal) eigenvectors.
+/// Hence if the result is (l, e) then m = e . diag(l) . transpose(e)
+///
+/// Only the upper triangle of the matrix is used.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::symmetric_eigen;
+/// let (l, e) = symmetric_eigen::<f64, 4, 2>(&[2., 1., 1., 2.]);
+/// assert!( (l[0] - 3.).abs() < 1E-12 && (l[1] - 1.).abs() < 1E-12 );
+/// // Eigenvector for 3 is (1,1)/sqrt(2)
+/// assert!( (e[0] - e[2]).abs() < 1E-12 );
+/// assert!( (e[0].abs() - 0.5_f64.sqrt()).abs() < 1E-12 );
+/// ```
+pub fn symmetric_eigen<V: Float, const D2: usize, const D: usize>(
+    m: &[V; D2],
+) -> ([V; D], [V; D2]) {
+    assert_eq!(D2, D * D);
+    let mut a = *m;
+    for r in 0..D {
+        for c in 0..r {
+            a[r * D + c] = a[c * D + r];
+        }
+    }
+    let mut v = identity::<V, D2, D>();
+    let two = V::one() + V::one();
+    let total = a.iter().fold(V::zero(), |acc, x| acc + *x * *x);
+    for _ in 0..64 {
+        let mut off = V::zero();
+        for p in 0..D {
+            for q in p + 1..D {
+                off += a[p * D + q] * a[p * D + q];
+            }
+        }
+        if off <= total * V::epsilon() * V::epsilon() {
+            break;
+        }
+        for p in 0..D {
+            for q in p + 1..D {
+                let apq = a[p * D + q];
+                if apq == V::zero() {
+                    continue;
+                }
+                let theta = (a[q * D + q] - a[p * D + p]) / (two * apq);
+                let t = V::one() / (theta.abs() + (theta * theta + V::one()).sqrt());
+                let t = if theta < V::zero() { -t } else { t };
+                let c = V::one() / (t * t + V::one()).sqrt();
+                let s = t * c;
+                for k in 0..D {
+                    let akp = a[k * D + p];
+                    let akq = a[k * D + q];
+                    a[k * D + p] = c * akp - s * akq;
+                    a[k * D + q] = s * akp + c * akq;
+                }
+                for k in 0..D {
+                    let apk = a[p * D + k];
+                    let aqk = a[q * D + k];
+                    a[p * D + k] = c * apk - s * aqk;
+                    a[q * D + k] = s * apk + c * aqk;
+                }
+                for k in 0..D {
+                    let vkp = v[k * D + p];
+                    let vkq = v[k * D + q];
+                    v[k * D + p] = c * vkp - s * vkq;
+                    v[k * D + q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let mut order = [0; D];
+    for (i, o) in order.iter_mut().enumerate() {
+        *o = i;
+    }
+    order.sort_by(|x, y| {
+        a[*y * D + *y]
+            .partial_cmp(&a[*x * D + *x])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut l = [V::zero(); D];
+    let mut e = [V::zero(); D2];
+    for (i, o) in order.iter().enumerate() {
+        l[i] = a[*o * D + *o];
+        for k in 0..D {
+            e[k * D + i] = v[k * D + *o];
+        }
+    }
+    (l, e)
+}
+
+//fp symmetric_eigen2
+/// Find the eigenvalues and eigenvectors of a symmetric 2-by-2
+/// matrix; see [symmetric_eigen]
+pub fn symmetric_eigen2<V: Float>(m: &[V; 4]) -> ([V; 2], [V; 4]) {
+    symmetric_eigen::<V, 4, 2>(m)
+}
+
+//fp symmetric_eigen3
+/// Find the eigenvalues and eigenvectors of a symmetric 3-by-3
+/// matrix; see [symmetric_eigen]
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::{multiply3, symmetric_eigen3, transpose};
+/// let m = [4., 1., 2.,  1., 3., 0.,  2., 0., 5.];
+/// let (l, e) = symmetric_eigen3(&m);
+/// let d = [l[0], 0., 0.,  0., l[1], 0.,  0., 0., l[2]];
+/// let m2 = multiply3(&multiply3(&e, &d), &transpose::<f64, 9, 3, 3>(e));
+/// for i in 0..9 {
+///     assert!( (m[i] - m2[i]).abs() < 1E-12 );
+/// }
+/// ```
+pub fn symmetric_eigen3<V: Float>(m: &[V; 9]) -> ([V; 3], [V; 9]) {
+    symmetric_eigen::<V, 9, 3>(m)
+}
+
+//fp symmetric_eigen4
+/// Find the eigenvalues and eigenvectors of a symmetric 4-by-4
+/// matrix; see [symmetric_eigen]
+pub fn symmetric_eigen4<V: Float>(m: &[V; 16]) -> ([V; 4], [V; 16]) {
+    symmetric_eigen::<V, 16, 4>(m)
+}
+
+//fp svd3
+/// Find the singular value decomposition of a 3-by-3 matrix; see
+/// [matrixr_op::svd]
+///
+/// The result is (U, s, V), with U and V orthogonal and s the
+/// singular values in decreasing order, such that the matrix is
+/// U . diag(s) . transpose(V)
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::{multiply3, svd3, transpose};
+/// let m = [1., 3., 2.,  0., 2., 3.,  -1., 2., 3.];
+/// let (u, s, v) = svd3(&m);
+/// let d = [s[0], 0., 0.,  0., s[1], 0.,  0., 0., s[2]];
+/// let m2 = multiply3(&multiply3(&u, &d), &transpose::<f64, 9, 3, 3>(v));
+/// for i in 0..9 {
+///     assert!( (m[i] - m2[i]).abs() < 1E-12 );
+/// }
+/// ```
+pub fn svd3<V: Float>(m: &[V; 9]) -> ([V; 9], [V; 3], [V; 9]) {
+    matrixr_op::svd::<V, 9, 9, 3, 3>(m)
+}
+
+//fp nearest_rotation3
+/// Find the rotation matrix that is nearest to a 3-by-3 matrix (in
+/// the Frobenius norm), such as to re-orthonormalize a rotation
+/// matrix that has drifted
+///
+/// This uses the singular value decomposition U . diag(s) .
+/// transpose(V) of the matrix, with the result being U .
+/// transpose(V) - with the sign of its last column flipped if
+/// required to make it a rotation rather than a reflection
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::{determinant3, nearest_rotation3};
+/// let r = nearest_rotation3(&[1.01_f64, 0.02, 0.,  -0.01, 0.99, 0.,  0., 0., 1.]);
+/// assert!( (determinant3(&r) - 1.).abs() < 1E-12 );
+/// assert!( (r[0] - r[4]).abs() < 1E-12 );
+/// assert!( (r[1] + r[3]).abs() < 1E-12 );
+/// ```
+pub fn nearest_rotation3<V: Float>(m: &[V; 9]) -> [V; 9] {
+    let (mut u, _, v) = svd3(m);
+    let vt = matrixr_op::transpose::<V, 9, 3, 3>(v);
+    if determinant3(&u) * determinant3(&v) < V::zero() {
+        u[2] = -u[2];
+        u[5] = -u[5];
+        u[8] = -u[8];
+    }
+    multiply3(&u, &vt)
 }
 
 //fp multiply2
@@ -286,6 +704,102 @@ pub fn look_at3<V: Float>(dirn: &[V; 3], up: &[V; 3]) -> [V; 9] {
     ]
 }
 
+//fi axis_rotation3
+/// Create a 3-by-3 matrix of a rotation about the X, Y or Z axis (0,
+/// 1 or 2)
+fn axis_rotation3<V: Float>(axis: usize, angle: V) -> [V; 9] {
+    let (s, c) = angle.sin_cos();
+    let mut r = identity3();
+    let a = (axis + 1) % 3;
+    let b = (axis + 2) % 3;
+    r[a * 3 + a] = c;
+    r[a * 3 + b] = -s;
+    r[b * 3 + a] = s;
+    r[b * 3 + b] = c;
+    r
+}
+
+//fp of_euler3
+/// Create a 3-by-3 rotation matrix from Euler angles applied in the
+/// given order
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{matrix, EulerAxes, EulerOrder};
+/// let order = EulerOrder::intrinsic(EulerAxes::Zyx);
+/// let m = matrix::of_euler3(order, &[0.3_f64, -0.2, 1.1]);
+/// let angles = matrix::to_euler3(order, &m);
+/// assert!((angles[0] - 0.3).abs() < 1E-12);
+/// assert!((angles[1] + 0.2).abs() < 1E-12);
+/// assert!((angles[2] - 1.1).abs() < 1E-12);
+/// ```
+#[must_use]
+pub fn of_euler3<V: Float>(order: EulerOrder, angles: &[V; 3]) -> [V; 9] {
+    let axes = order.axes.indices();
+    let r0 = axis_rotation3(axes[0], angles[0]);
+    let r1 = axis_rotation3(axes[1], angles[1]);
+    let r2 = axis_rotation3(axes[2], angles[2]);
+    if order.intrinsic {
+        multiply3(&multiply3(&r0, &r1), &r2)
+    } else {
+        multiply3(&multiply3(&r2, &r1), &r0)
+    }
+}
+
+//fp to_euler3
+/// Find the Euler angles, applied in the given order, of a 3-by-3
+/// rotation matrix
+///
+/// The middle angle is in the range -PI/2 to PI/2 for Tait-Bryan
+/// angles, and 0 to PI for proper Euler angles; the others are in
+/// the range -PI to PI. When the first and last rotations are about
+/// the same axis (gimbal lock) the last angle is zero.
+#[must_use]
+pub fn to_euler3<V: Float>(order: EulerOrder, m: &[V; 9]) -> [V; 3] {
+    if !order.intrinsic {
+        let [a2, a1, a0] = to_euler3(order.reversed(), m);
+        return [a0, a1, a2];
+    }
+    let [i, j, k] = order.axes.indices();
+    let r = |a: usize, b: usize| m[a * 3 + b];
+    let gimbal = V::epsilon() * V::int(16);
+    if i == k {
+        // Proper Euler angles; 'k' is the axis not used
+        let k = 3 - i - j;
+        let s = if (j + 3 - i) % 3 == 1 {
+            V::one()
+        } else {
+            -V::one()
+        };
+        let sin_b = (r(i, j) * r(i, j) + r(i, k) * r(i, k)).sqrt();
+        let b = sin_b.atan2(r(i, i));
+        if sin_b > gimbal {
+            let a = r(j, i).atan2(-s * r(k, i));
+            let c = r(i, j).atan2(s * r(i, k));
+            [a, b, c]
+        } else {
+            [(s * r(k, j)).atan2(r(j, j)), b, V::zero()]
+        }
+    } else {
+        // Tait-Bryan angles
+        let s = if (j + 3 - i) % 3 == 1 {
+            V::one()
+        } else {
+            -V::one()
+        };
+        let cos_b = (r(i, i) * r(i, i) + r(i, j) * r(i, j)).sqrt();
+        let b = (s * r(i, k)).atan2(cos_b);
+        if cos_b > gimbal {
+            let a = (-s * r(j, k)).atan2(r(k, k));
+            let c = (-s * r(i, j)).atan2(r(i, i));
+            [a, b, c]
+        } else {
+            [(s * r(k, j)).atan2(r(j, j)), b, V::zero()]
+        }
+    }
+}
+
 //fp multiply4
 /// Multiply two square 4x4 matrices and produce a result
 pub fn multiply4<V: Float>(a: &[V; 16], b: &[V; 16]) -> [V; 16] {
@@ -344,8 +858,167 @@ pub fn look_at4<V: Float>(eye: &[V; 3], centre: &[V; 3], up: &[V; 3]) -> [V; 16]
     ]
 }
 
+//fi projection4
+/// Create a projection matrix with the given X and Y scaling and
+/// offsets, and depth terms, for the clip space
+///
+/// If `perspective` then w is the distance in front of the viewer,
+/// and the X and Y offsets and depth offset are scaled by it; else w
+/// is one.
+#[allow(clippy::too_many_arguments)]
+fn projection4<V: Float>(
+    clip: ClipSpace,
+    perspective: bool,
+    x_scale: V,
+    x_offset: V,
+    y_scale: V,
+    y_offset: V,
+    z_scale: V,
+    z_offset: V,
+) -> [V; 16] {
+    let forward: V = clip.handedness.forward();
+    let mut r = [V::zero(); 16];
+    r[0] = x_scale;
+    r[5] = y_scale;
+    r[10] = z_scale * forward;
+    r[11] = z_offset;
+    if perspective {
+        r[2] = -x_offset * forward;
+        r[6] = -y_offset * forward;
+        r[14] = forward;
+    } else {
+        r[3] = -x_offset;
+        r[7] = -y_offset;
+        r[15] = V::one();
+    }
+    r
+}
+
+//fp frustum4
+/// Create a perspective projection matrix for a view frustum, whose
+/// near plane (at distance `near` in front of the viewer) spans
+/// `left` to `right` and `bottom` to `top`, and whose far plane is at
+/// distance `far` in front of the viewer
+///
+/// The matrix is row-major, to be applied to column vectors (as with
+/// [crate::SqMatrix::transform]); for the OpenGL clip space it is
+/// the matrix of glFrustum
+#[must_use]
+pub fn frustum4<V: Float>(
+    clip: ClipSpace,
+    left: V,
+    right: V,
+    bottom: V,
+    top: V,
+    near: V,
+    far: V,
+) -> [V; 16] {
+    let (z_near, z_far): (V, V) = clip.depth.near_far();
+    let two = V::frac(2, 1);
+    let depth = far - near;
+    projection4(
+        clip,
+        true,
+        two * near / (right - left),
+        (right + left) / (right - left),
+        two * near / (top - bottom),
+        (top + bottom) / (top - bottom),
+        (z_far * far - z_near * near) / depth,
+        (z_near - z_far) * near * far / depth,
+    )
+}
+
+//fp orthographic4
+/// Create an orthographic projection matrix for a view box spanning
+/// `left` to `right` and `bottom` to `top`, from `near` to `far` in
+/// front of the viewer
+///
+/// The matrix is row-major, to be applied to column vectors (as with
+/// [crate::SqMatrix::transform]); for the OpenGL clip space it is
+/// the matrix of glOrtho
+#[must_use]
+pub fn orthographic4<V: Float>(
+    clip: ClipSpace,
+    left: V,
+    right: V,
+    bottom: V,
+    top: V,
+    near: V,
+    far: V,
+) -> [V; 16] {
+    let (z_near, z_far): (V, V) = clip.depth.near_far();
+    let two = V::frac(2, 1);
+    let depth = far - near;
+    projection4(
+        clip,
+        false,
+        two / (right - left),
+        (right + left) / (right - left),
+        two / (top - bottom),
+        (top + bottom) / (top - bottom),
+        (z_far - z_near) / depth,
+        (z_near * far - z_far * near) / depth,
+    )
+}
+
+//fp perspective_fov4
+/// Create a symmetric perspective projection matrix with a vertical
+/// field of view `fov` (in radians) and an aspect ratio (width over
+/// height), with near and far planes at distances `near` and `far`
+/// in front of the viewer
+///
+/// The matrix is row-major, to be applied to column vectors (as with
+/// [crate::SqMatrix::transform]); for the OpenGL clip space it is
+/// the matrix of gluPerspective
+#[must_use]
+pub fn perspective_fov4<V: Float>(clip: ClipSpace, fov: V, aspect: V, near: V, far: V) -> [V; 16] {
+    let (z_near, z_far): (V, V) = clip.depth.near_far();
+    let f = V::one() / (fov / V::frac(2, 1)).tan();
+    let depth = far - near;
+    projection4(
+        clip,
+        true,
+        f / aspect,
+        V::zero(),
+        f,
+        V::zero(),
+        (z_far * far - z_near * near) / depth,
+        (z_near - z_far) * near * far / depth,
+    )
+}
+
+//fp infinite_perspective4
+/// Create a symmetric perspective projection matrix with a vertical
+/// field of view `fov` (in radians) and an aspect ratio (width over
+/// height), with the near plane at distance `near` in front of the
+/// viewer and the far plane at infinity
+///
+/// This is the limit of [perspective_fov4] as the far distance tends
+/// to infinity; it is commonly used with a reversed-Z depth range
+#[must_use]
+pub fn infinite_perspective4<V: Float>(clip: ClipSpace, fov: V, aspect: V, near: V) -> [V; 16] {
+    let (z_near, z_far): (V, V) = clip.depth.near_far();
+    let f = V::one() / (fov / V::frac(2, 1)).tan();
+    projection4(
+        clip,
+        true,
+        f / aspect,
+        V::zero(),
+        f,
+        V::zero(),
+        z_far,
+        (z_near - z_far) * near,
+    )
+}
+
 //fp perspective4
-/// Create a perspective graphics matrix
+/// Create a perspective graphics matrix for the OpenGL clip space
+///
+/// This is the transpose of the other projection matrices, which may
+/// be passed to OpenGL unchanged (as a column-major matrix); for the
+/// row-major form, to be used with [crate::SqMatrix::transform], use
+/// [perspective_fov4] with [ClipSpace::opengl]
+#[must_use]
 pub fn perspective4<V: Float>(fov: V, aspect: V, near: V, far: V) -> [V; 16] {
     let mut r = [V::zero(); 16];
     let two = V::from(2).unwrap();
@@ -359,6 +1032,172 @@ pub fn perspective4<V: Float>(fov: V, aspect: V, near: V, far: V) -> [V; 16] {
     r
 }
 
+//fi ndc_to_window
+/// Map normalized device coordinates to window coordinates for a
+/// viewport (x, y, width, height), with the window depth from 0 to 1
+fn ndc_to_window<V: Float>(ndc: &[V; 3], viewport: &[V; 4], depth: DepthRange) -> [V; 3] {
+    let half = V::frac(1, 2);
+    let z = match depth {
+        DepthRange::NegOneToOne => (ndc[2] + V::one()) * half,
+        _ => ndc[2],
+    };
+    [
+        viewport[0] + viewport[2] * (ndc[0] + V::one()) * half,
+        viewport[1] + viewport[3] * (ndc[1] + V::one()) * half,
+        z,
+    ]
+}
+
+//fi window_to_ndc
+/// Map window coordinates for a viewport (x, y, width, height), with
+/// the window depth from 0 to 1, to normalized device coordinates
+fn window_to_ndc<V: Float>(win: &[V; 3], viewport: &[V; 4], depth: DepthRange) -> [V; 3] {
+    let two = V::frac(2, 1);
+    let z = match depth {
+        DepthRange::NegOneToOne => win[2] * two - V::one(),
+        _ => win[2],
+    };
+    [
+        (win[0] - viewport[0]) * two / viewport[2] - V::one(),
+        (win[1] - viewport[1]) * two / viewport[3] - V::one(),
+        z,
+    ]
+}
+
+//fi transform_point4
+/// Apply a 4-by-4 matrix to a point and divide by the resulting w;
+/// returns None if w is zero
+fn transform_point4<V: Float>(m: &[V; 16], p: &[V; 3]) -> Option<[V; 3]> {
+    let h = transform_vec4(m, &[p[0], p[1], p[2], V::one()]);
+    if h[3] == V::zero() {
+        None
+    } else {
+        Some([h[0] / h[3], h[1] / h[3], h[2] / h[3]])
+    }
+}
+
+//fp project_mvp
+/// Map an object-space point to window coordinates, given the
+/// combined model-view-projection matrix and a viewport (x, y, width,
+/// height); see [project]
+///
+/// Returns None if the point is on the plane of the viewer
+#[must_use]
+pub fn project_mvp<V: Float>(
+    obj: &[V; 3],
+    mvp: &[V; 16],
+    viewport: &[V; 4],
+    depth: DepthRange,
+) -> Option<[V; 3]> {
+    let ndc = transform_point4(mvp, obj)?;
+    Some(ndc_to_window(&ndc, viewport, depth))
+}
+
+//fp project
+/// Map an object-space point to window coordinates, given the model
+/// (or model-view) matrix, the projection matrix, and a viewport (x,
+/// y, width, height), as gluProject does
+///
+/// The window depth is from 0 at the near plane to 1 at the far
+/// plane (or the reverse for [DepthRange::OneToZero]); the depth
+/// range is that of the projection matrix.
+///
+/// Returns None if the point is on the plane of the viewer, where it
+/// has no window coordinates.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{matrix, ClipSpace, DepthRange};
+/// let proj = matrix::perspective_fov4(ClipSpace::vulkan(), 1.0_f64, 2.0, 0.5, 10.0);
+/// let model = matrix::identity4();
+/// let viewport = [0., 0., 800., 400.];
+/// let win = matrix::project(&[0., 0., -0.5], &model, &proj, &viewport, DepthRange::ZeroToOne).unwrap();
+/// assert!((win[0] - 400.).abs() < 1E-9);
+/// assert!((win[1] - 200.).abs() < 1E-9);
+/// assert!(win[2].abs() < 1E-9);
+/// let obj = matrix::unproject(&win, &model, &proj, &viewport, DepthRange::ZeroToOne).unwrap();
+/// assert!((obj[2] + 0.5).abs() < 1E-9);
+/// ```
+#[must_use]
+pub fn project<V: Float>(
+    obj: &[V; 3],
+    model: &[V; 16],
+    proj: &[V; 16],
+    viewport: &[V; 4],
+    depth: DepthRange,
+) -> Option<[V; 3]> {
+    project_mvp(obj, &multiply4(proj, model), viewport, depth)
+}
+
+//fp unproject_mvp
+/// Map window coordinates to an object-space point, given the
+/// combined model-view-projection matrix and a viewport (x, y, width,
+/// height); see [unproject]
+///
+/// Returns None if the matrix is singular, or if the point is at
+/// infinity
+#[must_use]
+pub fn unproject_mvp<V: Float>(
+    win: &[V; 3],
+    mvp: &[V; 16],
+    viewport: &[V; 4],
+    depth: DepthRange,
+) -> Option<[V; 3]> {
+    let inverse = try_inverse4(mvp, V::zero()).ok()?;
+    transform_point4(&inverse, &window_to_ndc(win, viewport, depth))
+}
+
+//fp unproject
+/// Map window coordinates to an object-space point, given the model
+/// (or model-view) matrix, the projection matrix, and a viewport (x,
+/// y, width, height), as gluUnProject does; this is the inverse of
+/// [project]
+///
+/// Returns None if the matrices are singular, or if the point is at
+/// infinity (such as on the far plane of an infinite perspective)
+#[must_use]
+pub fn unproject<V: Float>(
+    win: &[V; 3],
+    model: &[V; 16],
+    proj: &[V; 16],
+    viewport: &[V; 4],
+    depth: DepthRange,
+) -> Option<[V; 3]> {
+    unproject_mvp(win, &multiply4(proj, model), viewport, depth)
+}
+
+//fp pick_ray
+/// Find the ray through a point given in normalized device
+/// coordinates (x and y from -1 to 1), given the inverse of the
+/// view-projection matrix, for picking
+///
+/// The result is the origin of the ray, on the near plane, and the
+/// unit direction of the ray away from the viewer (toward the far
+/// plane, which may be at infinity). For window coordinates in a
+/// viewport (x, y, width, height) the normalized device coordinates
+/// are `2 * (x - viewport[0]) / viewport[2] - 1` and similarly for y.
+#[must_use]
+pub fn pick_ray<V: Float>(
+    x: V,
+    y: V,
+    view_proj_inverse: &[V; 16],
+    depth: DepthRange,
+) -> ([V; 3], [V; 3]) {
+    let (z_near, z_far): (V, V) = depth.near_far();
+    let near = transform_vec4(view_proj_inverse, &[x, y, z_near, V::one()]);
+    let far = transform_vec4(view_proj_inverse, &[x, y, z_far, V::one()]);
+    let origin = [near[0] / near[3], near[1] / near[3], near[2] / near[3]];
+    // far / far.w - near / near.w, scaled by near.w * far.w (which is
+    // nonnegative), so that a far point at infinity is handled
+    let direction = [
+        far[0] * near[3] - near[0] * far[3],
+        far[1] * near[3] - near[1] * far[3],
+        far[2] * near[3] - near[2] * far[3],
+    ];
+    (origin, vector_op::normalize(direction))
+}
+
 //fp from_quat4
 /// Create a rotation 4-by-4 matrix from a quaternion
 pub fn from_quat4<V: Float>(q: [V; 4]) -> [V; 16] {
@@ -391,6 +1230,147 @@ pub fn from_quat4<V: Float>(q: [V; 4]) -> [V; 16] {
     r
 }
 
+//fp decompose4
+/// Decompose a 4-by-4 matrix into its perspective row, translation,
+/// rotation (as a unit quaternion), scaling along each axis and shear,
+/// such that the matrix is P . T . R . S . H (see
+/// [MatrixDecomposition])
+///
+/// This is the 'unmatrix' of Graphics Gems II, for matrices that
+/// apply to column vectors; the rotation and scaling are found by
+/// Gram-Schmidt orthogonalization of the first three columns, and the
+/// perspective row is the last row of the matrix times the inverse of
+/// its affine part, which is found from the same decomposition (so
+/// that it is accurate whatever the scale of the matrix).
+///
+/// Returns None if the top-left 3-by-3 matrix is singular
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix;
+/// let m = [2., 0., 0., 1., 0., 0., -3., 2., 0., 4., 0., 3., 0., 0., 0., 1.];
+/// let d = matrix::decompose4(&m).unwrap();
+/// assert_eq!(d.translation, [1., 2., 3.]);
+/// assert!((d.scale[0] - 2.0_f64).abs() < 1E-12);
+/// assert!((d.scale[1] - 4.0_f64).abs() < 1E-12);
+/// assert!((d.scale[2] - 3.0_f64).abs() < 1E-12);
+/// let m2 = matrix::compose4(&d);
+/// for i in 0..16 {
+///     assert!((m[i] - m2[i]).abs() < 1E-12);
+/// }
+/// ```
+#[must_use]
+pub fn decompose4<V: Float>(m: &[V; 16]) -> Option<MatrixDecomposition<[V; 3], [V; 4], [V; 4]>> {
+    let column = |c: usize| [m[c], m[4 + c], m[8 + c]];
+    let (c0, c1, c2) = (column(0), column(1), column(2));
+    let size = vector_op::length(&c0)
+        .max(vector_op::length(&c1))
+        .max(vector_op::length(&c2));
+    let singular = size * V::epsilon() * V::int(16);
+
+    // Gram-Schmidt orthonormalization of the columns, which is a QR
+    // decomposition with R the scaling times the shear
+    let mut sx = vector_op::length(&c0);
+    if sx <= singular {
+        return None;
+    }
+    let mut u0 = vector_op::reduce(c0, sx);
+    let mut sxy = vector_op::dot(&u0, &c1);
+    let c1 = vector_op::sub(c1, &u0, sxy);
+    let sy = vector_op::length(&c1);
+    if sy <= singular {
+        return None;
+    }
+    let u1 = vector_op::reduce(c1, sy);
+    let mut sxz = vector_op::dot(&u0, &c2);
+    let syz = vector_op::dot(&u1, &c2);
+    let c2 = vector_op::sub(vector_op::sub(c2, &u0, sxz), &u1, syz);
+    let sz = vector_op::length(&c2);
+    if sz <= singular {
+        return None;
+    }
+    let u2 = vector_op::reduce(c2, sz);
+
+    // Make the rotation proper by flipping the X axis
+    if vector_op::dot(&u0, &vector_op::cross_product3(&u1, &u2)) < V::zero() {
+        u0 = vector_op::scale(u0, -V::one());
+        sx = -sx;
+        sxy = -sxy;
+        sxz = -sxz;
+    }
+    let r = [
+        u0[0], u1[0], u2[0], u0[1], u1[1], u2[1], u0[2], u1[2], u2[2],
+    ];
+
+    // The perspective row p satisfies p . A = last row of m, where A
+    // is the affine part of m; its top-left 3-by-3 matrix is Q . U,
+    // with Q the rotation and U upper triangular, so p is found by
+    // forward substitution through U and then multiplying by Q^T
+    let y0 = m[12] / sx;
+    let y1 = (m[13] - y0 * sxy) / sy;
+    let y2 = (m[14] - y0 * sxz - y1 * syz) / sz;
+    let p = vector_op::add(vector_op::add(vector_op::scale(u0, y0), &u1, y1), &u2, y2);
+    let pw = m[15] - vector_op::dot(&p, &[m[3], m[7], m[11]]);
+    let perspective = [p[0], p[1], p[2], pw];
+
+    Some(MatrixDecomposition {
+        translation: [m[3], m[7], m[11]],
+        rotation: quaternion_op::of_rotation(&r),
+        scale: [sx, sy, sz],
+        shear: [sxy / sx, sxz / sx, syz / sy],
+        perspective,
+    })
+}
+
+//fp compose4
+/// Compose a 4-by-4 matrix from its perspective row, translation,
+/// rotation, scaling along each axis and shear, as P . T . R . S . H
+/// (see [MatrixDecomposition]); this is the inverse of [decompose4]
+#[must_use]
+pub fn compose4<V: Float>(d: &MatrixDecomposition<[V; 3], [V; 4], [V; 4]>) -> [V; 16] {
+    let [sx, sy, sz] = d.scale;
+    let [xy, xz, yz] = d.shear;
+    let scale_shear = [
+        sx,
+        sx * xy,
+        sx * xz,
+        V::zero(),
+        sy,
+        sy * yz,
+        V::zero(),
+        V::zero(),
+        sz,
+    ];
+    let mut r = [V::zero(); 9];
+    quaternion_op::to_rotation3(&d.rotation, &mut r);
+    let a = multiply3(&r, &scale_shear);
+    let t = d.translation;
+    let mut m = [
+        a[0],
+        a[1],
+        a[2],
+        t[0],
+        a[3],
+        a[4],
+        a[5],
+        t[1],
+        a[6],
+        a[7],
+        a[8],
+        t[2],
+        V::zero(),
+        V::zero(),
+        V::zero(),
+        V::one(),
+    ];
+    let p = d.perspective;
+    for c in 0..4 {
+        m[12 + c] = p[0] * m[c] + p[1] * m[4 + c] + p[2] * m[8 + c] + p[3] * m[12 + c];
+    }
+    m
+}
+
 /*
    #f invert
    @staticmethod
@@ -168,6 +168,231 @@ pub fn transform_vec<V: Float, const RD: usize, const R: usize, const D: usize>(
     multiply::<V, RD, D, R, R, D, 1>(m, v)
 }
 
+//a Singular value decomposition
+//mp svd_dyn
+/// Find the singular value decomposition of an r-by-c matrix (with
+/// r >= c), dynamically sized, using the one-sided Jacobi method
+///
+/// On entry `a` is the r-by-c matrix; on exit it is U, an r-by-c
+/// matrix with orthonormal columns, `v` is V, a c-by-c orthogonal
+/// matrix, and `s` the c (non-negative) singular values in decreasing
+/// order, such that the matrix is U . diag(s) . transpose(V)
+///
+/// The matrices are row-major slices that may be longer than
+/// required; this is the implementation of [svd] and
+/// [pseudo_inverse], for sizes that are not known at compile time
+pub fn svd_dyn<V: Float>(r: usize, c: usize, a: &mut [V], v: &mut [V], s: &mut [V]) {
+    assert!(r >= c);
+    assert!(a.len() >= r * c);
+    assert!(v.len() >= c * c);
+    assert!(s.len() >= c);
+    let two = V::one() + V::one();
+    for (i, v) in v[0..c * c].iter_mut().enumerate() {
+        *v = if i % (c + 1) == 0 {
+            V::one()
+        } else {
+            V::zero()
+        };
+    }
+
+    // Rotate pairs of columns until they are all orthogonal
+    for _ in 0..64 {
+        let mut rotated = false;
+        for p in 0..c {
+            for q in p + 1..c {
+                let mut alpha = V::zero();
+                let mut beta = V::zero();
+                let mut gamma = V::zero();
+                for k in 0..r {
+                    alpha += a[k * c + p] * a[k * c + p];
+                    beta += a[k * c + q] * a[k * c + q];
+                    gamma += a[k * c + p] * a[k * c + q];
+                }
+                if gamma == V::zero() || gamma.abs() <= V::epsilon() * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+                let zeta = (beta - alpha) / (two * gamma);
+                let t = V::one() / (zeta.abs() + (zeta * zeta + V::one()).sqrt());
+                let t = if zeta < V::zero() { -t } else { t };
+                let cs = V::one() / (t * t + V::one()).sqrt();
+                let sn = cs * t;
+                for k in 0..r {
+                    let akp = a[k * c + p];
+                    let akq = a[k * c + q];
+                    a[k * c + p] = cs * akp - sn * akq;
+                    a[k * c + q] = sn * akp + cs * akq;
+                }
+                for k in 0..c {
+                    let vkp = v[k * c + p];
+                    let vkq = v[k * c + q];
+                    v[k * c + p] = cs * vkp - sn * vkq;
+                    v[k * c + q] = sn * vkp + cs * vkq;
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    // Sort the columns by decreasing singular value
+    for i in 0..c {
+        s[i] = (0..r)
+            .fold(V::zero(), |acc, k| acc + a[k * c + i] * a[k * c + i])
+            .sqrt();
+    }
+    for i in 0..c {
+        let mut m = i;
+        for j in i + 1..c {
+            if s[j] > s[m] {
+                m = j;
+            }
+        }
+        if m != i {
+            s.swap(i, m);
+            for k in 0..r {
+                a.swap(k * c + i, k * c + m);
+            }
+            for k in 0..c {
+                v.swap(k * c + i, k * c + m);
+            }
+        }
+    }
+
+    // Normalize the columns to form U; columns with a (nearly) zero
+    // singular value are replaced with unit vectors orthogonal to the
+    // rest, so that U has orthonormal columns
+    let tiny = s[0] * V::epsilon() * V::from(r).unwrap();
+    for i in 0..c {
+        if s[i] > tiny && s[i] > V::zero() {
+            for k in 0..r {
+                a[k * c + i] /= s[i];
+            }
+            continue;
+        }
+        let mut best = (V::zero(), 0);
+        for e in 0..r {
+            // Length of the component of basis vector e orthogonal to the columns so far
+            let mut l = V::one();
+            for j in 0..i {
+                l -= a[e * c + j] * a[e * c + j];
+            }
+            if l > best.0 {
+                best = (l, e);
+            }
+        }
+        let e = best.1;
+        for k in 0..r {
+            a[k * c + i] = if k == e { V::one() } else { V::zero() };
+        }
+        for j in 0..i {
+            let d = a[e * c + j];
+            for k in 0..r {
+                let u = a[k * c + j];
+                a[k * c + i] -= d * u;
+            }
+        }
+        let l = (0..r)
+            .fold(V::zero(), |acc, k| acc + a[k * c + i] * a[k * c + i])
+            .sqrt();
+        for k in 0..r {
+            a[k * c + i] /= l;
+        }
+    }
+}
+
+//mp svd
+/// Find the singular value decomposition of an R-by-C matrix (with
+/// R >= C, RC = R*C and CC = C*C)
+///
+/// The result is (U, s, V), where U is an R-by-C matrix with
+/// orthonormal columns, s are the C (non-negative) singular values in
+/// decreasing order, and V is a C-by-C orthogonal matrix, such that
+/// the matrix is U . diag(s) . transpose(V)
+///
+/// For a matrix with R < C, decompose its transpose.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::svd;
+/// let (u, s, v) = svd::<f64, 6, 4, 3, 2>(&[3., 0., 0., 2., 0., 0.]);
+/// assert_eq!( s, [3., 2.] );
+/// assert_eq!( u, [1., 0., 0., 1., 0., 0.] );
+/// assert_eq!( v, [1., 0., 0., 1.] );
+/// ```
+pub fn svd<V: Float, const RC: usize, const CC: usize, const R: usize, const C: usize>(
+    m: &[V; RC],
+) -> ([V; RC], [V; C], [V; CC]) {
+    assert_eq!(RC, R * C);
+    assert_eq!(CC, C * C);
+    let mut u = *m;
+    let mut s = [V::zero(); C];
+    let mut v = [V::zero(); CC];
+    svd_dyn(R, C, &mut u, &mut v, &mut s);
+    (u, s, v)
+}
+
+//mp pseudo_inverse
+/// Find the (Moore-Penrose) pseudo-inverse of an R-by-C matrix (with
+/// RC = R*C), which is a C-by-R matrix, using its singular value
+/// decomposition
+///
+/// Singular values that are no more than `epsilon` times the largest
+/// singular value are treated as zero.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::matrix::{multiply, pseudo_inverse};
+/// // A 2-by-3 matrix and its 3-by-2 pseudo-inverse
+/// let a = [1., 0., 0., 0., 2., 0.];
+/// let a_pinv = pseudo_inverse::<f64, 6, 2, 3>(&a, 1E-12);
+/// assert_eq!( a_pinv, [1., 0., 0., 0.5, 0., 0.] );
+/// assert_eq!( multiply::<f64, 6, 6, 4, 2, 3, 2>(&a, &a_pinv), [1., 0., 0., 1.] );
+/// ```
+pub fn pseudo_inverse<V: Float, const RC: usize, const R: usize, const C: usize>(
+    m: &[V; RC],
+    epsilon: V,
+) -> [V; RC] {
+    assert_eq!(RC, R * C);
+    // Decompose the tall form of the matrix, as X . diag(s) . transpose(Y)
+    //
+    // The pseudo-inverse is then Y . diag(1/s) . transpose(X) if the
+    // matrix is tall, or (as it is the transpose) X . diag(1/s) . transpose(Y)
+    //
+    // Y is n-by-n and s has n values, where n is the lesser of R and
+    // C, so they fit within arrays of RC and C values
+    let (tall, n) = if R >= C { (R, C) } else { (C, R) };
+    let mut x = if R >= C {
+        *m
+    } else {
+        transpose::<V, RC, R, C>(*m)
+    };
+    let mut y = [V::zero(); RC];
+    let mut s = [V::zero(); C];
+    svd_dyn(tall, n, &mut x, &mut y, &mut s);
+    let tiny = s[0] * epsilon;
+    let mut r = [V::zero(); RC];
+    for i in 0..C {
+        for j in 0..R {
+            let mut e = V::zero();
+            for k in 0..n {
+                if s[k] > tiny && s[k] > V::zero() {
+                    e += if R >= C {
+                        y[i * n + k] * x[j * n + k]
+                    } else {
+                        x[i * n + k] * y[j * n + k]
+                    } / s[k];
+                }
+            }
+            r[i * R + j] = e;
+        }
+    }
+    r
+}
+
 //a Formatting
 //mp fmt - format a `Matrix` for display
 /// Format the matrix for display
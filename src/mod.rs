@@ -1,12 +0,0 @@
-mod point;
-mod bezier;
-mod rectangle;
-mod polygon;
-mod transform;
-
-pub use self::transform::Transform;
-pub use self::point::{Point, Range};
-pub use self::bezier::Bezier;
-pub use self::rectangle::{Rectangle, Float4};
-pub use self::polygon::Polygon;
-
@@ -0,0 +1,157 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    projection.rs
+@brief   Clip space conventions for projections - part of geometry library
+ */
+
+//a Imports
+use crate::Float;
+
+//a DepthRange
+//tp DepthRange
+/// The range of normalized device depth coordinates that the near and
+/// far planes of a projection map to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DepthRange {
+    /// The near plane maps to -1 and the far plane to 1, as in OpenGL
+    #[default]
+    NegOneToOne,
+    /// The near plane maps to 0 and the far plane to 1, as in Vulkan,
+    /// Direct3D and Metal
+    ZeroToOne,
+    /// The near plane maps to 1 and the far plane to 0; this is
+    /// 'reversed-Z', which gives much better depth precision with a
+    /// floating-point depth buffer
+    OneToZero,
+}
+
+//ip DepthRange
+impl DepthRange {
+    //mp near_far
+    /// Get the normalized device depth coordinates of the near and far
+    /// planes
+    #[must_use]
+    pub fn near_far<V: Float>(self) -> (V, V) {
+        match self {
+            Self::NegOneToOne => (-V::one(), V::one()),
+            Self::ZeroToOne => (V::zero(), V::one()),
+            Self::OneToZero => (V::one(), V::zero()),
+        }
+    }
+}
+
+//a Handedness
+//tp Handedness
+/// The handedness of the view space of a projection
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Handedness {
+    /// The view looks down the -Z axis, with X to the right and Y up,
+    /// as in OpenGL and Vulkan (and glTF cameras)
+    #[default]
+    Right,
+    /// The view looks down the +Z axis, with X to the right and Y up,
+    /// as in Direct3D
+    Left,
+}
+
+//ip Handedness
+impl Handedness {
+    //mp forward
+    /// Get the sign of the Z coordinate of points in front of the
+    /// viewer; -1 for right-handed, +1 for left-handed
+    #[must_use]
+    pub fn forward<V: Float>(self) -> V {
+        match self {
+            Self::Right => -V::one(),
+            Self::Left => V::one(),
+        }
+    }
+}
+
+//a ClipSpace
+//tp ClipSpace
+/// The convention for the clip space that a projection matrix maps
+/// view space to
+///
+/// The default is that of OpenGL. Note that Vulkan's clip space also
+/// has Y pointing down; the projection matrices here do not flip Y,
+/// so this must be done with a negative viewport height or by
+/// negating element \[5\] of the matrix.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{matrix, ClipSpace, DepthRange};
+/// let clip = ClipSpace::vulkan().with_depth(DepthRange::OneToZero);
+/// let m: [f64; 16] = matrix::infinite_perspective4(clip, 1.0, 1.5, 0.1);
+/// // A point on the near plane has a depth of one
+/// let p = matrix::transform_vec4(&m, &[0., 0., -0.1, 1.]);
+/// assert!((p[2] / p[3] - 1.).abs() < 1E-12);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ClipSpace {
+    /// The range of normalized device depth coordinates
+    pub depth: DepthRange,
+    /// The handedness of the view space
+    pub handedness: Handedness,
+}
+
+//ip ClipSpace
+impl ClipSpace {
+    //cp new
+    /// Create a new [ClipSpace] from a depth range and handedness
+    #[must_use]
+    pub const fn new(depth: DepthRange, handedness: Handedness) -> Self {
+        Self { depth, handedness }
+    }
+
+    //cp opengl
+    /// The OpenGL clip space; right-handed, with a depth range of -1 to 1
+    #[must_use]
+    pub const fn opengl() -> Self {
+        Self::new(DepthRange::NegOneToOne, Handedness::Right)
+    }
+
+    //cp vulkan
+    /// The Vulkan clip space; right-handed, with a depth range of 0 to 1
+    #[must_use]
+    pub const fn vulkan() -> Self {
+        Self::new(DepthRange::ZeroToOne, Handedness::Right)
+    }
+
+    //cp direct3d
+    /// The (conventional) Direct3D clip space; left-handed, with a
+    /// depth range of 0 to 1
+    #[must_use]
+    pub const fn direct3d() -> Self {
+        Self::new(DepthRange::ZeroToOne, Handedness::Left)
+    }
+
+    //cp with_depth
+    /// Get this clip space with a different depth range
+    #[must_use]
+    pub const fn with_depth(mut self, depth: DepthRange) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    //cp with_handedness
+    /// Get this clip space with a different handedness
+    #[must_use]
+    pub const fn with_handedness(mut self, handedness: Handedness) -> Self {
+        self.handedness = handedness;
+        self
+    }
+}
@@ -19,17 +19,17 @@ limitations under the License.
 //a Imports
 use crate::matrix_op as matrix;
 use crate::vector_op as vector;
-use crate::{Float, Num};
+use crate::{EulerOrder, Float, Num};
 
 //a Notes on matrices of quaternions
-/// 1 - 2*j2 - 2*k2           2*i*j - 2*k*r        2*i*k + 2*j*r
-///     2*i*j + 2*k*r     1 - 2*i2 - 2*k2          2*j*k - 2*i*r
-///     2*i*k - 2*j*r         2*j*k + 2*i*r    1 - 2*i2 - 2*j2
-///
-/// m[0] + m[4] + m[9] = 3 - 4(i^2+j^2+k^2) = 3 - 4(1-r^2) = 4r^2 - 1
-/// m[7] - m[5] = 4*i*r ( if <0 then i<0 )
-/// m[2] - m[6] = 4*j*r ( if <0 then j<0 )
-/// m[3] - m[1] = 4*k*r ( if <0 then k<0 )
+// 1 - 2*j2 - 2*k2           2*i*j - 2*k*r        2*i*k + 2*j*r
+//     2*i*j + 2*k*r     1 - 2*i2 - 2*k2          2*j*k - 2*i*r
+//     2*i*k - 2*j*r         2*j*k + 2*i*r    1 - 2*i2 - 2*j2
+//
+// m[0] + m[4] + m[9] = 3 - 4(i^2+j^2+k^2) = 3 - 4(1-r^2) = 4r^2 - 1
+// m[7] - m[5] = 4*i*r ( if <0 then i<0 )
+// m[2] - m[6] = 4*j*r ( if <0 then j<0 )
+// m[3] - m[1] = 4*k*r ( if <0 then k<0 )
 
 //a Constructors and destructors
 //fp new
@@ -165,34 +165,82 @@ pub fn to_rotation4<V: Float>(q: &[V; 4], m: &mut [V; 16]) {
 
 //fp of_rotation
 /// Find the quaternion of a Matrix3 assuming it is purely a rotation
+///
+/// The largest of the four components is found from the diagonal
+/// first, and the others derived from it, so that this is accurate
+/// for rotations by any angle (including those close to 180
+/// degrees); the result has a non-negative real part
 #[must_use]
 pub fn of_rotation<V: Float>(m: &[V; 9]) -> [V; 4] {
-    fn safe_sqrt<V: Float>(x: V) -> V {
-        if x < V::zero() {
-            V::zero()
+    let one = V::one();
+    let quarter = V::frac(1, 4);
+    let trace = m[0] + m[4] + m[8];
+    let q = {
+        if trace >= m[0] && trace >= m[4] && trace >= m[8] {
+            let r = (one + trace).sqrt() * V::frac(1, 2);
+            let s = quarter / r;
+            [(m[7] - m[5]) * s, (m[2] - m[6]) * s, (m[3] - m[1]) * s, r]
+        } else if m[0] >= m[4] && m[0] >= m[8] {
+            let i = (one + m[0] - m[4] - m[8]).sqrt() * V::frac(1, 2);
+            let s = quarter / i;
+            [i, (m[1] + m[3]) * s, (m[2] + m[6]) * s, (m[7] - m[5]) * s]
+        } else if m[4] >= m[8] {
+            let j = (one - m[0] + m[4] - m[8]).sqrt() * V::frac(1, 2);
+            let s = quarter / j;
+            [(m[1] + m[3]) * s, j, (m[5] + m[7]) * s, (m[2] - m[6]) * s]
         } else {
-            x.sqrt()
+            let k = (one - m[0] - m[4] + m[8]).sqrt() * V::frac(1, 2);
+            let s = quarter / k;
+            [(m[2] + m[6]) * s, (m[5] + m[7]) * s, k, (m[3] - m[1]) * s]
         }
+    };
+    if q[3] < V::zero() {
+        [-q[0], -q[1], -q[2], -q[3]]
+    } else {
+        q
     }
-    let r = safe_sqrt(V::one() + m[0] + m[4] + m[8]) * V::frac(1, 2);
-    let mut i = safe_sqrt(V::one() + m[0] - m[4] - m[8]) * V::frac(1, 2);
-    let mut j = safe_sqrt(V::one() - m[0] + m[4] - m[8]) * V::frac(1, 2);
-    let mut k = safe_sqrt(V::one() - m[0] - m[4] + m[8]) * V::frac(1, 2);
-
-    let r_i_4 = m[7] - m[5];
-    let r_j_4 = m[2] - m[6];
-    let r_k_4 = m[3] - m[1];
-    if r_i_4 < -V::epsilon() {
-        i = -i;
-    }
-    if r_j_4 < -V::epsilon() {
-        j = -j;
-    }
-    if r_k_4 < -V::epsilon() {
-        k = -k;
+}
+
+//fp of_euler
+/// Create a quaternion from Euler angles applied in the given order
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{quat, EulerAxes, EulerOrder};
+/// // Blender's XYZ order is extrinsic
+/// let order = EulerOrder::extrinsic(EulerAxes::Xyz);
+/// let q = quat::of_euler(order, &[0.1_f64, 0.2, 0.3]);
+/// let angles = quat::to_euler_ordered(order, &q);
+/// assert!((angles[0] - 0.1).abs() < 1E-12);
+/// assert!((angles[1] - 0.2).abs() < 1E-12);
+/// assert!((angles[2] - 0.3).abs() < 1E-12);
+/// ```
+#[must_use]
+pub fn of_euler<V: Float>(order: EulerOrder, angles: &[V; 3]) -> [V; 4] {
+    let axes = order.axes.indices();
+    let q = |n: usize| {
+        let (s, c) = (angles[n] * V::frac(1, 2)).sin_cos();
+        let mut q = [V::zero(), V::zero(), V::zero(), c];
+        q[axes[n]] = s;
+        q
+    };
+    if order.intrinsic {
+        multiply(&multiply(&q(0), &q(1)), &q(2))
+    } else {
+        multiply(&multiply(&q(2), &q(1)), &q(0))
     }
+}
 
-    [i, j, k, r]
+//fp to_euler_ordered
+/// Find the Euler angles, applied in the given order, of a unit
+/// quaternion; see [crate::matrix::to_euler3] for the ranges of the
+/// angles
+#[must_use]
+pub fn to_euler_ordered<V: Float>(order: EulerOrder, q: &[V; 4]) -> [V; 3] {
+    let mut m = [V::zero(); 9];
+    to_rotation3(q, &mut m);
+    matrix::to_euler3(order, &m)
 }
 
 //fp look_at
@@ -316,6 +364,196 @@ pub fn nlerp<V: Float>(t: V, in0: &[V; 4], in1: &[V; 4]) -> [V; 4] {
     normalize(vector::mix(in0, in1, t))
 }
 
+//fi slerp_unflipped
+/// Spherical linear interpolation between two unit quaternions,
+/// following the great arc between them without choosing the shorter
+/// path; falls back to [nlerp] if they are nearly parallel
+fn slerp_unflipped<V: Float>(t: V, in0: &[V; 4], in1: &[V; 4]) -> [V; 4] {
+    let cos = vector::dot(in0, in1);
+    if cos.abs() > V::frac(9995, 10000) {
+        return nlerp(t, in0, in1);
+    }
+    let angle = cos.acos();
+    let r_sin = V::one() / angle.sin();
+    let w0 = ((V::one() - t) * angle).sin() * r_sin;
+    let w1 = (t * angle).sin() * r_sin;
+    vector::add(vector::scale(*in0, w0), in1, w1)
+}
+
+//fp slerp
+/// Spherical linear interpolation from one unit quaternion to another
+/// with parameter `t` from zero to one, giving a constant angular
+/// velocity
+///
+/// The interpolation takes the shorter path between the two
+/// rotations, negating `in1` if required; if the quaternions are
+/// nearly the same then this is a normalized linear interpolation
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::quat;
+/// let q0 = quat::of_axis_angle(&[0., 0., 1.], 0.);
+/// let q1 = quat::of_axis_angle(&[0., 0., 1.], 1.);
+/// let q = quat::slerp(0.25, &q0, &q1);
+/// assert!(quat::distance_sq(&q, &quat::of_axis_angle(&[0., 0., 1.], 0.25)) < 1E-12);
+/// ```
+#[must_use]
+pub fn slerp<V: Float>(t: V, in0: &[V; 4], in1: &[V; 4]) -> [V; 4] {
+    if vector::dot(in0, in1) < V::zero() {
+        slerp_unflipped(t, in0, &vector::scale(*in1, -V::one()))
+    } else {
+        slerp_unflipped(t, in0, in1)
+    }
+}
+
+//fp exp
+/// Find the exponential of a quaternion
+///
+/// The exponential of a pure quaternion (one with a zero real part)
+/// of `v` is the unit quaternion of a rotation by twice the length
+/// of `v` about it; more generally the real part scales the result
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::quat;
+/// let q = quat::exp(&[0., 0., 0.25_f64, 0.]);
+/// assert!(quat::distance_sq(&q, &quat::of_axis_angle(&[0., 0., 1.], 0.5)) < 1E-12);
+/// ```
+#[must_use]
+pub fn exp<V: Float>(q: &[V; 4]) -> [V; 4] {
+    let l = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2]).sqrt();
+    let (sin, cos) = l.sin_cos();
+    let sinc = {
+        if l < V::epsilon().sqrt() {
+            V::one() - l * l / V::int(6)
+        } else {
+            sin / l
+        }
+    };
+    let e = q[3].exp();
+    let s = e * sinc;
+    [q[0] * s, q[1] * s, q[2] * s, e * cos]
+}
+
+//fp ln
+/// Find the natural logarithm of a quaternion
+///
+/// For a unit quaternion this is a pure quaternion whose vector is
+/// half the angle of the rotation about its axis; more generally the
+/// real part is the logarithm of the length of the quaternion. The
+/// logarithm of a negative real quaternion has its vector (of length
+/// PI) along the X axis; that of zero has a real part of negative
+/// infinity.
+#[must_use]
+pub fn ln<V: Float>(q: &[V; 4]) -> [V; 4] {
+    let l = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2]).sqrt();
+    let r = q[3];
+    let norm = l.hypot(r);
+    let s = {
+        if r > V::zero() && l < V::epsilon().sqrt() * r {
+            (V::one() - l * l / (V::int(3) * r * r)) / r
+        } else if l > V::zero() {
+            l.atan2(r) / l
+        } else if r < V::zero() {
+            return [V::pi(), V::zero(), V::zero(), norm.ln()];
+        } else {
+            V::zero()
+        }
+    };
+    [q[0] * s, q[1] * s, q[2] * s, norm.ln()]
+}
+
+//fp pow
+/// Raise a quaternion to a real power `t`, as exp(t * ln(q))
+///
+/// For a unit quaternion this is the rotation about the same axis by
+/// `t` times the angle
+#[must_use]
+pub fn pow<V: Float>(q: &[V; 4], t: V) -> [V; 4] {
+    exp(&vector::scale(ln(q), t))
+}
+
+//fp integrate_angular_velocity
+/// Apply an angular velocity `omega` (in radians per unit time about
+/// its axis, in the fixed frame) for a time `dt` to a unit
+/// quaternion, yielding exp(omega * dt / 2) * q
+///
+/// For an angular velocity in the rotated (body) frame, integrate
+/// the conjugate quaternion with a negated `omega`
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::quat;
+/// let q = quat::of_axis_angle(&[1., 0., 0.], 0.3_f64);
+/// let q = quat::integrate_angular_velocity(&q, &[2., 0., 0.], 0.1);
+/// assert!(quat::distance_sq(&q, &quat::of_axis_angle(&[1., 0., 0.], 0.5)) < 1E-12);
+/// ```
+#[must_use]
+pub fn integrate_angular_velocity<V: Float>(q: &[V; 4], omega: &[V; 3], dt: V) -> [V; 4] {
+    let h = dt * V::frac(1, 2);
+    let dq = exp(&[omega[0] * h, omega[1] * h, omega[2] * h, V::zero()]);
+    multiply(&dq, q)
+}
+
+//fp squad_control
+/// Find the intermediate control quaternion for the keyframe `q` of
+/// a [squad] spline, given the previous and next keyframes
+///
+/// For the first and last keyframes of a spline the keyframe itself
+/// may be used as its previous (or next) keyframe
+#[must_use]
+pub fn squad_control<V: Float>(prev: &[V; 4], q: &[V; 4], next: &[V; 4]) -> [V; 4] {
+    let hemisphere = |p: &[V; 4]| {
+        if vector::dot(q, p) < V::zero() {
+            vector::scale(*p, -V::one())
+        } else {
+            *p
+        }
+    };
+    let q_inv = conjugate(q);
+    let ln_next = ln(&multiply(&q_inv, &hemisphere(next)));
+    let ln_prev = ln(&multiply(&q_inv, &hemisphere(prev)));
+    let e = vector::scale(vector::add(ln_next, &ln_prev, V::one()), -V::frac(1, 4));
+    multiply(q, &exp(&e))
+}
+
+//fp squad
+/// Spherical quadrangle interpolation from keyframe `q0` to keyframe
+/// `q1` with parameter `t` from zero to one, using the control
+/// quaternions `a0` and `a1` for the two keyframes from
+/// [squad_control]
+///
+/// A sequence of keyframes interpolated this way gives a spline with
+/// a continuous angular velocity; `q1` and `a1` are negated if
+/// required so that the interpolation takes the shorter path
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::quat;
+/// let z = [0., 0., 1.];
+/// let keys = [0., 1., 2.].map(|a| quat::of_axis_angle(&z, a));
+/// let a1 = quat::squad_control(&keys[0], &keys[1], &keys[2]);
+/// let q = quat::squad(0.5, &keys[0], &keys[1], &keys[0], &a1);
+/// assert!(quat::distance_sq(&q, &quat::of_axis_angle(&z, 0.5)) < 1E-12);
+/// ```
+#[must_use]
+pub fn squad<V: Float>(t: V, q0: &[V; 4], q1: &[V; 4], a0: &[V; 4], a1: &[V; 4]) -> [V; 4] {
+    let (q1, a1) = {
+        if vector::dot(q0, q1) < V::zero() {
+            (vector::scale(*q1, -V::one()), vector::scale(*a1, -V::one()))
+        } else {
+            (*q1, *a1)
+        }
+    };
+    let s = slerp_unflipped(t, q0, &q1);
+    let a = slerp_unflipped(t, a0, &a1);
+    slerp_unflipped(V::frac(2, 1) * t * (V::one() - t), &s, &a)
+}
+
 //a Operational functions
 //fp distance_sq
 /// Get a measure of the 'distance' between two quaternions
@@ -344,6 +582,8 @@ pub fn distance<V: Float>(a: &[V; 4], b: &[V; 4]) -> V {
 
 //fp to_euler
 /// Convert the quaternion to a bank, heading, altitude tuple - applied in that order
+///
+/// See [to_euler_ordered] for other orders of Euler angles
 #[must_use]
 pub fn to_euler<V: Float>(q: &[V; 4]) -> (V, V, V) {
     let i = q[0];
@@ -442,7 +682,9 @@ pub fn weighted_average_pair<V: Float>(qa: &[V; 4], w_a: V, qb: &[V; 4], w_b: V)
 ///
 /// weights need not add up to 1, but must be nonzero
 ///
-/// This is an approximation compared to the Landis Markley paper
+/// This is an approximation compared to the Landis Markley paper,
+/// and its result depends on the order of the quaternions; see
+/// [weighted_average_markley] for the exact average
 #[must_use]
 pub fn weighted_average_many<I: Iterator<Item = (V, [V; 4])>, V: Float>(
     mut values_iter: I,
@@ -492,6 +734,61 @@ pub fn weighted_average_many<I: Iterator<Item = (V, [V; 4])>, V: Float>(
     }
 }
 
+//fp weighted_average_markley
+/// Calculate the weighted average of many unit quaternions, using
+/// the method of the Landis Markley paper
+///
+/// This accumulates the weighted outer products of the quaternions
+/// into a symmetric 4-by-4 matrix, whose eigenvector with the largest
+/// eigenvalue is the average. Hence the result does not depend on the
+/// order of the quaternions, nor on their signs (q and -q being the
+/// same rotation); the result is chosen to have a non-negative real
+/// part.
+///
+/// Weights need not add up to 1, but must not be negative.
+///
+/// Returns None if there are no quaternions, or if the weights are
+/// all zero.
+///
+/// See http://www.acsu.buffalo.edu/~johnc/ave_quat07.pdf
+/// Averaging Quaternions by F. Landis Markley
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::quat;
+/// let a = quat::of_axis_angle(&[0., 0., 1.], 0.2_f64);
+/// let b = quat::of_axis_angle(&[0., 0., 1.], 0.6_f64);
+/// let b_neg = [-b[0], -b[1], -b[2], -b[3]];
+/// let av = quat::weighted_average_markley([(1., a), (1., b_neg)].into_iter()).unwrap();
+/// let expected = quat::of_axis_angle(&[0., 0., 1.], 0.4);
+/// assert!( quat::distance_sq(&av, &expected) < 1E-20 );
+/// assert_eq!( quat::weighted_average_markley::<_, f64>([].into_iter()), None );
+/// ```
+#[must_use]
+pub fn weighted_average_markley<I: Iterator<Item = (V, [V; 4])>, V: Float>(
+    values_iter: I,
+) -> Option<[V; 4]> {
+    let mut m = [V::zero(); 16];
+    for (w, q) in values_iter {
+        for r in 0..4 {
+            for c in 0..4 {
+                m[r * 4 + c] += w * q[r] * q[c];
+            }
+        }
+    }
+    let (l, e) = matrix::symmetric_eigen4(&m);
+    if l[0] <= V::zero() {
+        return None;
+    }
+    let q = [e[0], e[4], e[8], e[12]];
+    if q[3] < V::zero() {
+        Some([-q[0], -q[1], -q[2], -q[3]])
+    } else {
+        Some(q)
+    }
+}
+
 //fp get_rotation_of_vec_to_vec
 /// Get a quaternion that is a rotation of one vector to another
 ///
@@ -0,0 +1,293 @@
+//a Imports
+use serde::Serialize;
+
+use crate::{vector, Aabb, FArray, FQArrayTrans, Float, Transform, Vector};
+
+//a RayHit
+//tp RayHit
+/// The point at which a [Ray] hits a surface, as the distance along
+/// the ray and the normal to the surface there
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit<F, V3>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+{
+    /// The distance along the ray, as a multiple of its direction;
+    /// the hit point is [Ray::at] this distance
+    pub distance: F,
+    /// The unit normal to the surface at the hit point
+    pub normal: V3,
+}
+
+//a Ray
+//tp Ray
+/// A ray, as an origin and a direction, with intersection tests
+/// against planes, spheres, triangles and boxes
+///
+/// The direction need not be a unit vector; distances along the ray
+/// are multiples of the direction, and they are only actual distances
+/// if the direction is a unit vector. Only hits at a distance of zero
+/// or more are reported; if the origin of the ray is inside a sphere
+/// or box then the hit is where the ray leaves it.
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{FArray, Ray, Vector};
+/// type Vec3 = FArray<f64, 3>;
+/// let ray = Ray::new(Vec3::from_array([0., 0., 5.]), Vec3::from_array([0., 0., -1.]));
+/// let hit = ray.intersect_sphere(&Vec3::zero(), 2.).unwrap();
+/// assert_eq!(hit.distance, 3.);
+/// assert_eq!(hit.normal.into_array(), [0., 0., 1.]);
+/// assert_eq!(ray.at(hit.distance).into_array(), [0., 0., 2.]);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Ray<F, V3>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+{
+    /// The origin of the ray
+    origin: V3,
+    /// The direction of the ray
+    direction: V3,
+    /// Marker for the float type
+    phantom: std::marker::PhantomData<F>,
+}
+
+//ip Display for Ray
+impl<F, V3> std::fmt::Display for Ray<F, V3>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} + t * {}", self.origin, self.direction)
+    }
+}
+
+//ip Ray
+impl<F, V3> Ray<F, V3>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+{
+    //cp new
+    /// Create a ray from an origin and a direction
+    pub fn new(origin: V3, direction: V3) -> Self {
+        Self {
+            origin,
+            direction,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    //mp origin
+    /// Get the origin of the ray
+    pub fn origin(&self) -> V3 {
+        self.origin
+    }
+
+    //mp direction
+    /// Get the direction of the ray
+    pub fn direction(&self) -> V3 {
+        self.direction
+    }
+
+    //mp at
+    /// Get the point at a distance along the ray
+    pub fn at(&self, t: F) -> V3 {
+        self.origin + self.direction * t
+    }
+
+    //fi cross
+    #[inline]
+    fn cross(a: &V3, b: &V3) -> V3 {
+        V3::from_array(vector::cross_product3(a.as_ref(), b.as_ref()))
+    }
+
+    //mp intersect_plane
+    /// Find where the ray hits a plane, given as a 4-vector (a, b, c,
+    /// d) of the points p with a.px + b.py + c.pz + d = 0 (as for the
+    /// planes of a [crate::Frustum])
+    ///
+    /// The normal of the hit is (a, b, c) made a unit vector. There is
+    /// no hit if the ray is parallel to the plane.
+    pub fn intersect_plane<V4: Vector<F, 4>>(&self, plane: &V4) -> Option<RayHit<F, V3>> {
+        let normal = V3::from_array([plane[0], plane[1], plane[2]]);
+        let denom = normal.dot(&self.direction);
+        if denom == F::zero() {
+            return None;
+        }
+        let distance = -(normal.dot(&self.origin) + plane[3]) / denom;
+        if distance < F::zero() {
+            return None;
+        }
+        Some(RayHit {
+            distance,
+            normal: normal.normalize(),
+        })
+    }
+
+    //mp intersect_sphere
+    /// Find where the ray first hits a sphere; the normal of the hit
+    /// points out of the sphere
+    pub fn intersect_sphere(&self, centre: &V3, radius: F) -> Option<RayHit<F, V3>> {
+        let oc = self.origin - *centre;
+        let a = self.direction.length_sq();
+        if a == F::zero() {
+            return None;
+        }
+        let b = oc.dot(&self.direction);
+        let c = oc.length_sq() - radius * radius;
+        let disc = b * b - a * c;
+        if disc < F::zero() {
+            return None;
+        }
+        let sq = disc.sqrt();
+        let t0 = (-b - sq) / a;
+        let t1 = (-b + sq) / a;
+        let distance = if t0 >= F::zero() {
+            t0
+        } else if t1 >= F::zero() {
+            t1
+        } else {
+            return None;
+        };
+        Some(RayHit {
+            distance,
+            normal: (self.at(distance) - *centre).normalize(),
+        })
+    }
+
+    //mp intersect_triangle
+    /// Find where the ray hits a triangle, using the method of Möller
+    /// and Trumbore, returning the hit and the barycentric coordinates
+    /// of the hit point (the weights of a, b and c)
+    ///
+    /// The normal of the hit is that of the triangle, (b-a) x (c-a)
+    /// made a unit vector, whichever side the ray hits. The front face
+    /// of the triangle is that on which its corners are
+    /// anticlockwise; if `cull` is true then the back face is culled,
+    /// and a ray that hits it does not hit the triangle.
+    pub fn intersect_triangle(
+        &self,
+        a: &V3,
+        b: &V3,
+        c: &V3,
+        cull: bool,
+    ) -> Option<(RayHit<F, V3>, [F; 3])> {
+        let e1 = *b - *a;
+        let e2 = *c - *a;
+        let p = Self::cross(&self.direction, &e2);
+        let det = e1.dot(&p);
+        // The determinant scales with the lengths of the direction and
+        // edges, so it is compared with a tolerance scaled likewise
+        let tiny = F::epsilon() * self.direction.length() * e1.length() * e2.length();
+        if (cull && det <= tiny) || det.abs() <= tiny {
+            return None;
+        }
+        let s = self.origin - *a;
+        let u = s.dot(&p) / det;
+        if u < F::zero() || u > F::one() {
+            return None;
+        }
+        let q = Self::cross(&s, &e1);
+        let v = self.direction.dot(&q) / det;
+        if v < F::zero() || u + v > F::one() {
+            return None;
+        }
+        let distance = e2.dot(&q) / det;
+        if distance < F::zero() {
+            return None;
+        }
+        let hit = RayHit {
+            distance,
+            normal: Self::cross(&e1, &e2).normalize(),
+        };
+        Some((hit, [F::one() - u - v, u, v]))
+    }
+
+    //mp intersect_aabb
+    /// Find where the ray first hits an axis-aligned box, using the
+    /// slab method; the normal of the hit is that of the face that is
+    /// hit, pointing out of the box
+    pub fn intersect_aabb(&self, aabb: &Aabb<F, V3, 3>) -> Option<RayHit<F, V3>> {
+        let (min, max) = (aabb.min(), aabb.max());
+        let mut t_near = F::neg_infinity();
+        let mut t_far = F::infinity();
+        // The axis and outward sign of the faces the ray enters and leaves by
+        let mut near_face = None;
+        let mut far_face = None;
+        for i in 0..3 {
+            let (o, d) = (self.origin[i], self.direction[i]);
+            if d == F::zero() {
+                if o < min[i] || o > max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let (t0, t1) = ((min[i] - o) / d, (max[i] - o) / d);
+            let (t0, t1, sign) = if d > F::zero() {
+                (t0, t1, F::one())
+            } else {
+                (t1, t0, -F::one())
+            };
+            if t0 > t_near {
+                t_near = t0;
+                near_face = Some((i, -sign));
+            }
+            if t1 < t_far {
+                t_far = t1;
+                far_face = Some((i, sign));
+            }
+        }
+        if t_near > t_far || t_far < F::zero() {
+            return None;
+        }
+        let (distance, (axis, sign)) = if t_near >= F::zero() {
+            (t_near, near_face?)
+        } else {
+            (t_far, far_face?)
+        };
+        let mut normal = V3::zero();
+        normal[axis] = sign;
+        Some(RayHit { distance, normal })
+    }
+
+    //mp intersect_obb
+    /// Find where the ray first hits an oriented box, which is an
+    /// axis-aligned box in its own space that is transformed into
+    /// that of the ray; the normal of the hit is that of the face that
+    /// is hit, pointing out of the box
+    ///
+    /// The distance of the hit is along this ray, since the
+    /// transformation maps the points of the ray in the space of the
+    /// box to those of this ray
+    ///
+    /// Returns None if the transformation is singular (its scale is
+    /// zero), as then the box has no volume
+    pub fn intersect_obb(
+        &self,
+        aabb: &Aabb<F, FArray<F, 3>, 3>,
+        trans: &FQArrayTrans<F>,
+    ) -> Option<RayHit<F, V3>>
+    where
+        F: Serialize,
+    {
+        let inverse = trans.try_inverse(F::epsilon()).ok()?;
+        let origin = FArray::from_array(self.origin.into_array());
+        let direction = FArray::from_array(self.direction.into_array());
+        let local = Ray::new(
+            inverse.transform_point(&origin),
+            inverse.transform_vector(&direction),
+        );
+        let hit = local.intersect_aabb(aabb)?;
+        let normal = trans.transform_vector(&hit.normal).normalize();
+        Some(RayHit {
+            distance: hit.distance,
+            normal: V3::from_array(normal.into_array()),
+        })
+    }
+}
@@ -0,0 +1,493 @@
+/*a Copyright
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+@file    simd_f32.rs
+@brief   Four-lane f32 register type for the SIMD vectors
+ */
+
+//a Documentation
+//! This provides the [F32x4] lane type that the SIMD vector types
+//! are built on.
+//!
+//! The data is held as an aligned `[f32; 4]`, so that it can be
+//! borrowed as an array at no cost; the arithmetic is performed with
+//! the `core::arch` SSE intrinsics where the target supports them,
+//! and with plain scalar code otherwise.
+
+//a Imports
+use crate::SingularMatrix;
+
+//a F32x4
+//tp F32x4
+/// A 16-byte aligned array of four `f32`, operated on as a single SIMD register
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(C, align(16))]
+pub struct F32x4(pub [f32; 4]);
+
+//ip F32x4
+impl F32x4 {
+    //fp splat
+    /// Create with all four lanes set to the same value
+    #[inline]
+    pub const fn splat(f: f32) -> Self {
+        Self([f, f, f, f])
+    }
+
+    //mp sum
+    /// Sum all four lanes
+    #[inline]
+    pub fn sum(self) -> f32 {
+        imp::sum(self)
+    }
+
+    //mp dot
+    /// Sum of the lane-wise products of two registers
+    #[inline]
+    pub fn dot(self, other: Self) -> f32 {
+        imp::sum(imp::mul(self, other))
+    }
+
+    //mp cross3
+    /// Cross product of lanes 0 to 2, with lane 3 of the result
+    /// being zero (provided lane 3 of the inputs is finite)
+    #[inline]
+    pub fn cross3(self, other: Self) -> Self {
+        imp::cross3(self, other)
+    }
+
+    //fp transpose4
+    /// Transpose four registers as the rows (or columns) of a 4-by-4 matrix
+    #[inline]
+    pub fn transpose4(rows: [Self; 4]) -> [Self; 4] {
+        imp::transpose4(rows)
+    }
+
+    //fp inverse4
+    /// Invert four registers as the rows (or columns) of a 4-by-4
+    /// matrix, or return an error if the magnitude of the determinant
+    /// is no more than `epsilon`, as with [crate::matrix::try_inverse4]
+    #[inline]
+    pub fn inverse4(rows: [Self; 4], epsilon: f32) -> Result<[Self; 4], SingularMatrix<f32>> {
+        imp::inverse4(rows, epsilon)
+    }
+}
+
+//ip Binary operations for F32x4
+macro_rules! binary_op {
+    { $trait_op:ident, $op:ident, $trait_assign_op:ident, $assign_op:ident } => {
+        impl std::ops::$trait_op<Self> for F32x4 {
+            type Output = Self;
+            #[inline]
+            fn $op(self, other: Self) -> Self {
+                imp::$op(self, other)
+            }
+        }
+        impl std::ops::$trait_assign_op<Self> for F32x4 {
+            #[inline]
+            fn $assign_op(&mut self, other: Self) {
+                *self = imp::$op(*self, other);
+            }
+        }
+    }
+}
+binary_op! { Add, add, AddAssign, add_assign }
+binary_op! { Sub, sub, SubAssign, sub_assign }
+binary_op! { Mul, mul, MulAssign, mul_assign }
+binary_op! { Div, div, DivAssign, div_assign }
+
+//ip Neg for F32x4
+impl std::ops::Neg for F32x4 {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        imp::neg(self)
+    }
+}
+
+//a SSE implementation
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse"
+))]
+mod imp {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    use super::F32x4;
+    use crate::SingularMatrix;
+
+    //fi load
+    #[inline(always)]
+    fn load(a: F32x4) -> __m128 {
+        // Safety: the F32x4 is 16-byte aligned and SSE is present
+        unsafe { _mm_load_ps(a.0.as_ptr()) }
+    }
+
+    //fi store
+    #[inline(always)]
+    fn store(v: __m128) -> F32x4 {
+        let mut r = F32x4::default();
+        // Safety: the F32x4 is 16-byte aligned and SSE is present
+        unsafe { _mm_store_ps(r.0.as_mut_ptr(), v) };
+        r
+    }
+
+    //fi binary operations
+    macro_rules! binary_fn {
+        { $op:ident, $intrinsic:ident } => {
+            #[inline(always)]
+            pub fn $op(a: F32x4, b: F32x4) -> F32x4 {
+                // Safety: SSE is present
+                store(unsafe { $intrinsic(load(a), load(b)) })
+            }
+        }
+    }
+    binary_fn! { add, _mm_add_ps }
+    binary_fn! { sub, _mm_sub_ps }
+    binary_fn! { mul, _mm_mul_ps }
+    binary_fn! { div, _mm_div_ps }
+
+    //fp neg
+    #[inline(always)]
+    pub fn neg(a: F32x4) -> F32x4 {
+        // Safety: SSE is present
+        store(unsafe { _mm_xor_ps(load(a), _mm_set1_ps(-0.0)) })
+    }
+
+    //fp sum
+    #[inline(always)]
+    pub fn sum(a: F32x4) -> f32 {
+        // Safety: SSE is present
+        unsafe {
+            let v = load(a);
+            let shuf = _mm_shuffle_ps::<0b10_11_00_01>(v, v);
+            let sums = _mm_add_ps(v, shuf);
+            let shuf = _mm_movehl_ps(shuf, sums);
+            _mm_cvtss_f32(_mm_add_ss(sums, shuf))
+        }
+    }
+
+    //fp cross3
+    #[inline(always)]
+    pub fn cross3(a: F32x4, b: F32x4) -> F32x4 {
+        // Safety: SSE is present
+        unsafe {
+            let a = load(a);
+            let b = load(b);
+            let a_yzx = _mm_shuffle_ps::<0b11_00_10_01>(a, a);
+            let b_yzx = _mm_shuffle_ps::<0b11_00_10_01>(b, b);
+            let c = _mm_sub_ps(_mm_mul_ps(a, b_yzx), _mm_mul_ps(a_yzx, b));
+            store(_mm_shuffle_ps::<0b11_00_10_01>(c, c))
+        }
+    }
+
+    //fp transpose4
+    #[inline(always)]
+    pub fn transpose4(rows: [F32x4; 4]) -> [F32x4; 4] {
+        // Safety: SSE is present
+        unsafe {
+            let t0 = _mm_unpacklo_ps(load(rows[0]), load(rows[1]));
+            let t1 = _mm_unpacklo_ps(load(rows[2]), load(rows[3]));
+            let t2 = _mm_unpackhi_ps(load(rows[0]), load(rows[1]));
+            let t3 = _mm_unpackhi_ps(load(rows[2]), load(rows[3]));
+            [
+                store(_mm_movelh_ps(t0, t1)),
+                store(_mm_movehl_ps(t1, t0)),
+                store(_mm_movelh_ps(t2, t3)),
+                store(_mm_movehl_ps(t3, t2)),
+            ]
+        }
+    }
+
+    //fi shuffle masks for inverse4
+    /// Mask for `_mm_shuffle_ps` selecting lanes x, y from the first
+    /// argument and z, w from the second
+    const fn mask(x: i32, y: i32, z: i32, w: i32) -> i32 {
+        x | (y << 2) | (z << 4) | (w << 6)
+    }
+    const M_0202: i32 = mask(0, 2, 0, 2);
+    const M_1313: i32 = mask(1, 3, 1, 3);
+    const M_0303: i32 = mask(0, 3, 0, 3);
+    const M_1032: i32 = mask(1, 0, 3, 2);
+    const M_2121: i32 = mask(2, 1, 2, 1);
+    const M_3300: i32 = mask(3, 3, 0, 0);
+    const M_1122: i32 = mask(1, 1, 2, 2);
+    const M_2301: i32 = mask(2, 3, 0, 1);
+    const M_3030: i32 = mask(3, 0, 3, 0);
+    const M_0213: i32 = mask(0, 2, 1, 3);
+    const M_3131: i32 = mask(3, 1, 3, 1);
+    const M_2020: i32 = mask(2, 0, 2, 0);
+
+    //fi mat2_mul
+    /// Multiply two 2-by-2 matrices held in single registers, A * B
+    #[inline(always)]
+    unsafe fn mat2_mul(a: __m128, b: __m128) -> __m128 {
+        _mm_add_ps(
+            _mm_mul_ps(a, _mm_shuffle_ps::<M_0303>(b, b)),
+            _mm_mul_ps(
+                _mm_shuffle_ps::<M_1032>(a, a),
+                _mm_shuffle_ps::<M_2121>(b, b),
+            ),
+        )
+    }
+
+    //fi mat2_adj_mul
+    /// Multiply the adjugate of a 2-by-2 matrix by another, A# * B
+    #[inline(always)]
+    unsafe fn mat2_adj_mul(a: __m128, b: __m128) -> __m128 {
+        _mm_sub_ps(
+            _mm_mul_ps(_mm_shuffle_ps::<M_3300>(a, a), b),
+            _mm_mul_ps(
+                _mm_shuffle_ps::<M_1122>(a, a),
+                _mm_shuffle_ps::<M_2301>(b, b),
+            ),
+        )
+    }
+
+    //fi mat2_mul_adj
+    /// Multiply a 2-by-2 matrix by the adjugate of another, A * B#
+    #[inline(always)]
+    unsafe fn mat2_mul_adj(a: __m128, b: __m128) -> __m128 {
+        _mm_sub_ps(
+            _mm_mul_ps(a, _mm_shuffle_ps::<M_3030>(b, b)),
+            _mm_mul_ps(
+                _mm_shuffle_ps::<M_1032>(a, a),
+                _mm_shuffle_ps::<M_2121>(b, b),
+            ),
+        )
+    }
+
+    //fp inverse4
+    /// Invert using the 2-by-2 block decomposition of the matrix
+    ///
+    /// With M = [A B; C D] for 2-by-2 blocks A, B, C and D (each held
+    /// in one register), the inverse is 1/|M| * [X Y; Z W] where
+    /// the adjugates of the blocks are:
+    ///
+    /// ```text
+    ///   X# = |D|A - B(D#C)
+    ///   Y# = |B|C - D(A#B)#
+    ///   Z# = |C|B - A(D#C)#
+    ///   W# = |A|D - C(A#B)
+    ///  |M| = |A||D| + |B||C| - tr((A#B)(D#C))
+    /// ```
+    #[inline(always)]
+    pub fn inverse4(rows: [F32x4; 4], epsilon: f32) -> Result<[F32x4; 4], SingularMatrix<f32>> {
+        // Safety: SSE is present
+        unsafe {
+            let r0 = load(rows[0]);
+            let r1 = load(rows[1]);
+            let r2 = load(rows[2]);
+            let r3 = load(rows[3]);
+            let a = _mm_movelh_ps(r0, r1);
+            let b = _mm_movehl_ps(r1, r0);
+            let c = _mm_movelh_ps(r2, r3);
+            let d = _mm_movehl_ps(r3, r2);
+
+            // (|A|, |B|, |C|, |D|)
+            let det_sub = _mm_sub_ps(
+                _mm_mul_ps(
+                    _mm_shuffle_ps::<M_0202>(r0, r2),
+                    _mm_shuffle_ps::<M_1313>(r1, r3),
+                ),
+                _mm_mul_ps(
+                    _mm_shuffle_ps::<M_1313>(r0, r2),
+                    _mm_shuffle_ps::<M_0202>(r1, r3),
+                ),
+            );
+            let det_a = _mm_shuffle_ps::<0b00_00_00_00>(det_sub, det_sub);
+            let det_b = _mm_shuffle_ps::<0b01_01_01_01>(det_sub, det_sub);
+            let det_c = _mm_shuffle_ps::<0b10_10_10_10>(det_sub, det_sub);
+            let det_d = _mm_shuffle_ps::<0b11_11_11_11>(det_sub, det_sub);
+
+            let d_c = mat2_adj_mul(d, c);
+            let a_b = mat2_adj_mul(a, b);
+            let x = _mm_sub_ps(_mm_mul_ps(det_d, a), mat2_mul(b, d_c));
+            let w = _mm_sub_ps(_mm_mul_ps(det_a, d), mat2_mul(c, a_b));
+            let y = _mm_sub_ps(_mm_mul_ps(det_b, c), mat2_mul_adj(d, a_b));
+            let z = _mm_sub_ps(_mm_mul_ps(det_c, b), mat2_mul_adj(a, d_c));
+
+            let tr = sum(store(_mm_mul_ps(a_b, _mm_shuffle_ps::<M_0213>(d_c, d_c))));
+            let det = _mm_cvtss_f32(_mm_add_ss(
+                _mm_mul_ss(det_a, det_d),
+                _mm_mul_ss(det_b, det_c),
+            )) - tr;
+            if det.abs() <= epsilon {
+                return Err(SingularMatrix { determinant: det });
+            }
+
+            // The adjugate of each block is formed in the final shuffle
+            let r_det = _mm_div_ps(_mm_setr_ps(1., -1., -1., 1.), _mm_set1_ps(det));
+            let x = _mm_mul_ps(x, r_det);
+            let y = _mm_mul_ps(y, r_det);
+            let z = _mm_mul_ps(z, r_det);
+            let w = _mm_mul_ps(w, r_det);
+            Ok([
+                store(_mm_shuffle_ps::<M_3131>(x, y)),
+                store(_mm_shuffle_ps::<M_2020>(x, y)),
+                store(_mm_shuffle_ps::<M_3131>(z, w)),
+                store(_mm_shuffle_ps::<M_2020>(z, w)),
+            ])
+        }
+    }
+}
+
+//a Scalar implementation
+#[cfg(not(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse"
+)))]
+mod imp {
+    use super::F32x4;
+    use crate::SingularMatrix;
+
+    //fi binary operations
+    macro_rules! binary_fn {
+        { $op:ident, $binop:tt } => {
+            #[inline(always)]
+            pub fn $op(a: F32x4, b: F32x4) -> F32x4 {
+                F32x4([
+                    a.0[0] $binop b.0[0],
+                    a.0[1] $binop b.0[1],
+                    a.0[2] $binop b.0[2],
+                    a.0[3] $binop b.0[3],
+                ])
+            }
+        }
+    }
+    binary_fn! { add, + }
+    binary_fn! { sub, - }
+    binary_fn! { mul, * }
+    binary_fn! { div, / }
+
+    //fp neg
+    #[inline(always)]
+    pub fn neg(a: F32x4) -> F32x4 {
+        F32x4([-a.0[0], -a.0[1], -a.0[2], -a.0[3]])
+    }
+
+    //fp sum
+    #[inline(always)]
+    pub fn sum(a: F32x4) -> f32 {
+        (a.0[0] + a.0[1]) + (a.0[2] + a.0[3])
+    }
+
+    //fp cross3
+    #[inline(always)]
+    pub fn cross3(a: F32x4, b: F32x4) -> F32x4 {
+        let a = a.0;
+        let b = b.0;
+        F32x4([
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+            a[3] * b[3] - a[3] * b[3],
+        ])
+    }
+
+    //fp transpose4
+    #[inline(always)]
+    pub fn transpose4(rows: [F32x4; 4]) -> [F32x4; 4] {
+        let mut r = [F32x4::default(); 4];
+        for (i, row) in rows.iter().enumerate() {
+            for (j, c) in row.0.iter().enumerate() {
+                r[j].0[i] = *c;
+            }
+        }
+        r
+    }
+
+    //fp inverse4
+    #[inline(always)]
+    pub fn inverse4(rows: [F32x4; 4], epsilon: f32) -> Result<[F32x4; 4], SingularMatrix<f32>> {
+        let mut m = [0.; 16];
+        for (i, row) in rows.iter().enumerate() {
+            m[i * 4..i * 4 + 4].copy_from_slice(&row.0);
+        }
+        let m = crate::matrix::try_inverse4(&m, epsilon)?;
+        let mut r = [F32x4::default(); 4];
+        for (i, row) in r.iter_mut().enumerate() {
+            row.0.copy_from_slice(&m[i * 4..i * 4 + 4]);
+        }
+        Ok(r)
+    }
+}
+
+//a F32x2
+//tp F32x2
+/// An 8-byte aligned array of two `f32`
+///
+/// There is no two-lane SSE register, so this is operated on with
+/// scalar code that the compiler is free to vectorize
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(C, align(8))]
+pub struct F32x2(pub [f32; 2]);
+
+//ip F32x2
+impl F32x2 {
+    //fp splat
+    /// Create with both lanes set to the same value
+    #[inline]
+    pub const fn splat(f: f32) -> Self {
+        Self([f, f])
+    }
+
+    //mp sum
+    /// Sum both lanes
+    #[inline]
+    pub fn sum(self) -> f32 {
+        self.0[0] + self.0[1]
+    }
+
+    //mp dot
+    /// Sum of the lane-wise products of two registers
+    #[inline]
+    pub fn dot(self, other: Self) -> f32 {
+        (self * other).sum()
+    }
+}
+
+//ip Binary operations for F32x2
+macro_rules! binary_op_x2 {
+    { $trait_op:ident, $op:ident, $binop:tt, $trait_assign_op:ident, $assign_op:ident } => {
+        impl std::ops::$trait_op<Self> for F32x2 {
+            type Output = Self;
+            #[inline]
+            fn $op(self, other: Self) -> Self {
+                Self([self.0[0] $binop other.0[0], self.0[1] $binop other.0[1]])
+            }
+        }
+        impl std::ops::$trait_assign_op<Self> for F32x2 {
+            #[inline]
+            fn $assign_op(&mut self, other: Self) {
+                *self = *self $binop other;
+            }
+        }
+    }
+}
+binary_op_x2! { Add, add, +, AddAssign, add_assign }
+binary_op_x2! { Sub, sub, -, SubAssign, sub_assign }
+binary_op_x2! { Mul, mul, *, MulAssign, mul_assign }
+binary_op_x2! { Div, div, /, DivAssign, div_assign }
+
+//ip Neg for F32x2
+impl std::ops::Neg for F32x2 {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        Self([-self.0[0], -self.0[1]])
+    }
+}
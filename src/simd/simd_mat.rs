@@ -0,0 +1,423 @@
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use super::simd_f32::F32x4;
+use super::{F32x4Vec3, F32x4Vec4};
+use crate::{matrix, FArray2, SingularMatrix};
+use crate::{SqMatrix, SqMatrix3, SqMatrix4, Vector};
+
+//a Macros
+//mi sq_matrix!
+/// Implement the traits required for a [SqMatrix] on a wrapper around
+/// an [FArray2], which operates on the SIMD vector type `$v`
+macro_rules! sq_matrix {
+    { $t:ident, $v:ty, $d:expr, $d2:expr } => {
+        //ip Add, Sub, Mul, Div for $t
+        impl std::ops::Add<Self> for $t {
+            type Output = Self;
+            #[inline]
+            fn add(self, other: Self) -> Self { Self(self.0 + other.0) }
+        }
+        impl std::ops::AddAssign<Self> for $t {
+            #[inline]
+            fn add_assign(&mut self, other: Self) { self.0 += other.0; }
+        }
+        impl std::ops::Sub<Self> for $t {
+            type Output = Self;
+            #[inline]
+            fn sub(self, other: Self) -> Self { Self(self.0 - other.0) }
+        }
+        impl std::ops::SubAssign<Self> for $t {
+            #[inline]
+            fn sub_assign(&mut self, other: Self) { self.0 -= other.0; }
+        }
+        impl std::ops::Mul<Self> for $t {
+            type Output = Self;
+            #[inline]
+            fn mul(self, other: Self) -> Self { Self(self.0 * other.0) }
+        }
+        impl std::ops::MulAssign<Self> for $t {
+            #[inline]
+            fn mul_assign(&mut self, other: Self) { self.0 *= other.0; }
+        }
+        impl std::ops::Mul<f32> for $t {
+            type Output = Self;
+            #[inline]
+            fn mul(self, other: f32) -> Self { Self(self.0 * other) }
+        }
+        impl std::ops::MulAssign<f32> for $t {
+            #[inline]
+            fn mul_assign(&mut self, other: f32) { self.0 *= other; }
+        }
+        impl std::ops::Div<f32> for $t {
+            type Output = Self;
+            #[inline]
+            fn div(self, other: f32) -> Self { Self(self.0 / other) }
+        }
+        impl std::ops::DivAssign<f32> for $t {
+            #[inline]
+            fn div_assign(&mut self, other: f32) { self.0 /= other; }
+        }
+
+        //ip AsRef, AsMut for $t
+        impl std::convert::AsRef<[f32; $d2]> for $t {
+            #[inline]
+            fn as_ref(&self) -> &[f32; $d2] { self.0.as_ref() }
+        }
+        impl std::convert::AsRef<[f32]> for $t {
+            #[inline]
+            fn as_ref(&self) -> &[f32] { self.0.as_ref() }
+        }
+        impl std::convert::AsMut<[f32; $d2]> for $t {
+            #[inline]
+            fn as_mut(&mut self) -> &mut [f32; $d2] { self.0.as_mut() }
+        }
+        impl std::convert::AsMut<[f32]> for $t {
+            #[inline]
+            fn as_mut(&mut self) -> &mut [f32] { self.0.as_mut() }
+        }
+
+        //ip Index, IndexMut for $t
+        impl std::ops::Index<usize> for $t {
+            type Output = f32;
+            #[inline]
+            fn index(&self, index: usize) -> &f32 { &self.0[index] }
+        }
+        impl std::ops::IndexMut<usize> for $t {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut f32 { &mut self.0[index] }
+        }
+
+        //ip From<[f32;D2]> for $t, and From<$t> for [f32;D2]
+        impl From<[f32; $d2]> for $t {
+            #[inline]
+            fn from(data: [f32; $d2]) -> Self { Self(data.into()) }
+        }
+        impl From<$t> for [f32; $d2] {
+            #[inline]
+            fn from(m: $t) -> [f32; $d2] { m.into_array() }
+        }
+
+        //ip SqMatrix for $t
+        impl SqMatrix<$v, f32, $d, $d2> for $t {
+            #[inline]
+            fn from_array(data: [f32; $d2]) -> Self {
+                Self(data.into())
+            }
+            #[inline]
+            fn into_array(self) -> [f32; $d2] {
+                self.0.into_array()
+            }
+            #[inline]
+            fn identity() -> Self {
+                Self(FArray2::identity())
+            }
+            #[inline]
+            fn zero() -> Self {
+                Self(FArray2::zero())
+            }
+            #[inline]
+            fn is_zero(&self) -> bool {
+                self.0.is_zero()
+            }
+            #[inline]
+            fn set_zero(&mut self) {
+                self.0.set_zero()
+            }
+            #[inline]
+            fn transpose(&self) -> Self {
+                Self(self.0.transpose())
+            }
+            #[inline]
+            fn determinant(&self) -> f32 {
+                self.0.determinant()
+            }
+            #[inline]
+            fn inverse(&self) -> Self {
+                Self(self.0.inverse())
+            }
+            #[inline]
+            fn try_inverse(&self, epsilon: f32) -> Result<Self, SingularMatrix<f32>> {
+                self.0.try_inverse(epsilon).map(Self)
+            }
+            #[inline]
+            fn transform(&self, v: &$v) -> $v {
+                let m: &[f32; $d2] = self.as_ref();
+                let mut r = [0.; $d];
+                for (i, r) in r.iter_mut().enumerate() {
+                    let row: &[f32; $d] = m[i * $d..(i + 1) * $d].try_into().unwrap();
+                    *r = v.dot(&<$v>::from_array(*row));
+                }
+                <$v>::from_array(r)
+            }
+        }
+    }
+}
+
+//a F32Mat3
+//tp F32Mat3
+/// A 3-by-3 matrix of `f32` (an [FArray2]) that operates on the
+/// [F32x4Vec3] SIMD vector type
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+#[repr(transparent)]
+pub struct F32Mat3(FArray2<f32, 3, 9>);
+
+sq_matrix! { F32Mat3, F32x4Vec3, 3, 9 }
+
+//ip SqMatrix3 for F32Mat3
+impl SqMatrix3<F32x4Vec3, f32> for F32Mat3 {}
+
+//a F32x4Mat4
+//tp F32x4Mat4
+/// A 4-by-4 row-major matrix of `f32` held as four 16-byte aligned
+/// SIMD registers, one per row, that operates on the [F32x4Vec4] SIMD
+/// vector type
+///
+/// The layout is exactly that of a `[f32; 16]` (aligned to 16 bytes),
+/// so it may be borrowed as an array (for example, to upload to a GPU)
+/// at no cost.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+#[repr(C, align(16))]
+pub struct F32x4Mat4([f32; 16]);
+
+//ip F32x4Mat4
+impl F32x4Mat4 {
+    //fi row
+    #[inline]
+    fn row(&self, n: usize) -> F32x4 {
+        F32x4(*self.0[n * 4..n * 4 + 4].first_chunk::<4>().unwrap())
+    }
+
+    //fi rows
+    #[inline]
+    fn rows(&self) -> [F32x4; 4] {
+        [self.row(0), self.row(1), self.row(2), self.row(3)]
+    }
+
+    //fi of_rows
+    #[inline]
+    fn of_rows(rows: [F32x4; 4]) -> Self {
+        let mut data = [0.; 16];
+        for (d, r) in data.chunks_exact_mut(4).zip(rows.iter()) {
+            d.copy_from_slice(&r.0);
+        }
+        Self(data)
+    }
+
+    //fi map_rows
+    #[inline]
+    fn map_rows<F: Fn(F32x4) -> F32x4>(&self, f: F) -> Self {
+        Self::of_rows(self.rows().map(f))
+    }
+
+    //fi zip_rows
+    #[inline]
+    fn zip_rows<F: Fn(F32x4, F32x4) -> F32x4>(&self, other: &Self, f: F) -> Self {
+        let a = self.rows();
+        let b = other.rows();
+        Self::of_rows([f(a[0], b[0]), f(a[1], b[1]), f(a[2], b[2]), f(a[3], b[3])])
+    }
+}
+
+//ip Add, Sub, Mul, Div for F32x4Mat4
+impl std::ops::Add<Self> for F32x4Mat4 {
+    type Output = Self;
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        self.zip_rows(&other, |a, b| a + b)
+    }
+}
+impl std::ops::AddAssign<Self> for F32x4Mat4 {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+impl std::ops::Sub<Self> for F32x4Mat4 {
+    type Output = Self;
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        self.zip_rows(&other, |a, b| a - b)
+    }
+}
+impl std::ops::SubAssign<Self> for F32x4Mat4 {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+impl std::ops::Mul<Self> for F32x4Mat4 {
+    type Output = Self;
+    /// Matrix multiplication; each row of the result is the sum of
+    /// the rows of `other` scaled by the elements of the row of `self`
+    #[inline]
+    fn mul(self, other: Self) -> Self {
+        let b = other.rows();
+        self.map_rows(|a| {
+            F32x4::splat(a.0[0]) * b[0]
+                + F32x4::splat(a.0[1]) * b[1]
+                + F32x4::splat(a.0[2]) * b[2]
+                + F32x4::splat(a.0[3]) * b[3]
+        })
+    }
+}
+impl std::ops::MulAssign<Self> for F32x4Mat4 {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+impl std::ops::Mul<f32> for F32x4Mat4 {
+    type Output = Self;
+    #[inline]
+    fn mul(self, other: f32) -> Self {
+        self.map_rows(|a| a * F32x4::splat(other))
+    }
+}
+impl std::ops::MulAssign<f32> for F32x4Mat4 {
+    #[inline]
+    fn mul_assign(&mut self, other: f32) {
+        *self = *self * other;
+    }
+}
+impl std::ops::Div<f32> for F32x4Mat4 {
+    type Output = Self;
+    #[inline]
+    fn div(self, other: f32) -> Self {
+        self.map_rows(|a| a / F32x4::splat(other))
+    }
+}
+impl std::ops::DivAssign<f32> for F32x4Mat4 {
+    #[inline]
+    fn div_assign(&mut self, other: f32) {
+        *self = *self / other;
+    }
+}
+
+//ip AsRef, AsMut for F32x4Mat4
+impl std::convert::AsRef<[f32; 16]> for F32x4Mat4 {
+    #[inline]
+    fn as_ref(&self) -> &[f32; 16] {
+        &self.0
+    }
+}
+impl std::convert::AsRef<[f32]> for F32x4Mat4 {
+    #[inline]
+    fn as_ref(&self) -> &[f32] {
+        &self.0
+    }
+}
+impl std::convert::AsMut<[f32; 16]> for F32x4Mat4 {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [f32; 16] {
+        &mut self.0
+    }
+}
+impl std::convert::AsMut<[f32]> for F32x4Mat4 {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [f32] {
+        &mut self.0
+    }
+}
+
+//ip Index, IndexMut for F32x4Mat4
+impl std::ops::Index<usize> for F32x4Mat4 {
+    type Output = f32;
+    #[inline]
+    fn index(&self, index: usize) -> &f32 {
+        &self.0[index]
+    }
+}
+impl std::ops::IndexMut<usize> for F32x4Mat4 {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        &mut self.0[index]
+    }
+}
+
+//ip From<[f32;16]> for F32x4Mat4, and From<F32x4Mat4> for [f32;16]
+impl From<[f32; 16]> for F32x4Mat4 {
+    #[inline]
+    fn from(data: [f32; 16]) -> Self {
+        Self(data)
+    }
+}
+impl From<F32x4Mat4> for [f32; 16] {
+    #[inline]
+    fn from(m: F32x4Mat4) -> [f32; 16] {
+        m.0
+    }
+}
+
+//ip SqMatrix for F32x4Mat4
+impl SqMatrix<F32x4Vec4, f32, 4, 16> for F32x4Mat4 {
+    #[inline]
+    fn from_array(data: [f32; 16]) -> Self {
+        Self(data)
+    }
+    #[inline]
+    fn into_array(self) -> [f32; 16] {
+        self.0
+    }
+    #[inline]
+    fn identity() -> Self {
+        Self(FArray2::<f32, 4, 16>::identity().into_array())
+    }
+    #[inline]
+    fn zero() -> Self {
+        Self([0.; 16])
+    }
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(|f| *f == 0.)
+    }
+    #[inline]
+    fn set_zero(&mut self) {
+        self.0 = [0.; 16];
+    }
+    #[inline]
+    fn transpose(&self) -> Self {
+        Self::of_rows(F32x4::transpose4(self.rows()))
+    }
+    #[inline]
+    fn determinant(&self) -> f32 {
+        matrix::determinant4(&self.0)
+    }
+    #[inline]
+    fn inverse(&self) -> Self {
+        self.try_inverse(f32::EPSILON).unwrap_or_default()
+    }
+    #[inline]
+    fn try_inverse(&self, epsilon: f32) -> Result<Self, SingularMatrix<f32>> {
+        F32x4::inverse4(self.rows(), epsilon).map(Self::of_rows)
+    }
+    /// Multiply each row by the vector, and sum the products of each
+    /// row by transposing them
+    #[inline]
+    fn transform(&self, v: &F32x4Vec4) -> F32x4Vec4 {
+        let [a, b, c, d] = F32x4::transpose4(self.rows().map(|r| r * v.0));
+        F32x4Vec4(a + b + c + d)
+    }
+}
+
+//ip SqMatrix4 for F32x4Mat4
+impl SqMatrix4<f32, F32x4Vec3, F32x4Vec4> for F32x4Mat4 {
+    fn perspective(fov: f32, aspect: f32, near: f32, far: f32) -> Self {
+        Self::from_array(matrix::perspective4(fov, aspect, near, far))
+    }
+    fn look_at(eye: &F32x4Vec3, center: &F32x4Vec3, up: &F32x4Vec3) -> Self {
+        Self::from_array(matrix::look_at4(eye.as_ref(), center.as_ref(), up.as_ref()))
+    }
+    fn translate3(&mut self, by: &F32x4Vec3) {
+        self.0[3] += by[0];
+        self.0[7] += by[1];
+        self.0[11] += by[2];
+    }
+    fn translate4(&mut self, by: &F32x4Vec4) {
+        self.0[3] += by[0];
+        self.0[7] += by[1];
+        self.0[11] += by[2];
+    }
+}
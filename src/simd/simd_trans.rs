@@ -0,0 +1,108 @@
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use super::{F32x4Mat4, F32x4Vec3, F32x4Vec4};
+use crate::{FQArrayTrans, QArray, Quaternion, SingularMatrix, SqMatrix, Transform, Vector};
+
+//a Types
+/// The quaternion type used with the SIMD vector types
+pub type F32x4Quat = QArray<f32, F32x4Vec3, F32x4Vec4>;
+
+//a F32Trans
+//tp F32Trans
+/// A translation . scaling . rotation transformation (an
+/// [FQArrayTrans]) that operates on the SIMD vector types
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct F32Trans(FQArrayTrans<f32>);
+
+//ip Display for F32Trans
+impl std::fmt::Display for F32Trans {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+//ip Transform for F32Trans
+impl Transform<f32, F32x4Vec3, F32x4Vec4, F32x4Mat4, F32x4Quat> for F32Trans {
+    fn of_trs(t: F32x4Vec3, r: F32x4Quat, s: f32) -> Self {
+        Self(FQArrayTrans::of_trs(
+            t.into_array().into(),
+            r.into_array().into(),
+            s,
+        ))
+    }
+    fn scale(&self) -> f32 {
+        self.0.scale()
+    }
+    fn translation(&self) -> F32x4Vec3 {
+        F32x4Vec3::from_array(self.0.translation().into_array())
+    }
+    fn rotation(&self) -> F32x4Quat {
+        F32x4Quat::from_array(self.0.rotation().into_array())
+    }
+    fn inverse(&self) -> Self {
+        Self(self.0.inverse())
+    }
+    fn try_inverse(&self, epsilon: f32) -> Result<Self, SingularMatrix<f32>> {
+        self.0.try_inverse(epsilon).map(Self)
+    }
+    fn invert(&mut self) {
+        self.0.invert()
+    }
+    fn as_mat(&self) -> F32x4Mat4 {
+        F32x4Mat4::from_array(self.0.as_mat().into_array())
+    }
+}
+
+//ip Mul<Self> for F32Trans
+impl std::ops::Mul<Self> for F32Trans {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self(self.0 * other.0)
+    }
+}
+impl std::ops::MulAssign<Self> for F32Trans {
+    fn mul_assign(&mut self, other: Self) {
+        self.0 *= other.0;
+    }
+}
+
+//ip Mul<F32x4Quat> for F32Trans
+impl std::ops::Mul<F32x4Quat> for F32Trans {
+    type Output = Self;
+    fn mul(self, other: F32x4Quat) -> Self {
+        Self(self.0 * QArray::from_array(other.into_array()))
+    }
+}
+impl std::ops::MulAssign<F32x4Quat> for F32Trans {
+    fn mul_assign(&mut self, other: F32x4Quat) {
+        *self = *self * other;
+    }
+}
+
+//ip Mul<f32> for F32Trans
+impl std::ops::Mul<f32> for F32Trans {
+    type Output = Self;
+    fn mul(self, other: f32) -> Self {
+        Self(self.0 * other)
+    }
+}
+impl std::ops::MulAssign<f32> for F32Trans {
+    fn mul_assign(&mut self, other: f32) {
+        self.0 *= other;
+    }
+}
+
+//ip Add<F32x4Vec3> for F32Trans
+impl std::ops::Add<F32x4Vec3> for F32Trans {
+    type Output = Self;
+    fn add(self, other: F32x4Vec3) -> Self {
+        Self(self.0 + other.into_array().into())
+    }
+}
+impl std::ops::AddAssign<F32x4Vec3> for F32Trans {
+    fn add_assign(&mut self, other: F32x4Vec3) {
+        *self = *self + other;
+    }
+}
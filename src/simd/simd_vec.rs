@@ -1,364 +1,433 @@
-use crate::{Vector, Vector3D};
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use super::simd_f32::{F32x2, F32x4};
+use crate::{vector, Vector, Vector3};
 
 //a Macros
-macro_rules! binary_op {
-    { $t:ty, $trait_op:ident, $op:ident, $binop:tt, $trait_assign_op:ident, $assign_op:ident, $assign_binop:tt } => {
-        impl std::ops::$trait_op<$t> for $t {
-            type Output = Self; fn $op(self, other: Self) -> Self { Self(self.0 $binop other.0) }
+//mi vec_f32x4!
+/// Implement a [Vector] of dimension `$d` held in the first `$d`
+/// lanes of an [F32x4]
+///
+/// The unused lanes are kept at zero, so that operations such as
+/// `dot` and `reduce_sum` may operate on the whole register; `$pad`
+/// is the lane values which are one in just the unused lanes, and it
+/// is added to a divisor so that the unused lanes do not become NaN.
+macro_rules! vec_f32x4 {
+    { $t:ident, $d:expr, $pad:expr } => {
+        impl $t {
+            const PAD: F32x4 = F32x4($pad);
+
+            //fi of_float
+            #[inline]
+            fn of_float(f: f32) -> F32x4 {
+                let mut r = F32x4::default();
+                r.0[0..$d].fill(f);
+                r
+            }
         }
-        impl std::ops::$trait_assign_op<$t> for $t {
-            fn $assign_op(&mut self, other: Self) { self.0 $assign_binop other.0; }
+
+        //ip Add, Sub, Mul, Div for $t
+        impl std::ops::Add<Self> for $t {
+            type Output = Self;
+            #[inline]
+            fn add(self, other: Self) -> Self { Self(self.0 + other.0) }
         }
-        impl std::ops::$trait_op<f32> for $t {
-            type Output = Self; fn $op(self, other:f32) -> Self { Self(self.0 $binop other) }
+        impl std::ops::AddAssign<Self> for $t {
+            #[inline]
+            fn add_assign(&mut self, other: Self) { self.0 += other.0; }
         }
-        impl std::ops::$trait_assign_op<f32> for $t {
-            fn $assign_op(&mut self, other:f32) { self.0 $assign_binop other; }
+        impl std::ops::Add<f32> for $t {
+            type Output = Self;
+            #[inline]
+            fn add(self, other: f32) -> Self { Self(self.0 + Self::of_float(other)) }
+        }
+        impl std::ops::AddAssign<f32> for $t {
+            #[inline]
+            fn add_assign(&mut self, other: f32) { self.0 += Self::of_float(other); }
+        }
+        impl std::ops::Sub<Self> for $t {
+            type Output = Self;
+            #[inline]
+            fn sub(self, other: Self) -> Self { Self(self.0 - other.0) }
+        }
+        impl std::ops::SubAssign<Self> for $t {
+            #[inline]
+            fn sub_assign(&mut self, other: Self) { self.0 -= other.0; }
+        }
+        impl std::ops::Sub<f32> for $t {
+            type Output = Self;
+            #[inline]
+            fn sub(self, other: f32) -> Self { Self(self.0 - Self::of_float(other)) }
+        }
+        impl std::ops::SubAssign<f32> for $t {
+            #[inline]
+            fn sub_assign(&mut self, other: f32) { self.0 -= Self::of_float(other); }
+        }
+        impl std::ops::Mul<Self> for $t {
+            type Output = Self;
+            #[inline]
+            fn mul(self, other: Self) -> Self { Self(self.0 * other.0) }
+        }
+        impl std::ops::MulAssign<Self> for $t {
+            #[inline]
+            fn mul_assign(&mut self, other: Self) { self.0 *= other.0; }
+        }
+        impl std::ops::Mul<f32> for $t {
+            type Output = Self;
+            #[inline]
+            fn mul(self, other: f32) -> Self { Self(self.0 * Self::of_float(other)) }
+        }
+        impl std::ops::MulAssign<f32> for $t {
+            #[inline]
+            fn mul_assign(&mut self, other: f32) { self.0 *= Self::of_float(other); }
+        }
+        impl std::ops::Div<Self> for $t {
+            type Output = Self;
+            #[inline]
+            fn div(self, other: Self) -> Self { Self(self.0 / (other.0 + Self::PAD)) }
+        }
+        impl std::ops::DivAssign<Self> for $t {
+            #[inline]
+            fn div_assign(&mut self, other: Self) { self.0 /= other.0 + Self::PAD; }
+        }
+        impl std::ops::Div<f32> for $t {
+            type Output = Self;
+            #[inline]
+            fn div(self, other: f32) -> Self { Self(self.0 / (Self::of_float(other) + Self::PAD)) }
+        }
+        impl std::ops::DivAssign<f32> for $t {
+            #[inline]
+            fn div_assign(&mut self, other: f32) { self.0 /= Self::of_float(other) + Self::PAD; }
         }
-    }
-}
 
-macro_rules! binary_op_n {
-    { $t:ty, $trait_op:ident, $op:ident, $binop:tt, $trait_assign_op:ident, $assign_op:ident, $assign_binop:tt } => {
-        impl std::ops::$trait_op<$t> for $t {
-            type Output = Self; fn $op(self, other: Self) -> Self { Self(self.0 $binop other.0) }
+        //ip Neg for $t
+        impl std::ops::Neg for $t {
+            type Output = Self;
+            #[inline]
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
         }
-        impl std::ops::$trait_assign_op<$t> for $t {
-            fn $assign_op(&mut self, other: Self) { self.0 $assign_binop other.0; }
+
+        //ip AsRef, AsMut for $t
+        impl std::convert::AsRef<[f32; $d]> for $t {
+            #[inline]
+            fn as_ref(&self) -> &[f32; $d] {
+                self.0 .0.first_chunk::<$d>().unwrap()
+            }
         }
-        impl std::ops::$trait_op<f32> for $t {
-            type Output = Self; fn $op(self, other:f32) -> Self { Self(self.0 $binop Self::of_float(other).0) }
+        impl std::convert::AsRef<[f32]> for $t {
+            #[inline]
+            fn as_ref(&self) -> &[f32] {
+                &self.0 .0[0..$d]
+            }
         }
-        impl std::ops::$trait_assign_op<f32> for $t {
-            fn $assign_op(&mut self, other:f32) { self.0 $assign_binop Self::of_float(other).0; }
+        impl std::convert::AsMut<[f32; $d]> for $t {
+            #[inline]
+            fn as_mut(&mut self) -> &mut [f32; $d] {
+                self.0 .0.first_chunk_mut::<$d>().unwrap()
+            }
+        }
+        impl std::convert::AsMut<[f32]> for $t {
+            #[inline]
+            fn as_mut(&mut self) -> &mut [f32] {
+                &mut self.0 .0[0..$d]
+            }
         }
-    }
-}
 
-//a F32x4Vec4
-#[derive(Clone, Copy, Debug)]
-pub struct F32x4Vec4(core_simd::f32x4);
+        //ip Index, IndexMut for $t
+        impl std::ops::Index<usize> for $t {
+            type Output = f32;
+            #[inline]
+            fn index(&self, index: usize) -> &f32 {
+                let x: &[f32] = self.as_ref();
+                &x[index]
+            }
+        }
+        impl std::ops::IndexMut<usize> for $t {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut f32 {
+                let x: &mut [f32] = self.as_mut();
+                &mut x[index]
+            }
+        }
 
-//ip Add for F32x4Vec4
-binary_op! { F32x4Vec4, Add, add, +, AddAssign, add_assign, += }
-binary_op! { F32x4Vec4, Sub, sub, -, SubAssign, sub_assign, -= }
-binary_op! { F32x4Vec4, Mul, mul, *, MulAssign, mul_assign, *= }
-binary_op! { F32x4Vec4, Div, div, /, DivAssign, div_assign, /= }
-impl std::convert::AsRef<[f32; 4]> for F32x4Vec4 {
-    fn as_ref(&self) -> &[f32; 4] {
-        unsafe { std::mem::transmute::<&core_simd::f32x4, &[f32; 4]>(&self.0) }
-    }
-}
-impl std::convert::AsRef<[f32]> for F32x4Vec4 {
-    fn as_ref(&self) -> &[f32] {
-        unsafe { std::mem::transmute::<&core_simd::f32x4, &[f32; 4]>(&self.0) }
-    }
-}
-impl std::convert::AsMut<[f32; 4]> for F32x4Vec4 {
-    fn as_mut(&mut self) -> &mut [f32; 4] {
-        unsafe { std::mem::transmute::<&mut core_simd::f32x4, &mut [f32; 4]>(&mut self.0) }
-    }
-}
-impl std::convert::AsMut<[f32]> for F32x4Vec4 {
-    fn as_mut(&mut self) -> &mut [f32] {
-        unsafe { std::mem::transmute::<&mut core_simd::f32x4, &mut [f32; 4]>(&mut self.0) }
-    }
-}
-impl std::default::Default for F32x4Vec4 {
-    fn default() -> Self {
-        Self(core_simd::f32x4::default())
-    }
-}
-impl std::ops::Index<usize> for F32x4Vec4 {
-    type Output = f32;
-    fn index(&self, index: usize) -> &f32 {
-        let x: &[f32] = self.as_ref();
-        &x[index]
-    }
-}
-impl std::ops::IndexMut<usize> for F32x4Vec4 {
-    fn index_mut(&mut self, index: usize) -> &mut f32 {
-        let x: &mut [f32] = self.as_mut();
-        &mut x[index]
-    }
-}
-impl std::ops::Neg for F32x4Vec4 {
-    type Output = Self;
-    fn neg(self) -> Self {
-        Self(-self.0)
-    }
-}
+        //ip Display for $t
+        impl std::fmt::Display for $t {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                vector::fmt(f, <Self as AsRef<[f32]>>::as_ref(self))
+            }
+        }
 
-//ip Vector<f32, 4> for F32x4Vec4
-impl Vector<f32, 4> for F32x4Vec4 {
-    fn from_array(data: [f32; 4]) -> Self {
-        Self(core_simd::f32x4::from_array(data))
-    }
-    fn zero() -> Self {
-        Self(core_simd::f32x4::splat(0.))
-    }
-    fn is_zero(&self) -> bool {
-        self.0.lanes_eq(core_simd::f32x4::splat(0.)).all()
-    }
-    fn set_zero(&mut self) {
-        self.0 = core_simd::f32x4::splat(0.)
-    }
-    fn mix(&self, other: &Self, t: f32) -> Self {
-        Self(self.0 * (1.0 - t) + other.0 * t)
-    }
-    fn reduce_sum(&self) -> f32 {
-        self.0.horizontal_sum()
-    }
-    fn dot(&self, other: &Self) -> f32 {
-        (self.0 * other.0).horizontal_sum()
+        //ip Serialize, Deserialize for $t
+        impl Serialize for $t {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                <Self as AsRef<[f32; $d]>>::as_ref(self).serialize(serializer)
+            }
+        }
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<DE>(deserializer: DE) -> Result<Self, DE::Error>
+            where
+                DE: serde::Deserializer<'de>,
+            {
+                let data = <[f32; $d]>::deserialize(deserializer)?;
+                Ok(Self::from_array(data))
+            }
+        }
+
+        //ip From<[f32;D]> for $t, and From<$t> for [f32;D]
+        impl From<[f32; $d]> for $t {
+            #[inline]
+            fn from(data: [f32; $d]) -> Self {
+                Self::from_array(data)
+            }
+        }
+        impl From<$t> for [f32; $d] {
+            #[inline]
+            fn from(v: $t) -> [f32; $d] {
+                v.into_array()
+            }
+        }
+
+        //ip Vector<f32, D> for $t
+        impl Vector<f32, $d> for $t {
+            #[inline]
+            fn from_array(data: [f32; $d]) -> Self {
+                let mut r = F32x4::default();
+                r.0[0..$d].copy_from_slice(&data);
+                Self(r)
+            }
+            #[inline]
+            fn into_array(self) -> [f32; $d] {
+                *self.0 .0.first_chunk::<$d>().unwrap()
+            }
+            #[inline]
+            fn zero() -> Self {
+                Self(F32x4::default())
+            }
+            #[inline]
+            fn is_zero(&self) -> bool {
+                self.0 == F32x4::default()
+            }
+            #[inline]
+            fn set_zero(&mut self) {
+                self.0 = F32x4::default();
+            }
+            #[inline]
+            fn mix(self, other: &Self, t: f32) -> Self {
+                Self(self.0 * F32x4::splat(1.0 - t) + other.0 * F32x4::splat(t))
+            }
+            #[inline]
+            fn reduce_sum(&self) -> f32 {
+                self.0.sum()
+            }
+            #[inline]
+            fn dot(&self, other: &Self) -> f32 {
+                self.0.dot(other.0)
+            }
+        }
     }
 }
 
+//a F32x4Vec4
+//tp F32x4Vec4
+/// A 4-element [Vector] of `f32` held in a single 16-byte aligned SIMD register
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct F32x4Vec4(pub(super) F32x4);
+
+vec_f32x4! { F32x4Vec4, 4, [0., 0., 0., 0.] }
+
 //a F32x4Vec3
-#[derive(Clone, Copy, Debug)]
-pub struct F32x4Vec3(core_simd::f32x4);
+//tp F32x4Vec3
+/// A 3-element [Vector] of `f32` held in a single 16-byte aligned
+/// SIMD register, with the fourth lane unused (and kept at zero)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct F32x4Vec3(F32x4);
 
-//ip Add for F32x4Vec3
-binary_op_n! { F32x4Vec3, Add, add, +, AddAssign, add_assign, += }
-binary_op_n! { F32x4Vec3, Sub, sub, -, SubAssign, sub_assign, -= }
-binary_op_n! { F32x4Vec3, Mul, mul, *, MulAssign, mul_assign, *= }
-binary_op! { F32x4Vec3, Div, div, /, DivAssign, div_assign, /= }
-impl std::convert::AsRef<[f32; 3]> for F32x4Vec3 {
-    fn as_ref(&self) -> &[f32; 3] {
-        unsafe { std::mem::transmute::<&core_simd::f32x4, &[f32; 3]>(&self.0) }
-    }
-}
-impl std::convert::AsRef<[f32]> for F32x4Vec3 {
-    fn as_ref(&self) -> &[f32] {
-        unsafe { std::mem::transmute::<&core_simd::f32x4, &[f32; 3]>(&self.0) }
-    }
-}
-impl std::convert::AsMut<[f32; 3]> for F32x4Vec3 {
-    fn as_mut(&mut self) -> &mut [f32; 3] {
-        unsafe { std::mem::transmute::<&mut core_simd::f32x4, &mut [f32; 3]>(&mut self.0) }
-    }
-}
-impl std::convert::AsMut<[f32]> for F32x4Vec3 {
-    fn as_mut(&mut self) -> &mut [f32] {
-        unsafe { std::mem::transmute::<&mut core_simd::f32x4, &mut [f32; 3]>(&mut self.0) }
-    }
-}
-impl std::default::Default for F32x4Vec3 {
-    fn default() -> Self {
-        Self(core_simd::f32x4::default())
-    }
-}
-impl std::ops::Index<usize> for F32x4Vec3 {
-    type Output = f32;
-    fn index(&self, index: usize) -> &f32 {
-        let x: &[f32] = self.as_ref();
-        &x[index]
-    }
-}
-impl std::ops::IndexMut<usize> for F32x4Vec3 {
-    fn index_mut(&mut self, index: usize) -> &mut f32 {
-        let x: &mut [f32] = self.as_mut();
-        &mut x[index]
-    }
-}
-impl std::ops::Neg for F32x4Vec3 {
-    type Output = Self;
-    fn neg(self) -> Self {
-        Self(-self.0)
-    }
-}
+vec_f32x4! { F32x4Vec3, 3, [0., 0., 0., 1.] }
 
-//ip Vector<f32, 4> for F32x4Vec3
-impl F32x4Vec3 {
-    fn of_float(f: f32) -> Self {
-        Self::from_array([f, f, f])
-    }
-}
-impl Vector<f32, 3> for F32x4Vec3 {
-    fn from_array(data: [f32; 3]) -> Self {
-        Self(core_simd::f32x4::from_array([
-            data[0], data[1], data[2], 0.,
-        ]))
-    }
-    fn zero() -> Self {
-        Self(core_simd::f32x4::splat(0.))
-    }
-    fn is_zero(&self) -> bool {
-        self.0.lanes_eq(core_simd::f32x4::splat(0.)).all()
-    }
-    fn set_zero(&mut self) {
-        self.0 = core_simd::f32x4::splat(0.)
-    }
-    fn mix(&self, other: &Self, t: f32) -> Self {
-        Self(self.0 * (1.0 - t) + other.0 * t)
-    }
-    fn reduce_sum(&self) -> f32 {
-        self.0.horizontal_sum()
-    }
-    fn dot(&self, other: &Self) -> f32 {
-        (self.0 * other.0).horizontal_sum()
+//ip Vector3<f32> for F32x4Vec3
+impl Vector3<f32> for F32x4Vec3 {
+    #[inline]
+    fn cross_product(&self, other: &Self) -> Self {
+        Self(self.0.cross3(other.0))
     }
 }
 
 //a F32x4Vec2
-#[derive(Clone, Copy, Debug)]
-pub struct F32x4Vec2(core_simd::f32x4);
+//tp F32x4Vec2
+/// A 2-element [Vector] of `f32` held in a single 16-byte aligned
+/// SIMD register, with the third and fourth lanes unused (and kept at
+/// zero)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct F32x4Vec2(F32x4);
 
-//ip Add for F32x4Vec2
-binary_op_n! { F32x4Vec2, Add, add, +, AddAssign, add_assign, += }
-binary_op_n! { F32x4Vec2, Sub, sub, -, SubAssign, sub_assign, -= }
-binary_op_n! { F32x4Vec2, Mul, mul, *, MulAssign, mul_assign, *= }
-binary_op! { F32x4Vec2, Div, div, /, DivAssign, div_assign, /= }
-impl std::convert::AsRef<[f32; 2]> for F32x4Vec2 {
-    fn as_ref(&self) -> &[f32; 2] {
-        unsafe { std::mem::transmute::<&core_simd::f32x4, &[f32; 2]>(&self.0) }
-    }
-}
-impl std::convert::AsRef<[f32]> for F32x4Vec2 {
-    fn as_ref(&self) -> &[f32] {
-        unsafe { std::mem::transmute::<&core_simd::f32x4, &[f32; 2]>(&self.0) }
-    }
-}
-impl std::convert::AsMut<[f32; 2]> for F32x4Vec2 {
-    fn as_mut(&mut self) -> &mut [f32; 2] {
-        unsafe { std::mem::transmute::<&mut core_simd::f32x4, &mut [f32; 2]>(&mut self.0) }
-    }
-}
-impl std::convert::AsMut<[f32]> for F32x4Vec2 {
-    fn as_mut(&mut self) -> &mut [f32] {
-        unsafe { std::mem::transmute::<&mut core_simd::f32x4, &mut [f32; 2]>(&mut self.0) }
-    }
-}
-impl std::default::Default for F32x4Vec2 {
-    fn default() -> Self {
-        Self(core_simd::f32x4::default())
-    }
-}
-impl std::ops::Index<usize> for F32x4Vec2 {
-    type Output = f32;
-    fn index(&self, index: usize) -> &f32 {
-        let x: &[f32] = self.as_ref();
-        &x[index]
-    }
-}
-impl std::ops::IndexMut<usize> for F32x4Vec2 {
-    fn index_mut(&mut self, index: usize) -> &mut f32 {
-        let x: &mut [f32] = self.as_mut();
-        &mut x[index]
+vec_f32x4! { F32x4Vec2, 2, [0., 0., 1., 1.] }
+
+//a F32x2Vec2
+//tp F32x2Vec2
+/// A 2-element [Vector] of `f32` held in an 8-byte aligned pair
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct F32x2Vec2(F32x2);
+
+//ip Add, Sub, Mul, Div for F32x2Vec2
+macro_rules! binary_op {
+    { $t:ty, $trait_op:ident, $op:ident, $binop:tt, $trait_assign_op:ident, $assign_op:ident, $assign_binop:tt } => {
+        impl std::ops::$trait_op<$t> for $t {
+            type Output = Self;
+            #[inline]
+            fn $op(self, other: Self) -> Self { Self(self.0 $binop other.0) }
+        }
+        impl std::ops::$trait_assign_op<$t> for $t {
+            #[inline]
+            fn $assign_op(&mut self, other: Self) { self.0 $assign_binop other.0; }
+        }
+        impl std::ops::$trait_op<f32> for $t {
+            type Output = Self;
+            #[inline]
+            fn $op(self, other:f32) -> Self { Self(self.0 $binop F32x2::splat(other)) }
+        }
+        impl std::ops::$trait_assign_op<f32> for $t {
+            #[inline]
+            fn $assign_op(&mut self, other:f32) { self.0 $assign_binop F32x2::splat(other); }
+        }
     }
 }
-impl std::ops::Neg for F32x4Vec2 {
+binary_op! { F32x2Vec2, Add, add, +, AddAssign, add_assign, += }
+binary_op! { F32x2Vec2, Sub, sub, -, SubAssign, sub_assign, -= }
+binary_op! { F32x2Vec2, Mul, mul, *, MulAssign, mul_assign, *= }
+binary_op! { F32x2Vec2, Div, div, /, DivAssign, div_assign, /= }
+
+//ip Neg for F32x2Vec2
+impl std::ops::Neg for F32x2Vec2 {
     type Output = Self;
+    #[inline]
     fn neg(self) -> Self {
         Self(-self.0)
     }
 }
 
-//ip Vector<f32, 4> for F32x4Vec2
-impl F32x4Vec2 {
-    fn of_float(f: f32) -> Self {
-        Self::from_array([f, f])
-    }
-}
-impl Vector<f32, 2> for F32x4Vec2 {
-    fn from_array(data: [f32; 2]) -> Self {
-        Self(core_simd::f32x4::from_array([data[0], data[1], 0., 0.]))
-    }
-    fn zero() -> Self {
-        Self(core_simd::f32x4::splat(0.))
-    }
-    fn is_zero(&self) -> bool {
-        self.0.lanes_eq(core_simd::f32x4::splat(0.)).all()
-    }
-    fn set_zero(&mut self) {
-        self.0 = core_simd::f32x4::splat(0.)
-    }
-    fn mix(&self, other: &Self, t: f32) -> Self {
-        Self(self.0 * (1.0 - t) + other.0 * t)
-    }
-    fn reduce_sum(&self) -> f32 {
-        self.0.horizontal_sum()
-    }
-    fn dot(&self, other: &Self) -> f32 {
-        (self.0 * other.0).horizontal_sum()
-    }
-}
-
-//a F32x2Vec2
-#[derive(Clone, Copy, Debug)]
-pub struct F32x2Vec2(core_simd::f32x2);
-
-//ip Add for F32x2Vec2
-binary_op! { F32x2Vec2, Add, add, +, AddAssign, add_assign, += }
-binary_op! { F32x2Vec2, Sub, sub, -, SubAssign, sub_assign, -= }
-binary_op! { F32x2Vec2, Mul, mul, *, MulAssign, mul_assign, *= }
-binary_op! { F32x2Vec2, Div, div, /, DivAssign, div_assign, /= }
+//ip AsRef, AsMut for F32x2Vec2
 impl std::convert::AsRef<[f32; 2]> for F32x2Vec2 {
+    #[inline]
     fn as_ref(&self) -> &[f32; 2] {
-        unsafe { std::mem::transmute::<&core_simd::f32x2, &[f32; 2]>(&self.0) }
+        &self.0 .0
     }
 }
 impl std::convert::AsRef<[f32]> for F32x2Vec2 {
+    #[inline]
     fn as_ref(&self) -> &[f32] {
-        unsafe { std::mem::transmute::<&core_simd::f32x2, &[f32; 2]>(&self.0) }
+        &self.0 .0
     }
 }
 impl std::convert::AsMut<[f32; 2]> for F32x2Vec2 {
+    #[inline]
     fn as_mut(&mut self) -> &mut [f32; 2] {
-        unsafe { std::mem::transmute::<&mut core_simd::f32x2, &mut [f32; 2]>(&mut self.0) }
+        &mut self.0 .0
     }
 }
 impl std::convert::AsMut<[f32]> for F32x2Vec2 {
+    #[inline]
     fn as_mut(&mut self) -> &mut [f32] {
-        unsafe { std::mem::transmute::<&mut core_simd::f32x2, &mut [f32; 2]>(&mut self.0) }
-    }
-}
-impl std::default::Default for F32x2Vec2 {
-    fn default() -> Self {
-        Self(core_simd::f32x2::default())
+        &mut self.0 .0
     }
 }
+
+//ip Index, IndexMut for F32x2Vec2
 impl std::ops::Index<usize> for F32x2Vec2 {
     type Output = f32;
+    #[inline]
     fn index(&self, index: usize) -> &f32 {
-        let x: &[f32] = self.as_ref();
-        &x[index]
+        &self.0 .0[index]
     }
 }
 impl std::ops::IndexMut<usize> for F32x2Vec2 {
+    #[inline]
     fn index_mut(&mut self, index: usize) -> &mut f32 {
-        let x: &mut [f32] = self.as_mut();
-        &mut x[index]
+        &mut self.0 .0[index]
     }
 }
-impl std::ops::Neg for F32x2Vec2 {
-    type Output = Self;
-    fn neg(self) -> Self {
-        Self(-self.0)
+
+//ip Display for F32x2Vec2
+impl std::fmt::Display for F32x2Vec2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        vector::fmt(f, &self.0 .0)
+    }
+}
+
+//ip Serialize, Deserialize for F32x2Vec2
+impl Serialize for F32x2Vec2 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0 .0.serialize(serializer)
+    }
+}
+impl<'de> Deserialize<'de> for F32x2Vec2 {
+    fn deserialize<DE>(deserializer: DE) -> Result<Self, DE::Error>
+    where
+        DE: serde::Deserializer<'de>,
+    {
+        let data = <[f32; 2]>::deserialize(deserializer)?;
+        Ok(Self::from_array(data))
+    }
+}
+
+//ip From<[f32;2]> for F32x2Vec2, and From<F32x2Vec2> for [f32;2]
+impl From<[f32; 2]> for F32x2Vec2 {
+    #[inline]
+    fn from(data: [f32; 2]) -> Self {
+        Self::from_array(data)
+    }
+}
+impl From<F32x2Vec2> for [f32; 2] {
+    #[inline]
+    fn from(v: F32x2Vec2) -> [f32; 2] {
+        v.into_array()
     }
 }
 
 //ip Vector<f32, 2> for F32x2Vec2
 impl Vector<f32, 2> for F32x2Vec2 {
+    #[inline]
     fn from_array(data: [f32; 2]) -> Self {
-        Self(core_simd::f32x2::from_array(data))
+        Self(F32x2(data))
+    }
+    #[inline]
+    fn into_array(self) -> [f32; 2] {
+        self.0 .0
     }
+    #[inline]
     fn zero() -> Self {
-        Self(core_simd::f32x2::splat(0.))
+        Self(F32x2::default())
     }
+    #[inline]
     fn is_zero(&self) -> bool {
-        self.0.lanes_eq(core_simd::f32x2::splat(0.)).all()
+        self.0 == F32x2::default()
     }
+    #[inline]
     fn set_zero(&mut self) {
-        self.0 = core_simd::f32x2::splat(0.)
+        self.0 = F32x2::default();
     }
-    fn mix(&self, other: &Self, t: f32) -> Self {
-        Self(self.0 * (1.0 - t) + other.0 * t)
+    #[inline]
+    fn mix(self, other: &Self, t: f32) -> Self {
+        Self(self.0 * F32x2::splat(1.0 - t) + other.0 * F32x2::splat(t))
     }
+    #[inline]
     fn reduce_sum(&self) -> f32 {
-        self.0.horizontal_sum()
+        self.0.sum()
     }
+    #[inline]
     fn dot(&self, other: &Self) -> f32 {
-        (self.0 * other.0).horizontal_sum()
+        self.0.dot(other.0)
     }
 }
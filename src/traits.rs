@@ -17,7 +17,9 @@ limitations under the License.
  */
 
 //a Imports
-use crate::{quat, vector};
+use crate::matrixr_op as matrixr;
+use crate::{dquat, matrix, quat, vector};
+use crate::{ClipSpace, DepthRange, EulerOrder, MatrixDecomposition, SingularMatrix};
 
 //a Num and Float traits
 //tp Num
@@ -251,6 +253,113 @@ pub trait Vector<F: Float, const D: usize>:
     }
 }
 
+//tt Matrix
+/// The [Matrix] trait describes an R-by-C matrix of [Float], held
+/// row-major in an array of RC (= R*C) elements.
+///
+/// This trait is not stable.
+///
+/// Such [Matrix] support addition and subtraction of matrices of the
+/// same shape, and multiplication and division of all components by
+/// a scalar [Float] value. Matrix multiplication, whose output shape
+/// depends on both of the inputs, is provided by the implementing
+/// types.
+///
+/// Square matrices are better described by [SqMatrix], which
+/// provides identity, determinant, inverse and so on.
+pub trait Matrix<F: Float, const R: usize, const C: usize, const RC: usize>:
+    Clone
+    + Copy
+    + std::fmt::Debug
+    + std::fmt::Display
+    + std::default::Default
+    + std::convert::AsRef<[F; RC]>
+    + std::convert::AsMut<[F; RC]>
+    + std::convert::AsRef<[F]>
+    + std::convert::AsMut<[F]>
+    + std::ops::Index<usize, Output = F>
+    + std::ops::IndexMut<usize>
+    + std::ops::Neg<Output = Self>
+    + std::ops::Add<Output = Self>
+    + std::ops::AddAssign
+    + std::ops::Sub<Output = Self>
+    + std::ops::SubAssign
+    + std::ops::Mul<F, Output = Self>
+    + std::ops::MulAssign<F>
+    + std::ops::Div<F, Output = Self>
+    + std::ops::DivAssign<F>
+{
+    /// The C-by-R matrix type that is the transpose of this
+    type Transpose: Matrix<F, C, R, RC>;
+
+    //fp from_array
+    /// Create a [Matrix] from a row-major array of [Float]s
+    #[must_use]
+    fn from_array(data: [F; RC]) -> Self;
+
+    //fp into_array
+    /// Convert the [Matrix] into its row-major array of [Float]s
+    #[must_use]
+    fn into_array(self) -> [F; RC];
+
+    //fp zero
+    /// Create a zero [Matrix]
+    #[must_use]
+    fn zero() -> Self;
+
+    //fp is_zero
+    /// Return true if the matrix is zero
+    fn is_zero(&self) -> bool {
+        let data: &[F] = self.as_ref();
+        data.iter().all(|f| f.is_zero())
+    }
+
+    //fp set_zero
+    /// Set the matrix to zero
+    fn set_zero(&mut self) {
+        *self = Self::zero();
+    }
+
+    //mp element
+    /// Get the element at row `r` and column `c`
+    fn element(&self, r: usize, c: usize) -> F {
+        self[r * C + c]
+    }
+
+    //mp row
+    /// Get row `r` of the matrix
+    fn row(&self, r: usize) -> [F; C] {
+        let mut v = [F::zero(); C];
+        for (c, v) in v.iter_mut().enumerate() {
+            *v = self[r * C + c];
+        }
+        v
+    }
+
+    //mp column
+    /// Get column `c` of the matrix
+    fn column(&self, c: usize) -> [F; R] {
+        let mut v = [F::zero(); R];
+        for (r, v) in v.iter_mut().enumerate() {
+            *v = self[r * C + c];
+        }
+        v
+    }
+
+    //mp transpose
+    /// Return the transpose of the matrix
+    fn transpose(&self) -> Self::Transpose {
+        Self::Transpose::from_array(matrixr::transpose::<F, RC, R, C>(self.into_array()))
+    }
+
+    //mp transform_vec
+    /// Apply the matrix to a C-element column vector, to produce an
+    /// R-element vector
+    fn transform_vec(&self, v: &[F; C]) -> [F; R] {
+        matrixr::transform_vec::<F, RC, R, C>(self.as_ref(), v)
+    }
+}
+
 //tt SqMatrix
 /// The [SqMatrix] trait describes an N-dimensional square matrix of [Float] type that operates on a [Vector].
 ///
@@ -326,9 +435,33 @@ pub trait SqMatrix<V: Vector<F, D>, F: Float, const D: usize, const D2: usize>:
     /// Create an inverse matrix
     fn inverse(&self) -> Self;
 
+    //mp try_inverse
+    /// Create an inverse matrix, or return an error if the magnitude
+    /// of the determinant is no more than `epsilon`
+    fn try_inverse(&self, epsilon: F) -> Result<Self, SingularMatrix<F>> {
+        let determinant = self.determinant();
+        if determinant.abs() <= epsilon {
+            Err(SingularMatrix { determinant })
+        } else {
+            Ok(self.inverse())
+        }
+    }
+
     //mp transform
     /// Apply the matrix to a vector to transform it
     fn transform(&self, v: &V) -> V;
+
+    //mp symmetric_eigen
+    /// Find the eigenvalues and eigenvectors of the matrix, which must
+    /// be symmetric (only its upper triangle is used)
+    ///
+    /// This returns the eigenvalues in decreasing order, and a matrix
+    /// whose columns are the corresponding orthonormal eigenvectors;
+    /// see [crate::matrix::symmetric_eigen]
+    fn symmetric_eigen(&self) -> (V, Self) {
+        let (l, e) = matrix::symmetric_eigen::<F, D2, D>(self.as_ref());
+        (V::from_array(l), Self::from_array(e))
+    }
 }
 
 //tt Vector3
@@ -354,6 +487,17 @@ pub trait Vector3<F: Float>: Vector<F, 3> {
 pub trait SqMatrix3<V3: Vector<F, 3>, F: Float>: SqMatrix<V3, F, 3, 9> {
     // fn invert(&mut self);
     // fn inverse(&self) -> Self;
+
+    //mp svd
+    /// Find the singular value decomposition of the matrix
+    ///
+    /// The result is (U, s, V), with U and V orthogonal and s the
+    /// singular values in decreasing order, such that the matrix is
+    /// U . diag(s) . transpose(V); see [crate::matrix::svd3]
+    fn svd(&self) -> (Self, V3, Self) {
+        let (u, s, v) = matrix::svd3(self.as_ref());
+        (Self::from_array(u), V3::from_array(s), Self::from_array(v))
+    }
 }
 
 //tt SqMatrix4
@@ -362,9 +506,90 @@ pub trait SqMatrix3<V3: Vector<F, 3>, F: Float>: SqMatrix<V3, F, 3, 9> {
 pub trait SqMatrix4<F: Float, V3: Vector<F, 3>, V4: Vector<F, 4>>: SqMatrix<V4, F, 4, 16> {
     // fn invert(&mut self);
     // fn inverse(&self) -> Self;
-    /// Generate a perspective matrix
+    /// Generate a perspective matrix for the OpenGL clip space, in the
+    /// column-major layout of [crate::matrix::perspective4]; for the
+    /// row-major form use [SqMatrix4::perspective_fov]
     fn perspective(fov: F, aspect: F, near: F, far: F) -> Self;
 
+    //cp perspective_fov
+    /// Generate a symmetric perspective matrix for a clip space from a
+    /// vertical field of view and aspect ratio; see
+    /// [crate::matrix::perspective_fov4]
+    #[must_use]
+    fn perspective_fov(clip: ClipSpace, fov: F, aspect: F, near: F, far: F) -> Self {
+        Self::from_array(matrix::perspective_fov4(clip, fov, aspect, near, far))
+    }
+
+    //cp infinite_perspective
+    /// Generate a symmetric perspective matrix for a clip space with
+    /// the far plane at infinity; see
+    /// [crate::matrix::infinite_perspective4]
+    #[must_use]
+    fn infinite_perspective(clip: ClipSpace, fov: F, aspect: F, near: F) -> Self {
+        Self::from_array(matrix::infinite_perspective4(clip, fov, aspect, near))
+    }
+
+    //cp frustum
+    /// Generate a perspective matrix for a clip space from the extent
+    /// of the near plane; see [crate::matrix::frustum4]
+    #[must_use]
+    fn frustum(clip: ClipSpace, left: F, right: F, bottom: F, top: F, near: F, far: F) -> Self {
+        Self::from_array(matrix::frustum4(clip, left, right, bottom, top, near, far))
+    }
+
+    //cp orthographic
+    /// Generate an orthographic projection matrix for a clip space;
+    /// see [crate::matrix::orthographic4]
+    #[must_use]
+    fn orthographic(
+        clip: ClipSpace,
+        left: F,
+        right: F,
+        bottom: F,
+        top: F,
+        near: F,
+        far: F,
+    ) -> Self {
+        Self::from_array(matrix::orthographic4(
+            clip, left, right, bottom, top, near, far,
+        ))
+    }
+
+    //mp project
+    /// Map an object-space point to window coordinates for a viewport
+    /// (x, y, width, height), where this is the model-view-projection
+    /// matrix; see [crate::matrix::project]
+    ///
+    /// Returns None if the point is on the plane of the viewer
+    #[must_use]
+    fn project(&self, obj: &V3, viewport: &V4, depth: DepthRange) -> Option<V3> {
+        matrix::project_mvp(obj.as_ref(), self.as_ref(), viewport.as_ref(), depth)
+            .map(V3::from_array)
+    }
+
+    //mp unproject
+    /// Map window coordinates for a viewport (x, y, width, height) to
+    /// an object-space point, where this is the model-view-projection
+    /// matrix; see [crate::matrix::unproject]
+    ///
+    /// Returns None if the matrix is singular or the point is at
+    /// infinity
+    #[must_use]
+    fn unproject(&self, win: &V3, viewport: &V4, depth: DepthRange) -> Option<V3> {
+        matrix::unproject_mvp(win.as_ref(), self.as_ref(), viewport.as_ref(), depth)
+            .map(V3::from_array)
+    }
+
+    //mp pick_ray
+    /// Find the origin and unit direction of the ray through a point
+    /// in normalized device coordinates, where this is the inverse of
+    /// the view-projection matrix; see [crate::matrix::pick_ray]
+    #[must_use]
+    fn pick_ray(&self, x: F, y: F, depth: DepthRange) -> (V3, V3) {
+        let (origin, direction) = matrix::pick_ray(x, y, self.as_ref(), depth);
+        (V3::from_array(origin), V3::from_array(direction))
+    }
+
     /// Generate a matrix that represents a 'look at a vector'
     fn look_at(eye: &V3, center: &V3, up: &V3) -> Self;
 
@@ -373,6 +598,38 @@ pub trait SqMatrix4<F: Float, V3: Vector<F, 3>, V4: Vector<F, 4>>: SqMatrix<V4,
 
     /// Translate the matrix by a Vec4
     fn translate4(&mut self, by: &V4);
+
+    //mp decompose
+    /// Decompose the matrix into its translation, rotation, scaling
+    /// along each axis, shear and perspective row; see
+    /// [MatrixDecomposition] and [crate::matrix::decompose4]
+    ///
+    /// Returns None if the top-left 3-by-3 matrix is singular
+    fn decompose<Q: Quaternion<F, V3, V4>>(&self) -> Option<MatrixDecomposition<V3, Q, V4>> {
+        let d = matrix::decompose4(self.as_ref())?;
+        Some(MatrixDecomposition {
+            translation: V3::from_array(d.translation),
+            rotation: Q::from_array(d.rotation),
+            scale: V3::from_array(d.scale),
+            shear: V3::from_array(d.shear),
+            perspective: V4::from_array(d.perspective),
+        })
+    }
+
+    //cp compose
+    /// Compose a matrix from its translation, rotation, scaling along
+    /// each axis, shear and perspective row; this is the inverse of
+    /// [SqMatrix4::decompose]
+    #[must_use]
+    fn compose<Q: Quaternion<F, V3, V4>>(d: &MatrixDecomposition<V3, Q, V4>) -> Self {
+        Self::from_array(matrix::compose4(&MatrixDecomposition {
+            translation: d.translation.into_array(),
+            rotation: d.rotation.into_array(),
+            scale: d.scale.into_array(),
+            shear: d.shear.into_array(),
+            perspective: d.perspective.into_array(),
+        }))
+    }
 }
 
 //tt Quaternion
@@ -451,6 +708,14 @@ where V3:Vector<F,3>, V4:Vector<F,4>, F:Float
         Self::from_array(quat::of_axis_angle(axis.as_ref(), angle))
     }
 
+    //cp of_euler
+    /// Create a unit quaternion for a rotation given by Euler angles
+    /// applied in the given order
+    #[must_use]
+    fn of_euler(order:EulerOrder, angles:&[F;3]) -> Self {
+        Self::from_array(quat::of_euler(order, angles))
+    }
+
     //cp rotate_x
     /// Apply a rotation about the X-axis to this quaternion
     #[inline]
@@ -514,13 +779,28 @@ where V3:Vector<F,3>, V4:Vector<F,4>, F:Float
     ///
     /// weights need not add up to 1
     ///
-    /// This is an approximation compared to the Landis Markley paper
+    /// This is an approximation compared to the Landis Markley paper;
+    /// see [Quaternion::weighted_average_markley] for the exact average
     #[must_use]
     fn weighted_average_many<I: Iterator<Item = (F, Self)>>(value_iter:I) -> Self {
         let value_iter = value_iter.map(|(w,v)| (w,v.into_array()));
         Self::from_array(quat::weighted_average_many(value_iter))
     }
 
+    //cp weighted_average_markley
+    /// Calculate the weighted average of many unit quaternions using
+    /// the method of the Landis Markley paper, which does not depend
+    /// on the order of the quaternions
+    ///
+    /// Weights need not add up to 1, but must not be negative
+    ///
+    /// Returns None if there are no quaternions (or all the weights are zero)
+    #[must_use]
+    fn weighted_average_markley<I: Iterator<Item = (F, Self)>>(value_iter:I) -> Option<Self> {
+        let value_iter = value_iter.map(|(w,v)| (w,v.into_array()));
+        quat::weighted_average_markley(value_iter).map(Self::from_array)
+    }
+
     //mp into_array
     /// Create an array [Float] for the fquaternion in order i, j, k, r
     #[must_use]
@@ -537,6 +817,13 @@ where V3:Vector<F,3>, V4:Vector<F,4>, F:Float
         (V3::from_array(axis), angle)
     }
 
+    //mp as_euler
+    /// Find the Euler angles, applied in the given order, of a unit
+    /// quaternion
+    fn as_euler(&self, order:EulerOrder) -> [F;3] {
+        quat::to_euler_ordered(order, self.as_ref())
+    }
+
     //mp set_zero
     /// Set the quaternion to be all zeros
     fn set_zero(&mut self);
@@ -546,6 +833,64 @@ where V3:Vector<F,3>, V4:Vector<F,4>, F:Float
     #[must_use]
     fn mix(self, other:&Self, t:F) -> Self;
 
+    //mp slerp
+    /// Spherically interpolate from this unit [Quaternion] to another
+    /// using parameter `t` from zero to one, with a constant angular
+    /// velocity along the shorter path
+    #[must_use]
+    fn slerp(self, other:&Self, t:F) -> Self {
+        Self::from_array(quat::slerp(t, self.as_ref(), other.as_ref()))
+    }
+
+    //mp squad
+    /// Interpolate from this unit [Quaternion] keyframe to another
+    /// using parameter `t` from zero to one, as part of a smooth
+    /// spline through keyframes; `a0` and `a1` are the control
+    /// quaternions for the two keyframes, from
+    /// [Quaternion::squad_control]
+    #[must_use]
+    fn squad(self, other:&Self, a0:&Self, a1:&Self, t:F) -> Self {
+        Self::from_array(quat::squad(t, self.as_ref(), other.as_ref(), a0.as_ref(), a1.as_ref()))
+    }
+
+    //cp squad_control
+    /// Find the control quaternion for keyframe `q` of a spline used
+    /// by [Quaternion::squad], given the previous and next keyframes
+    #[must_use]
+    fn squad_control(prev:&Self, q:&Self, next:&Self) -> Self {
+        Self::from_array(quat::squad_control(prev.as_ref(), q.as_ref(), next.as_ref()))
+    }
+
+    //mp exp
+    /// Find the exponential of the [Quaternion]
+    #[must_use]
+    fn exp(self) -> Self {
+        Self::from_array(quat::exp(self.as_ref()))
+    }
+
+    //mp ln
+    /// Find the natural logarithm of the [Quaternion]
+    #[must_use]
+    fn ln(self) -> Self {
+        Self::from_array(quat::ln(self.as_ref()))
+    }
+
+    //mp pow
+    /// Raise the [Quaternion] to a real power; for a unit quaternion
+    /// this scales the angle of its rotation by `t`
+    #[must_use]
+    fn pow(self, t:F) -> Self {
+        Self::from_array(quat::pow(self.as_ref(), t))
+    }
+
+    //mp integrate_angular_velocity
+    /// Apply an angular velocity `omega` (about the fixed axes) for a
+    /// time `dt` to this unit [Quaternion]
+    #[must_use]
+    fn integrate_angular_velocity(self, omega:&V3, dt:F) -> Self {
+        Self::from_array(quat::integrate_angular_velocity(self.as_ref(), omega.as_ref(), dt))
+    }
+
     //mp dot
     /// Return the dot product of two quaternions; basically used for length
     #[must_use]
@@ -612,31 +957,37 @@ where V3:Vector<F,3>, V4:Vector<F,4>, F:Float
 /// A transformation that is a translation . scaling . rotation
 /// (i.e. it applies the rotation to an object, then scales it, then
 /// translates it)
+///
+/// Transformations compose with `*`, so that `a * b` applies `b` and
+/// then `a`; similarly `a * q` for a quaternion `q` applies the
+/// rotation `q` and then `a`, and `a * s` for a scale `s` applies the
+/// scaling and then `a`. Adding a V3 to a transformation applies that
+/// translation after it.
 pub trait Transform<F, V3, V4, M4, Q>:
-    Clone + Copy + std::fmt::Debug + std::fmt::Display + std::default::Default
+    Clone
+    + Copy
+    + std::fmt::Debug
+    + std::fmt::Display
+    + std::default::Default
+    // apply to self
+    + std::ops::Mul<Self, Output = Self>
+    + std::ops::MulAssign<Self>
+    // translation of self
+    + std::ops::Add<V3, Output = Self>
+    + std::ops::AddAssign<V3>
+    // scaling
+    + std::ops::Mul<F, Output = Self>
+    + std::ops::MulAssign<F>
+    // rotation
+    + std::ops::Mul<Q, Output = Self>
+    + std::ops::MulAssign<Q>
 // + std::ops::Neg<Output = Self>
-// apply to self - this is possible
-// + std::ops::Mul<Self, Output = Self>
-// + std::ops::MulAssign<Self>
 // + std::ops::Div<Self, Output = Self>
 // + std::ops::DivAssign<Self>
-// translation of self - can only choose one of V3 or V4
-// + std::ops::Add<V3, Output = Self>
-// + std::ops::AddAssign<V3>
 // + std::ops::Sub<V3, Output = Self>
 // + std::ops::SubAssign<V3>
-// + std::ops::Add<V4, Output = Self>
-// + std::ops::AddAssign<V4>
-// + std::ops::Sub<V4, Output = Self>
-// + std::ops::SubAssign<V4>
-// scaling
-// + std::ops::Mul<F, Output = Self>
-// + std::ops::MulAssign<F>
 // + std::ops::Div<F, Output = Self>
 // + std::ops::DivAssign<F>
-// rotation
-// + std::ops::Mul<Q, Output = Self>
-// + std::ops::MulAssign<Q>
 // + std::ops::Div<Q, Output = Self>
 // + std::ops::DivAssign<Q>
 // and probably where Q:std::ops::Mul<Self, Output=Self> etc
@@ -657,10 +1008,225 @@ where
     fn rotation(&self) -> Q;
     /// Get the inverse transformation
     fn inverse(&self) -> Self;
+    /// Get the inverse transformation, or return an error if the
+    /// magnitude of the scale is no more than `epsilon`; the error
+    /// carries the determinant of the transformation (the cube of the
+    /// scale)
+    fn try_inverse(&self, epsilon: F) -> Result<Self, SingularMatrix<F>> {
+        let scale = self.scale();
+        if scale.abs() <= epsilon {
+            Err(SingularMatrix {
+                determinant: scale * scale * scale,
+            })
+        } else {
+            Ok(self.inverse())
+        }
+    }
     /// Invert the transformation
     fn invert(&mut self);
     /// Convert it to a 4-by-4 matrix
     fn as_mat(&self) -> M4;
+    /// Apply the transformation to a point; this rotates, scales and
+    /// then translates it
+    fn transform_point(&self, p: &V3) -> V3 {
+        self.transform_vector(p) + self.translation()
+    }
+    /// Apply the transformation to a vector, which (unlike a point)
+    /// is rotated and scaled but not translated
+    fn transform_vector(&self, v: &V3) -> V3 {
+        self.rotation().apply3(v) * self.scale()
+    }
+    /// Interpolate between this transformation and another with
+    /// parameter `t` from zero to one; the translation and scale are
+    /// interpolated linearly, and the rotation spherically
+    #[must_use]
+    fn interpolate(&self, other: &Self, t: F) -> Self {
+        let translation = self.translation().mix(&other.translation(), t);
+        let rotation = self.rotation().slerp(&other.rotation(), t);
+        let scale = self.scale() * (F::one() - t) + other.scale() * t;
+        Self::of_trs(translation, rotation, scale)
+    }
+    /// Interpolate between this transformation and another with
+    /// parameter `t` from zero to one; this is the same as
+    /// [Transform::interpolate]
+    #[must_use]
+    fn lerp(&self, other: &Self, t: F) -> Self {
+        self.interpolate(other, t)
+    }
+}
+
+//tt DualQuaternion
+/// The [DualQuaternion] trait describes a rigid transformation (a
+/// rotation followed by a translation) as a unit dual quaternion
+///
+/// Unlike a [Transform], dual quaternions may be interpolated with
+/// screw linear interpolation ([DualQuaternion::sclerp]) and blended
+/// linearly ([DualQuaternion::blend]), as used for skinning
+///
+/// The composition of two dual quaternions `a * b` applies `b` and
+/// then `a`
+pub trait DualQuaternion<F, V3, V4, Q>:
+    Clone
+    + Copy
+    + std::fmt::Debug
+    + std::fmt::Display
+    + std::default::Default
+    + std::ops::Mul<Self, Output = Self>
+    + std::ops::MulAssign<Self>
+where
+    F: Float,
+    V3: Vector<F, 3>,
+    V4: Vector<F, 4>,
+    Q: Quaternion<F, V3, V4>,
+{
+    //cp of_real_dual
+    /// Create a dual quaternion from its real and dual quaternions
+    #[must_use]
+    fn of_real_dual(real: Q, dual: Q) -> Self;
+
+    //mp real
+    /// Get the real quaternion of the dual quaternion
+    fn real(&self) -> Q;
+
+    //mp dual
+    /// Get the dual quaternion of the dual quaternion
+    fn dual(&self) -> Q;
+
+    //cp from_array
+    /// Create a dual quaternion from an array of the real quaternion
+    /// followed by the dual quaternion
+    #[must_use]
+    fn from_array(data: [F; 8]) -> Self {
+        let real = Q::from_array([data[0], data[1], data[2], data[3]]);
+        let dual = Q::from_array([data[4], data[5], data[6], data[7]]);
+        Self::of_real_dual(real, dual)
+    }
+
+    //mp into_array
+    /// Get an array of the real quaternion followed by the dual
+    /// quaternion
+    #[must_use]
+    fn into_array(self) -> [F; 8] {
+        let r = self.real().into_array();
+        let d = self.dual().into_array();
+        [r[0], r[1], r[2], r[3], d[0], d[1], d[2], d[3]]
+    }
+
+    //cp identity
+    /// Create the identity transformation
+    #[must_use]
+    fn identity() -> Self {
+        Self::from_array(dquat::identity())
+    }
+
+    //cp of_rotation_translation
+    /// Create a transformation that rotates by a unit quaternion and
+    /// then translates
+    #[must_use]
+    fn of_rotation_translation(rotation: &Q, translation: &V3) -> Self {
+        Self::from_array(dquat::of_rotation_translation(
+            rotation.as_ref(),
+            translation.as_ref(),
+        ))
+    }
+
+    //mp rotation
+    /// Get the rotation of the transformation, as a unit quaternion
+    fn rotation(&self) -> Q {
+        Q::from_array(dquat::as_rotation_translation(&self.into_array()).0)
+    }
+
+    //mp translation
+    /// Get the translation of the transformation
+    fn translation(&self) -> V3 {
+        V3::from_array(dquat::as_rotation_translation(&self.into_array()).1)
+    }
+
+    //cp of_transform
+    /// Create a dual quaternion from the rotation and translation of a
+    /// [Transform]; any scaling of the transform is discarded
+    #[must_use]
+    fn of_transform<M4, T>(transform: &T) -> Self
+    where
+        M4: SqMatrix4<F, V3, V4>,
+        T: Transform<F, V3, V4, M4, Q>,
+    {
+        Self::of_rotation_translation(&transform.rotation(), &transform.translation())
+    }
+
+    //mp as_transform
+    /// Get the [Transform] (with a scale of one) for the dual quaternion
+    #[must_use]
+    fn as_transform<M4, T>(&self) -> T
+    where
+        M4: SqMatrix4<F, V3, V4>,
+        T: Transform<F, V3, V4, M4, Q>,
+    {
+        T::of_trs(self.translation(), self.rotation(), F::one())
+    }
+
+    //cp of_mat
+    /// Create a dual quaternion from a 4-by-4 matrix that is a
+    /// rotation followed by a translation
+    #[must_use]
+    fn of_mat<M4: SqMatrix4<F, V3, V4>>(m: &M4) -> Self {
+        Self::from_array(dquat::of_mat4(m.as_ref()))
+    }
+
+    //mp as_mat
+    /// Get the 4-by-4 matrix for the dual quaternion
+    #[must_use]
+    fn as_mat<M4: SqMatrix4<F, V3, V4>>(&self) -> M4 {
+        M4::from_array(dquat::to_mat4(&self.into_array()))
+    }
+
+    //mp conjugate
+    /// Get the quaternion conjugate of the dual quaternion
+    #[must_use]
+    fn conjugate(&self) -> Self {
+        Self::from_array(dquat::conjugate(&self.into_array()))
+    }
+
+    //mp inverse
+    /// Get the inverse transformation
+    #[must_use]
+    fn inverse(&self) -> Self {
+        Self::from_array(dquat::invert(&self.into_array()))
+    }
+
+    //mp normalize
+    /// Normalize the dual quaternion to be a unit dual quaternion
+    #[must_use]
+    fn normalize(self) -> Self {
+        Self::from_array(dquat::normalize(self.into_array()))
+    }
+
+    //mp apply3
+    /// Apply the transformation to a point
+    #[must_use]
+    fn apply3(&self, v: &V3) -> V3 {
+        V3::from_array(dquat::apply3(&self.into_array(), v.as_ref()))
+    }
+
+    //mp sclerp
+    /// Interpolate from this transformation to another using screw
+    /// linear interpolation, with parameter `t` from zero to one
+    #[must_use]
+    fn sclerp(&self, other: &Self, t: F) -> Self {
+        Self::from_array(dquat::sclerp(t, &self.into_array(), &other.into_array()))
+    }
+
+    //cp blend
+    /// Blend many weighted transformations using dual-quaternion
+    /// linear blending
+    ///
+    /// Returns None if there are no transformations, or if the blend
+    /// is degenerate
+    #[must_use]
+    fn blend<I: Iterator<Item = (F, Self)>>(value_iter: I) -> Option<Self> {
+        let value_iter = value_iter.map(|(w, dq)| (w, dq.into_array()));
+        dquat::blend(value_iter).map(Self::from_array)
+    }
 }
 
 //a Vector3D, Geometry3D
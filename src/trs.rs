@@ -0,0 +1,328 @@
+//a Imports
+use serde::{Deserialize, Serialize};
+
+use crate::{matrix, FArray, FArray2, FQArrayTrans};
+use crate::{Float, QArray, Quaternion, SingularMatrix, SqMatrix, Transform, Vector};
+
+//tp TrsTransform
+/// A transformation that is a translation . rotation . scaling, where
+/// the scaling may be different along each axis (i.e. it scales an
+/// object along its X, Y and Z axes, then applies the rotation, then
+/// translates it)
+///
+/// This is the transformation of nodes in glTF and USD scenes; with
+/// a uniform scale it is the same as an [FQArrayTrans].
+///
+/// A product of two such transformations is not, in general, of the
+/// same form, as rotating a non-uniformly scaled object and then
+/// scaling it again introduces a shear. Where this is the case the
+/// operations on a [TrsTransform] (such as composition and inversion)
+/// produce the nearest transformation without the shear, as from
+/// [TrsTransform::decompose_with_shear].
+///
+/// # Example
+///
+/// ```
+/// use geo_nd::{FArray, QArray, Quaternion, Transform, TrsTransform, Vector};
+/// let z = FArray::from_array([0., 0., 1.]);
+/// let q = QArray::of_axis_angle(&z, std::f64::consts::FRAC_PI_2);
+/// let t = TrsTransform::of_trs3(z, q, FArray::from_array([2., 3., 4.]));
+/// let p = t.transform_point(&FArray::from_array([1., 1., 1.]));
+/// assert!(p.distance(&FArray::from_array([-3., 2., 5.])) < 1E-12);
+/// let back = TrsTransform::decompose(&t.as_mat()).unwrap();
+/// assert!(back.scale3().distance(&t.scale3()) < 1E-12);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TrsTransform<F: Float + Serialize> {
+    /// Translation
+    translation: FArray<F, 3>,
+    /// Quaternion of the rotation
+    rotation: QArray<F, FArray<F, 3>, FArray<F, 4>>,
+    /// Scaling along each axis
+    scale: FArray<F, 3>,
+}
+
+//ip Default for TrsTransform
+impl<F: Float + Serialize> std::default::Default for TrsTransform<F> {
+    fn default() -> Self {
+        Self {
+            translation: FArray::zero(),
+            rotation: QArray::unit(),
+            scale: FArray::from_array([F::one(); 3]),
+        }
+    }
+}
+
+//ip Display for TrsTransform
+impl<F: Float + Serialize> std::fmt::Display for TrsTransform<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "trs[+({},{},{}) rot{} *({},{},{})]",
+            self.translation[0],
+            self.translation[1],
+            self.translation[2],
+            self.rotation,
+            self.scale[0],
+            self.scale[1],
+            self.scale[2],
+        )
+    }
+}
+
+//ip TrsTransform
+impl<F: Float + Serialize> TrsTransform<F> {
+    //cp of_trs3
+    /// Create a [TrsTransform] from a translation, rotation and a
+    /// scaling along each axis
+    pub fn of_trs3(
+        translation: FArray<F, 3>,
+        rotation: QArray<F, FArray<F, 3>, FArray<F, 4>>,
+        scale: FArray<F, 3>,
+    ) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    //mp scale3
+    /// Get the scaling along each axis
+    pub fn scale3(&self) -> FArray<F, 3> {
+        self.scale
+    }
+
+    //cp decompose_with_shear
+    /// Decompose an affine 4-by-4 matrix into translation . rotation .
+    /// scaling . shear, returning the [TrsTransform] and the shear
+    ///
+    /// The shear is returned as `[xy, xz, yz]`, being the amount of
+    /// the Y coordinate added to X, of Z added to X, and of Z added to
+    /// Y respectively before the scaling.
+    ///
+    /// If the scaling has an odd number of negative components then
+    /// the X scale is negative.
+    ///
+    /// Returns None if the matrix is not affine (its last row is not
+    /// 0, 0, 0, 1) or if it is singular
+    pub fn decompose_with_shear(m: &FArray2<F, 4, 16>) -> Option<(Self, [F; 3])> {
+        let d = matrix::decompose4(m.as_ref())?;
+        let epsilon = F::epsilon().sqrt();
+        let p = d.perspective;
+        if p[0].abs() > epsilon
+            || p[1].abs() > epsilon
+            || p[2].abs() > epsilon
+            || (p[3] - F::one()).abs() > epsilon
+        {
+            return None;
+        }
+        let trs = Self::of_trs3(
+            d.translation.into(),
+            QArray::from_array(d.rotation),
+            d.scale.into(),
+        );
+        Some((trs, d.shear))
+    }
+
+    //cp decompose
+    /// Decompose an affine 4-by-4 matrix into translation . rotation .
+    /// scaling
+    ///
+    /// Returns None if the matrix is not affine, if it is singular, or
+    /// if it includes a shear (and so cannot be represented as a
+    /// [TrsTransform]); see [TrsTransform::decompose_with_shear]
+    pub fn decompose(m: &FArray2<F, 4, 16>) -> Option<Self> {
+        let (trs, shear) = Self::decompose_with_shear(m)?;
+        let epsilon = F::epsilon().sqrt();
+        if shear.iter().any(|s| s.abs() > epsilon) {
+            None
+        } else {
+            Some(trs)
+        }
+    }
+
+    //fi of_mat_without_shear
+    /// Get the [TrsTransform] for a matrix that should be affine and
+    /// nonsingular, discarding any shear; if it is not, then the
+    /// result is the identity
+    fn of_mat_without_shear(m: &FArray2<F, 4, 16>) -> Self {
+        Self::decompose_with_shear(m)
+            .map(|(trs, _)| trs)
+            .unwrap_or_default()
+    }
+}
+
+//ip Transform for TrsTransform
+impl<F: Float + Serialize>
+    Transform<
+        F,
+        FArray<F, 3>,
+        FArray<F, 4>,
+        FArray2<F, 4, 16>,
+        QArray<F, FArray<F, 3>, FArray<F, 4>>,
+    > for TrsTransform<F>
+{
+    //fp of_trs
+    /// Create a [TrsTransform] from a Vector3 translation, Quat
+    /// rotation and a uniform Float scaling
+    fn of_trs(t: FArray<F, 3>, r: QArray<F, FArray<F, 3>, FArray<F, 4>>, s: F) -> Self {
+        Self::of_trs3(t, r, FArray::from_array([s; 3]))
+    }
+
+    //mp scale
+    /// Get the uniform scaling of the transformation; for a
+    /// non-uniform scaling this is the geometric mean of the scaling
+    /// along each axis, which preserves the volume scaling
+    fn scale(&self) -> F {
+        (self.scale[0] * self.scale[1] * self.scale[2]).cbrt()
+    }
+
+    //mp translation
+    /// Get the translation of the transformation
+    fn translation(&self) -> FArray<F, 3> {
+        self.translation
+    }
+
+    //mp rotation
+    /// Get the rotation of the transformation
+    fn rotation(&self) -> QArray<F, FArray<F, 3>, FArray<F, 4>> {
+        self.rotation
+    }
+
+    //cp inverse
+    /// Get a transformation that is the inverse of this, without any
+    /// shear that the true inverse requires
+    ///
+    /// If any of the scaling is (nearly) zero then this returns the
+    /// identity transformation; use [Transform::try_inverse] to
+    /// detect this
+    fn inverse(&self) -> Self {
+        self.try_inverse(F::epsilon()).unwrap_or_default()
+    }
+
+    //cp try_inverse
+    /// Get a transformation that is the inverse of this (without any
+    /// shear), or an error if the magnitude of the product of the
+    /// scalings is no more than `epsilon`
+    fn try_inverse(&self, epsilon: F) -> Result<Self, SingularMatrix<F>> {
+        let determinant = self.scale[0] * self.scale[1] * self.scale[2];
+        if determinant.abs() <= epsilon {
+            return Err(SingularMatrix { determinant });
+        }
+        let inverse = matrix::inverse4(self.as_mat().as_ref());
+        Ok(Self::of_mat_without_shear(&inverse.into()))
+    }
+
+    //mp invert
+    /// Invert this transformation
+    fn invert(&mut self) {
+        *self = self.inverse();
+    }
+
+    //mp as_mat
+    /// Return the matrix
+    fn as_mat(&self) -> FArray2<F, 4, 16> {
+        let mut m = FArray2::<F, 4, 16>::zero();
+        self.rotation.set_rotation4(&mut m);
+        for r in 0..3 {
+            for c in 0..3 {
+                m[r * 4 + c] *= self.scale[c];
+            }
+        }
+        m[3] = self.translation[0];
+        m[7] = self.translation[1];
+        m[11] = self.translation[2];
+        m
+    }
+
+    //mp transform_vector
+    /// Apply the transformation to a vector, scaling and rotating it
+    fn transform_vector(&self, v: &FArray<F, 3>) -> FArray<F, 3> {
+        self.rotation.apply3(&(*v * self.scale))
+    }
+
+    //mp interpolate
+    /// Interpolate between this transformation and another with
+    /// parameter `t` from zero to one; the translation and the scaling
+    /// along each axis are interpolated linearly, and the rotation
+    /// spherically
+    fn interpolate(&self, other: &Self, t: F) -> Self {
+        Self::of_trs3(
+            self.translation.mix(&other.translation, t),
+            self.rotation.slerp(&other.rotation, t),
+            self.scale.mix(&other.scale, t),
+        )
+    }
+}
+
+//ip Mul<Self> for TrsTransform
+impl<F: Float + Serialize> std::ops::Mul<Self> for TrsTransform<F> {
+    type Output = Self;
+    /// Compose two transformations; the result applies `other` and
+    /// then `self`, without any shear that this requires
+    fn mul(self, other: Self) -> Self {
+        Self::of_mat_without_shear(&(self.as_mat() * other.as_mat()))
+    }
+}
+impl<F: Float + Serialize> std::ops::MulAssign<Self> for TrsTransform<F> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+//ip Mul<QArray> for TrsTransform
+impl<F: Float + Serialize> std::ops::Mul<QArray<F, FArray<F, 3>, FArray<F, 4>>>
+    for TrsTransform<F>
+{
+    type Output = Self;
+    /// Apply a rotation before the transformation, without any shear
+    /// that this requires
+    fn mul(self, other: QArray<F, FArray<F, 3>, FArray<F, 4>>) -> Self {
+        self * Self::of_trs(FArray::zero(), other, F::one())
+    }
+}
+impl<F: Float + Serialize> std::ops::MulAssign<QArray<F, FArray<F, 3>, FArray<F, 4>>>
+    for TrsTransform<F>
+{
+    fn mul_assign(&mut self, other: QArray<F, FArray<F, 3>, FArray<F, 4>>) {
+        *self = *self * other;
+    }
+}
+
+//ip Mul<F> for TrsTransform
+impl<F: Float + Serialize> std::ops::Mul<F> for TrsTransform<F> {
+    type Output = Self;
+    /// Apply a uniform scaling before the transformation
+    fn mul(mut self, other: F) -> Self {
+        self.scale *= other;
+        self
+    }
+}
+impl<F: Float + Serialize> std::ops::MulAssign<F> for TrsTransform<F> {
+    fn mul_assign(&mut self, other: F) {
+        *self = *self * other;
+    }
+}
+
+//ip Add<FArray> for TrsTransform
+impl<F: Float + Serialize> std::ops::Add<FArray<F, 3>> for TrsTransform<F> {
+    type Output = Self;
+    /// Apply a translation after the transformation
+    fn add(mut self, other: FArray<F, 3>) -> Self {
+        self.translation += other;
+        self
+    }
+}
+impl<F: Float + Serialize> std::ops::AddAssign<FArray<F, 3>> for TrsTransform<F> {
+    fn add_assign(&mut self, other: FArray<F, 3>) {
+        *self = *self + other;
+    }
+}
+
+//ip From<FQArrayTrans> for TrsTransform
+impl<F: Float + Serialize> From<FQArrayTrans<F>> for TrsTransform<F> {
+    fn from(t: FQArrayTrans<F>) -> Self {
+        Self::of_trs(t.translation(), t.rotation(), t.scale())
+    }
+}
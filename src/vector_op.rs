@@ -316,6 +316,44 @@ pub fn comp_mult<V: Num, const D: usize>(mut v: [V; D], s: &[V; D]) -> [V; D] {
     v
 }
 
+//cp comp_min
+/// Consume the vector and return a new vector that is the minimum
+/// of the original and another in each coordinate
+//
+/// # Example
+///
+/// ```
+/// use geo_nd::vector;
+/// let a = vector::comp_min([2., 4., 6.], &[1., 5., 2.]);
+/// assert_eq!( a, [1., 4., 2.]);
+/// ```
+///
+pub fn comp_min<V: Float, const D: usize>(mut v: [V; D], other: &[V; D]) -> [V; D] {
+    for i in 0..D {
+        v[i] = v[i].min(other[i]);
+    }
+    v
+}
+
+//cp comp_max
+/// Consume the vector and return a new vector that is the maximum
+/// of the original and another in each coordinate
+//
+/// # Example
+///
+/// ```
+/// use geo_nd::vector;
+/// let a = vector::comp_max([2., 4., 6.], &[1., 5., 2.]);
+/// assert_eq!( a, [2., 5., 6.]);
+/// ```
+///
+pub fn comp_max<V: Float, const D: usize>(mut v: [V; D], other: &[V; D]) -> [V; D] {
+    for i in 0..D {
+        v[i] = v[i].max(other[i]);
+    }
+    v
+}
+
 //cp reduce
 /// Reduce ever element of a vector by a single scaling factor
 //
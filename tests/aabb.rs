@@ -0,0 +1,141 @@
+//a Imports
+use geo_nd::{Aabb, ClipSpace, FArray, FArray2, FQArrayTrans, QArray, Quaternion};
+use geo_nd::{SqMatrix, SqMatrix4, Transform, Vector};
+
+mod common;
+use common::{assert_near, assert_rect, values, vec2};
+
+//a Types
+type Vec2 = FArray<f64, 2>;
+type Vec3 = FArray<f64, 3>;
+type Vec4 = FArray<f64, 4>;
+type Mat4 = FArray2<f64, 4, 16>;
+type Quat = QArray<f64, Vec3, Vec4>;
+type Rect = Aabb<f64, Vec2, 2>;
+type Box3 = Aabb<f64, Vec3, 3>;
+
+//a Useful functions
+//fi corners
+/// The eight corners of a box
+fn corners(b: &Box3) -> Vec<Vec3> {
+    let (min, max) = (b.min(), b.max());
+    (0..8)
+        .map(|i| {
+            Vec3::from_array([
+                if i & 1 == 0 { min[0] } else { max[0] },
+                if i & 2 == 0 { min[1] } else { max[1] },
+                if i & 4 == 0 { min[2] } else { max[2] },
+            ])
+        })
+        .collect()
+}
+
+//a Tests
+//ft test_aabb_2d
+#[test]
+fn test_aabb_2d() {
+    let a = Rect::new(&vec2(3., 1.), &vec2(1., 2.));
+    assert_eq!(a.min().into_array(), [1., 1.]);
+    assert_eq!(a.max().into_array(), [3., 2.]);
+    assert_eq!(a.center().into_array(), [2., 1.5]);
+    assert_eq!(a.extent().into_array(), [2., 1.]);
+    assert_eq!(a.volume(), 2.);
+    assert!(a.contains(&vec2(1., 2.)));
+    assert!(!a.contains(&vec2(0.5, 1.5)));
+    assert_eq!(format!("{a}"), "[(1,1) - (3,2)]");
+
+    assert!(Rect::from_points(std::iter::empty()).is_none());
+    let points = [vec2(0., 0.), vec2(2., -1.), vec2(1., 3.)];
+    let b = Rect::from_points(points.iter()).unwrap();
+    assert_rect(&b, [0., -1.], [2., 3.]);
+    assert!(points.iter().all(|p| b.contains(p)));
+
+    // Union and intersection
+    let u = a.union(&b);
+    assert_rect(&u, [0., -1.], [3., 3.]);
+    assert_rect(&a.intersection(&b).unwrap(), [1., 1.], [2., 2.]);
+    assert!(a.intersects(&b));
+    let c = Rect::new(&vec2(5., 5.), &vec2(6., 6.));
+    assert!(!a.intersects(&c));
+    assert!(a.intersection(&c).is_none());
+    let touching = Rect::new(&vec2(3., 0.), &vec2(4., 1.));
+    assert!(a.intersects(&touching));
+    assert_eq!(a.intersection(&touching).unwrap().volume(), 0.);
+
+    // Expansion, and shrinking no further than the centre
+    assert_rect(&a.expand(1.), [0., 0.], [4., 3.]);
+    assert_rect(&a.expand(-0.25), [1.25, 1.25], [2.75, 1.75]);
+    assert_rect(&a.expand(-0.75), [1.75, 1.5], [2.25, 1.5]);
+
+    // Closest points
+    assert_eq!(a.closest_point(&vec2(2., 1.5)).into_array(), [2., 1.5]);
+    assert_eq!(a.closest_point(&vec2(0., 5.)).into_array(), [1., 2.]);
+    assert_eq!(a.closest_point(&vec2(2.5, -3.)).into_array(), [2.5, 1.]);
+}
+
+//ft test_aabb_3d
+#[test]
+fn test_aabb_3d() {
+    let vs = values(3 * 20);
+    let points: Vec<Vec3> = vs
+        .chunks(3)
+        .map(|v| Vec3::from_array([v[0], v[1], v[2]]) * 4.)
+        .collect();
+    let b = Box3::from_points(points.iter()).unwrap();
+    for p in &points {
+        assert!(b.contains(p));
+        assert_eq!(b.closest_point(p).into_array(), p.into_array());
+        let outside = *p * 10.;
+        let c = b.closest_point(&outside);
+        assert!(b.contains(&c));
+        for q in &points {
+            assert!(outside.distance(&c) <= outside.distance(q) + 1E-12);
+        }
+    }
+    for i in 0..3 {
+        assert!(points.iter().any(|p| p[i] == b.min()[i]));
+        assert!(points.iter().any(|p| p[i] == b.max()[i]));
+    }
+    let e = b.extent();
+    assert!((b.volume() - e[0] * e[1] * e[2]).abs() < 1E-12);
+    assert_near((b.center() * 2.).as_ref(), (b.min() + b.max()).as_ref());
+}
+
+//ft test_aabb_transform
+#[test]
+fn test_aabb_transform() {
+    let b = Box3::new(
+        &Vec3::from_array([-1., 0., 2.]),
+        &Vec3::from_array([2., 0.5, 3.]),
+    );
+    for v in values(8 * 5).chunks(8) {
+        let q = Quat::of_rijk(v[0], v[1], v[2], v[3]).normalize();
+        let t = FQArrayTrans::of_trs(Vec3::from_array([v[4], v[5], v[6]]), q, 1.5 + v[7]);
+        let m = t.as_mat();
+
+        // The bounds of an affine transformation are those of the
+        // transformed corners
+        let tb = b.transform(&m);
+        let tc: Vec<Vec3> = corners(&b).iter().map(|c| t.transform_point(c)).collect();
+        let expected = Box3::from_points(tc.iter()).unwrap();
+        assert_near(tb.min().as_ref(), expected.min().as_ref());
+        assert_near(tb.max().as_ref(), expected.max().as_ref());
+    }
+
+    // A projective transformation maps the corners
+    let p = Mat4::perspective_fov(ClipSpace::opengl(), 1.0, 1.0, 0.5, 10.);
+    let b = Box3::new(
+        &Vec3::from_array([-1., -1., -4.]),
+        &Vec3::from_array([1., 2., -2.]),
+    );
+    let tb = b.transform(&p);
+    for c in corners(&b) {
+        let h = p.transform(&Vec4::from_array([c[0], c[1], c[2], 1.]));
+        let c = Vec3::from_array([h[0] / h[3], h[1] / h[3], h[2] / h[3]]);
+        assert!(tb.contains(&c));
+    }
+    assert_near(
+        tb.max().as_ref(),
+        &[0.5 / 0.5_f64.tan(), 1. / 0.5_f64.tan(), tb.max()[2]],
+    );
+}
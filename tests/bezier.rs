@@ -0,0 +1,247 @@
+//a Imports
+use geo_nd::{Bezier, FArray, Vector};
+
+mod common;
+use common::{assert_near, values, vec2};
+
+//a Types
+type Vec2 = FArray<f64, 2>;
+type Vec3 = FArray<f64, 3>;
+type Curve2 = Bezier<f64, Vec2, 2>;
+type Curve3 = Bezier<f64, Vec3, 3>;
+
+//a Useful functions
+//fi curves3
+/// A set of random lines, quadratics and cubics in three dimensions
+fn curves3(n: usize) -> Vec<Curve3> {
+    values(n * 12)
+        .chunks(12)
+        .enumerate()
+        .map(|(i, v)| {
+            let p: Vec<Vec3> = v
+                .chunks(3)
+                .map(|c| Vec3::from_array([c[0], c[1], c[2]]) * 4.)
+                .collect();
+            match i % 3 {
+                0 => Curve3::line(&p[0], &p[3]),
+                1 => Curve3::quadratic(&p[0], &p[1], &p[3]),
+                _ => Curve3::cubic(&p[0], &p[1], &p[2], &p[3]),
+            }
+        })
+        .collect()
+}
+
+//fi quarter_circle
+/// A cubic approximation to a quarter of the unit circle,
+/// anticlockwise from the X axis
+fn quarter_circle() -> Curve2 {
+    let k = 4. * (2.0_f64.sqrt() - 1.) / 3.;
+    Curve2::cubic(&vec2(1., 0.), &vec2(1., k), &vec2(k, 1.), &vec2(0., 1.))
+}
+
+//a Tests
+//ft test_bezier_evaluate
+#[test]
+fn test_bezier_evaluate() {
+    for b in curves3(12) {
+        let p = b.control_points();
+        for t in [0., 0.2, 0.5, 0.9, 1.] {
+            // The point is that of the Bernstein polynomials
+            let s = 1. - t;
+            let expected = match b.degree() {
+                1 => p[0] * s + p[1] * t,
+                2 => p[0] * (s * s) + p[1] * (2. * s * t) + p[2] * (t * t),
+                _ => {
+                    p[0] * (s * s * s)
+                        + p[1] * (3. * s * s * t)
+                        + p[2] * (3. * s * t * t)
+                        + p[3] * (t * t * t)
+                }
+            };
+            assert_near(b.point_at(t).as_ref(), expected.as_ref());
+
+            // The derivatives match finite differences
+            let h = 1E-5;
+            let d = (b.point_at(t + h) - b.point_at(t - h)) / (2. * h);
+            assert!(d.distance(&b.tangent_at(t)) < 1E-6);
+            let d = (b.tangent_at(t + h) - b.tangent_at(t - h)) / (2. * h);
+            assert!(d.distance(&b.second_derivative_at(t)) < 1E-6);
+
+            // The normal is perpendicular to the tangent, towards the
+            // direction of turning
+            match b.normal_at(t) {
+                None => assert_eq!(b.degree(), 1),
+                Some(n) => {
+                    assert!((n.length() - 1.).abs() < 1E-12);
+                    assert!(n.dot(&b.tangent_at(t)).abs() < 1E-9);
+                    assert!(n.dot(&b.second_derivative_at(t)) > 0.);
+                }
+            }
+        }
+    }
+
+    // A quadratic with collinear control points does not turn
+    let straight = Curve2::quadratic(&vec2(0., 0.), &vec2(1., 1.), &vec2(3., 3.));
+    assert!(straight.normal_at(0.5).is_none());
+    // The normal of an anticlockwise arc points to its centre
+    let n = quarter_circle().normal_at(0.5).unwrap();
+    assert_near(n.as_ref(), &[-0.5_f64.sqrt(), -0.5_f64.sqrt()]);
+}
+
+//ft test_bezier_split
+#[test]
+fn test_bezier_split() {
+    for b in curves3(9) {
+        for t in [0.25, 0.6] {
+            let (first, second) = b.split(t);
+            assert_eq!(first.degree(), b.degree());
+            assert_eq!(second.degree(), b.degree());
+            assert_eq!(first.start().into_array(), b.start().into_array());
+            assert_eq!(second.end().into_array(), b.end().into_array());
+            for u in [0., 0.3, 0.5, 1.] {
+                assert_near(first.point_at(u).as_ref(), b.point_at(t * u).as_ref());
+                assert_near(
+                    second.point_at(u).as_ref(),
+                    b.point_at(t + (1. - t) * u).as_ref(),
+                );
+            }
+        }
+        let r = b.reversed();
+        assert_near(r.point_at(0.3).as_ref(), b.point_at(0.7).as_ref());
+    }
+}
+
+//ft test_bezier_bounds
+#[test]
+fn test_bezier_bounds() {
+    for b in curves3(12) {
+        let bounds = b.bounds();
+        let samples: Vec<Vec3> = (0..=1000).map(|i| b.point_at(i as f64 / 1000.)).collect();
+        for c in 0..3 {
+            // The bounds contain the curve, and are tight
+            let min = samples.iter().fold(f64::MAX, |m, p| m.min(p[c]));
+            let max = samples.iter().fold(f64::MIN, |m, p| m.max(p[c]));
+            assert!(bounds.min()[c] <= min + 1E-12);
+            assert!(bounds.max()[c] >= max - 1E-12);
+            assert!(min - bounds.min()[c] < 1E-4);
+            assert!(bounds.max()[c] - max < 1E-4);
+        }
+    }
+    let arc = quarter_circle().bounds();
+    assert_near(arc.min().as_ref(), &[0., 0.]);
+    assert_near(arc.max().as_ref(), &[1., 1.]);
+    let bump = Curve2::quadratic(&vec2(0., 0.), &vec2(1., 2.), &vec2(2., 0.));
+    assert_near(bump.bounds().max().as_ref(), &[2., 1.]);
+}
+
+//ft test_bezier_length
+#[test]
+fn test_bezier_length() {
+    let line = Curve3::line(&Vec3::zero(), &Vec3::from_array([2., 3., 6.]));
+    assert_eq!(line.length(1E-9), 7.);
+    assert!((line.length_to(0.25, 1E-9) - 1.75).abs() < 1E-12);
+    assert!((line.t_at_length(1.75, 1E-9) - 0.25).abs() < 1E-9);
+
+    let arc = quarter_circle();
+    let length = arc.length(1E-10);
+    assert!((length - std::f64::consts::FRAC_PI_2).abs() < 1E-3);
+    assert_eq!(arc.length_to(0., 1E-10), 0.);
+    assert_eq!(arc.length_to(1., 1E-10), length);
+    assert_eq!(arc.t_at_length(-1., 1E-10), 0.);
+    assert_eq!(arc.t_at_length(length + 1., 1E-10), 1.);
+
+    // The parameter at a length is the inverse of the length to a
+    // parameter
+    for b in curves3(9) {
+        let length = b.length(1E-10);
+        for f in [0.1, 0.5, 0.77] {
+            let t = b.t_at_length(length * f, 1E-10);
+            assert!((0. ..=1.).contains(&t));
+            assert!((b.length_to(t, 1E-10) - length * f).abs() < 1E-8);
+        }
+        let (first, second) = b.split(0.4);
+        let total = first.length(1E-10) + second.length(1E-10);
+        assert!((total - length).abs() < 1E-8);
+    }
+}
+
+//ft test_bezier_offset
+#[test]
+fn test_bezier_offset() {
+    // A line is offset exactly to its left (or right)
+    let line = Curve2::line(&vec2(0., 0.), &vec2(2., 0.));
+    assert_eq!(line.perpendicular_at(0.5).into_array(), [0., 1.]);
+    let offset = line.offset(0.5, 1E-9);
+    assert_eq!(offset.len(), 1);
+    assert_near(offset[0].start().as_ref(), &[0., 0.5]);
+    assert_near(offset[0].end().as_ref(), &[2., 0.5]);
+    let offset = line.offset(-0.5, 1E-9);
+    assert_near(offset[0].end().as_ref(), &[2., -0.5]);
+
+    // Offsets of a quarter circle are (close to) arcs of other radii,
+    // as a sequence of joined curves
+    let arc = quarter_circle();
+    assert_near(arc.perpendicular_at(0.).as_ref(), &[-1., 0.]);
+    for (distance, radius) in [(-0.5, 1.5), (0.5, 0.5)] {
+        let curves = arc.offset(distance, 1E-4);
+        assert!(!curves.is_empty());
+        assert_near(curves[0].start().as_ref(), &[radius, 0.]);
+        assert_near(curves[curves.len() - 1].end().as_ref(), &[0., radius]);
+        for pair in curves.windows(2) {
+            assert!(pair[0].end().distance(&pair[1].start()) < 1E-12);
+        }
+        for c in &curves {
+            assert_eq!(c.degree(), 3);
+            for i in 0..=10 {
+                let r = c.point_at(i as f64 / 10.).length();
+                assert!((r - radius).abs() < 1E-3, "{r} != {radius}");
+            }
+        }
+    }
+
+    // A cubic with coincident control points at its start, and a
+    // quadratic, with a tight tolerance requiring subdivision
+    let s = Curve2::cubic(&vec2(0., 0.), &vec2(0., 0.), &vec2(2., 3.), &vec2(4., 0.));
+    assert_near(
+        s.perpendicular_at(0.).as_ref(),
+        vec2(-3., 2.).normalize().as_ref(),
+    );
+    let q = Curve2::quadratic(&vec2(0., 0.), &vec2(1., 2.), &vec2(2., 0.));
+    for b in [s, q] {
+        let curves = b.offset(0.2, 1E-6);
+        assert!(curves.len() > 1);
+        for c in &curves {
+            assert_eq!(c.degree(), b.degree());
+            for i in 0..=4 {
+                let p = c.point_at(i as f64 / 4.);
+                let closest = (0..=2000)
+                    .map(|j| b.point_at(j as f64 / 2000.).distance(&p))
+                    .fold(f64::MAX, f64::min);
+                assert!((closest - 0.2).abs() < 1E-3, "{closest}");
+            }
+        }
+    }
+}
+
+//ft test_bezier_serde
+#[test]
+fn test_bezier_serde() {
+    use serde::de::value::{Error, SeqDeserializer};
+    use serde::Deserialize;
+    type De = SeqDeserializer<std::vec::IntoIter<f64>, Error>;
+    let points = |n: usize| -> Vec<De> {
+        (0..n)
+            .map(|i| SeqDeserializer::new(vec![i as f64, 1.].into_iter()))
+            .collect()
+    };
+
+    // A curve is its control points, of which there must be 2 to 4
+    let de = SeqDeserializer::<_, Error>::new(points(3).into_iter());
+    let b = Curve2::deserialize(de).unwrap();
+    assert_eq!(b.degree(), 2);
+    assert_eq!(b.end().into_array(), [2., 1.]);
+    for n in [0, 1, 5] {
+        let de = SeqDeserializer::<_, Error>::new(points(n).into_iter());
+        assert!(Curve2::deserialize(de).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+//! Helpers shared by the integration tests
+//!
+//! Each test file uses only some of these
+#![allow(dead_code)]
+
+//a Imports
+use geo_nd::{Aabb, FArray, Vector};
+
+//a Useful functions
+//fp vec2
+pub fn vec2(x: f64, y: f64) -> FArray<f64, 2> {
+    FArray::from_array([x, y])
+}
+
+//fp assert_near
+/// Assert that two slices are equal but for rounding errors
+pub fn assert_near(a: &[f64], b: &[f64]) {
+    assert_eq!(a.len(), b.len());
+    let d2: f64 = a.iter().zip(b.iter()).map(|(x, y)| (x - y) * (x - y)).sum();
+    assert!(d2 < 1E-20, "{a:?} != {b:?}");
+}
+
+//fp assert_rect
+/// Assert that the bounds of a rectangle (an [Aabb], or anything that
+/// converts to one) are equal to `min` and `max` but for rounding errors
+pub fn assert_rect<R>(r: &R, min: [f64; 2], max: [f64; 2])
+where
+    R: Clone + Into<Aabb<f64, FArray<f64, 2>, 2>>,
+{
+    let r: Aabb<f64, FArray<f64, 2>, 2> = r.clone().into();
+    assert_near(r.min().as_ref(), &min);
+    assert_near(r.max().as_ref(), &max);
+}
+
+//fp values
+/// A deterministic sequence of values in the range -1 to 1
+pub fn values(n: usize) -> Vec<f64> {
+    let mut seed: u32 = 0x9e37_79b9;
+    (0..n)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            ((seed >> 8) as f64) / ((1 << 23) as f64) - 1.0
+        })
+        .collect()
+}
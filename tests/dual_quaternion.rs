@@ -0,0 +1,154 @@
+//a Imports
+use geo_nd::{dquat, vector};
+use geo_nd::{DQArray, DualQuaternion, FArray, FArray2, FQArrayTrans, QArray};
+use geo_nd::{Quaternion, SqMatrix, Transform, Vector};
+
+mod common;
+use common::assert_near;
+
+//a Types
+type Vec3 = FArray<f64, 3>;
+type Vec4 = FArray<f64, 4>;
+type Mat4 = FArray2<f64, 4, 16>;
+type Quat = QArray<f64, Vec3, Vec4>;
+type DQuat = DQArray<f64, Vec3, Vec4>;
+
+//a Useful functions
+//fi dq
+/// A dual quaternion rotating by angle about an axis and then translating
+fn dq(axis: [f64; 3], angle: f64, t: [f64; 3]) -> DQuat {
+    let q = Quat::of_axis_angle(&Vec3::from_array(axis).normalize(), angle);
+    DQuat::of_rotation_translation(&q, &Vec3::from_array(t))
+}
+
+//a Tests
+//ft test_transform
+#[test]
+fn test_transform() {
+    let q = Quat::of_axis_angle(&Vec3::from_array([1., 2., 3.]).normalize(), 0.7);
+    let t = Vec3::from_array([3., -1., 2.]);
+    let a = DQuat::of_rotation_translation(&q, &t);
+    assert_near(a.rotation().as_ref(), q.as_ref());
+    assert_near(a.translation().as_ref(), t.as_ref());
+
+    let p = Vec3::from_array([0.5, 1.5, -2.]);
+    assert_near(a.apply3(&p).as_ref(), (q.apply3(&p) + t).as_ref());
+    let m: Mat4 = a.as_mat();
+    let p4 = m.transform(&Vec4::from_array([p[0], p[1], p[2], 1.]));
+    assert_near(&p4.into_array()[0..3], a.apply3(&p).as_ref());
+    assert_near(&DQuat::of_mat(&m).into_array(), &a.into_array());
+    assert_eq!(DQuat::default().into_array(), dquat::identity());
+
+    // Conversion to and from FQArrayTrans, which discards the scale
+    let fq = FQArrayTrans::of_trs(t, q, 2.);
+    let from_fq: DQuat = fq.into();
+    assert_near(&from_fq.into_array(), &a.into_array());
+    assert_near(&DQuat::of_transform(&fq).into_array(), &a.into_array());
+    let back: FQArrayTrans<f64> = a.into();
+    assert_eq!(back.scale(), 1.);
+    assert_near(back.translation().as_ref(), t.as_ref());
+    let back: FQArrayTrans<f64> = a.as_transform();
+    assert_near(back.rotation().as_ref(), q.as_ref());
+}
+
+//ft test_compose_invert
+#[test]
+fn test_compose_invert() {
+    let a = dq([1., 2., 3.], 0.7, [3., -1., 2.]);
+    let b = dq([0., -1., 1.], 2.5, [-1., 0.5, 4.]);
+    let p = Vec3::from_array([0.5, 1.5, -2.]);
+
+    // a * b applies b and then a, and matches the matrix product
+    let ab = a * b;
+    assert_near(ab.apply3(&p).as_ref(), a.apply3(&b.apply3(&p)).as_ref());
+    let m_ab: Mat4 = ab.as_mat();
+    assert_near(
+        m_ab.as_ref(),
+        (a.as_mat::<Mat4>() * b.as_mat::<Mat4>()).as_ref(),
+    );
+    let mut c = a;
+    c *= b;
+    assert_near(&c.into_array(), &ab.into_array());
+
+    // The inverse undoes the transformation, even if not normalized
+    let a_inv = a.inverse();
+    assert_near(a_inv.apply3(&a.apply3(&p)).as_ref(), p.as_ref());
+    assert_near(&(a * a_inv).into_array(), &dquat::identity());
+    assert_near(&a.conjugate().into_array(), &a_inv.into_array());
+    let scaled = DQuat::from_array(vector::scale(a.into_array(), 3.));
+    assert_near(
+        &(scaled * scaled.inverse()).into_array(),
+        &dquat::identity(),
+    );
+    assert_near(&scaled.normalize().into_array(), &a.into_array());
+}
+
+//ft test_sclerp
+#[test]
+fn test_sclerp() {
+    let a = dq([1., 2., 3.], 0.7, [3., -1., 2.]);
+    let b = dq([0., -1., 1.], 2.5, [-1., 0.5, 4.]);
+    assert_near(&a.sclerp(&b, 0.).into_array(), &a.into_array());
+    assert_near(&a.sclerp(&b, 1.).into_array(), &b.into_array());
+
+    // The motion from a to b is split into equal screw motions
+    let half = a.sclerp(&b, 0.5);
+    let step = a.inverse() * half;
+    assert_near(&(a * step * step).into_array(), &b.into_array());
+    let third = a.sclerp(&b, 1. / 3.);
+    let step = a.inverse() * third;
+    assert_near(&(a * step * step * step).into_array(), &b.into_array());
+
+    // A screw about Z moves uniformly along and around it
+    let id = DQuat::identity();
+    let screw = dq([0., 0., 1.], 1.2, [0., 0., 6.]);
+    let s = id.sclerp(&screw, 0.25);
+    assert_near(
+        s.rotation().as_ref(),
+        Quat::of_axis_angle(&Vec3::from_array([0., 0., 1.]), 0.3).as_ref(),
+    );
+    assert_near(s.translation().as_ref(), &[0., 0., 1.5]);
+
+    // A pure translation interpolates linearly, and the sign of the
+    // dual quaternion does not matter
+    let tr = dq([0., 0., 1.], 0., [2., 4., -6.]);
+    let s = id.sclerp(&tr, 0.25);
+    assert_near(s.translation().as_ref(), &[0.5, 1., -1.5]);
+    let neg_b = DQuat::from_array(vector::scale(b.into_array(), -1.));
+    assert_near(
+        &dquat::as_rotation_translation(&a.sclerp(&neg_b, 0.5).into_array()).1,
+        half.translation().as_ref(),
+    );
+}
+
+//ft test_blend
+#[test]
+fn test_blend() {
+    let a = dq([0., 0., 1.], 0.4, [1., 0., 0.]);
+    let b = dq([0., 0., 1.], 0.8, [1., 0., 0.]);
+
+    // Blending a single transform, or one with itself negated, gives it back
+    let blend = DQuat::blend([(0.5, a)].into_iter()).unwrap();
+    assert_near(&blend.into_array(), &a.into_array());
+    let neg_a = DQuat::from_array(vector::scale(a.into_array(), -1.));
+    let blend = DQuat::blend([(1., a), (2., neg_a)].into_iter()).unwrap();
+    assert_near(&blend.into_array(), &a.into_array());
+
+    // Equal blends of rotations about the same axis through a point
+    // are the intermediate rotation
+    let blend = DQuat::blend([(1., a), (1., b)].into_iter()).unwrap();
+    assert_near(&blend.into_array(), &a.sclerp(&b, 0.5).into_array());
+    let r = dquat::as_rotation_translation(&blend.into_array()).0;
+    let expected = Quat::of_axis_angle(&Vec3::from_array([0., 0., 1.]), 0.6);
+    assert_near(&r, expected.as_ref());
+
+    // The result is a unit dual quaternion
+    let c = dq([1., 2., 3.], 2.0, [3., -1., 2.]);
+    let blend = DQuat::blend([(0.2, a), (0.3, b), (0.5, c)].into_iter()).unwrap();
+    let [r0, r1, r2, r3, d0, d1, d2, d3] = blend.into_array();
+    assert!((vector::length_sq(&[r0, r1, r2, r3]) - 1.).abs() < 1E-12);
+    assert!(vector::dot(&[r0, r1, r2, r3], &[d0, d1, d2, d3]).abs() < 1E-12);
+
+    assert!(DQuat::blend(std::iter::empty()).is_none());
+    assert!(DQuat::blend([(0., a)].into_iter()).is_none());
+}
@@ -0,0 +1,160 @@
+//a Imports
+use geo_nd::{matrix, ClipSpace, Containment, DepthRange, Frustum};
+use geo_nd::{FArray, FArray2, FQArrayTrans, QArray, Quaternion};
+use geo_nd::{Geometry3D, SqMatrix, SqMatrix4, Transform, Vector};
+
+//a Useful functions
+//fi check_frustum
+/// Check the frustum of a view-projection matrix on the types of a
+/// [Geometry3D]
+fn check_frustum<G: Geometry3D<f32>>(clip: ClipSpace) {
+    type Mat4<G> = <G as Geometry3D<f32>>::Mat4;
+    type Vec3<G> = <G as Geometry3D<f32>>::Vec3;
+    type Vec4<G> = <G as Geometry3D<f32>>::Vec4;
+    let forward: f32 = clip.handedness.forward();
+    let (l, r, b, t, n, f) = (-2., 1., -1., 1.5, 1., 10.);
+    let proj = Mat4::<G>::frustum(clip, l, r, b, t, n, f);
+    let view = FQArrayTrans::<f32>::of_trs(
+        FArray::from_array([0.5, -1., 2.]),
+        QArray::of_axis_angle(&FArray::from_array([0., 0.6, 0.8]), 0.7),
+        1.,
+    );
+    let view_mat = Mat4::<G>::from_array(view.as_mat().into_array());
+    let frustum: Frustum<f32, Vec3<G>, Vec4<G>> =
+        Frustum::of_matrix(&(proj * view_mat), clip.depth);
+
+    // Map a point in view space to world space
+    let world = |x: f32, y: f32, z: f32| {
+        let p = view
+            .inverse()
+            .transform_point(&FArray::from_array([x, y, z * forward]));
+        Vec3::<G>::from_array(p.into_array())
+    };
+
+    // The corners are those of the frustum in view space
+    let k = f / n;
+    let expected = [
+        world(l, b, n),
+        world(r, b, n),
+        world(l, t, n),
+        world(r, t, n),
+        world(l * k, b * k, f),
+        world(r * k, b * k, f),
+        world(l * k, t * k, f),
+        world(r * k, t * k, f),
+    ];
+    for (c, e) in frustum.corners().iter().zip(expected.iter()) {
+        assert!(c.distance(e) < 1E-4, "{c:?} != {e:?}");
+    }
+
+    // The planes have unit normals, and contain the corners
+    for plane in frustum.planes() {
+        let normal = Vec3::<G>::from_array([plane[0], plane[1], plane[2]]);
+        assert!((normal.length() - 1.).abs() < 1E-5);
+        let on_plane = frustum
+            .corners()
+            .iter()
+            .filter(|c| (normal.dot(c) + plane[3]).abs() < 1E-4)
+            .count();
+        assert_eq!(on_plane, 4);
+    }
+
+    // Points
+    assert!(frustum.contains_point(&world(0., 0., 5.)));
+    assert_eq!(frustum.test_point(&world(0., 0., 5.)), Containment::Inside);
+    assert_eq!(
+        frustum.test_point(&world(0., 0., 0.5)),
+        Containment::Outside
+    );
+    assert_eq!(
+        frustum.test_point(&world(0., 0., 11.)),
+        Containment::Outside
+    );
+    assert_eq!(
+        frustum.test_point(&world(-10., 0., 4.)),
+        Containment::Outside
+    );
+    assert_eq!(
+        frustum.test_point(&world(0., 6.5, 4.)),
+        Containment::Outside
+    );
+
+    // Spheres
+    let centre = world(-0.5, 0., 5.);
+    assert_eq!(frustum.test_sphere(&centre, 1.), Containment::Inside);
+    assert_eq!(frustum.test_sphere(&centre, 5.), Containment::Intersecting);
+    assert_eq!(
+        frustum.test_sphere(&world(0., 0., -3.), 2.),
+        Containment::Outside
+    );
+    assert_eq!(
+        frustum.test_sphere(&world(0., 0., 11.), 2.),
+        Containment::Intersecting
+    );
+
+    // Boxes, which are axis-aligned in world space
+    let aabb = |c: &Vec3<G>, h: f32| {
+        let h = Vec3::<G>::from_array([h; 3]);
+        (*c - h, *c + h)
+    };
+    let (min, max) = aabb(&centre, 0.5);
+    assert_eq!(frustum.test_aabb(&min, &max), Containment::Inside);
+    let (min, max) = aabb(&centre, 4.);
+    assert_eq!(frustum.test_aabb(&min, &max), Containment::Intersecting);
+    let (min, max) = aabb(&world(0., 0., 20.), 2.);
+    assert_eq!(frustum.test_aabb(&min, &max), Containment::Outside);
+    let (min, max) = aabb(&world(0., 0., 5.), 50.);
+    assert_eq!(frustum.test_aabb(&min, &max), Containment::Intersecting);
+}
+
+//a Tests
+//ft test_frustum
+#[test]
+fn test_frustum() {
+    check_frustum::<f32>(ClipSpace::opengl());
+    check_frustum::<f32>(ClipSpace::vulkan());
+    check_frustum::<f32>(ClipSpace::direct3d());
+    check_frustum::<f32>(ClipSpace::vulkan().with_depth(DepthRange::OneToZero));
+}
+
+//ft test_frustum_simd
+#[cfg(feature = "simd")]
+#[test]
+fn test_frustum_simd() {
+    check_frustum::<geo_nd::simd::VecF32A16>(ClipSpace::opengl());
+    check_frustum::<geo_nd::simd::VecF32A16>(ClipSpace::direct3d());
+}
+
+//ft test_frustum_infinite
+#[test]
+fn test_frustum_infinite() {
+    type Vec3 = FArray<f64, 3>;
+    type Vec4 = FArray<f64, 4>;
+    for clip in [
+        ClipSpace::opengl(),
+        ClipSpace::vulkan().with_depth(DepthRange::OneToZero),
+    ] {
+        let proj = FArray2::<f64, 4, 16>::infinite_perspective(clip, 1.2, 1., 0.1);
+        let frustum: Frustum<f64, Vec3, Vec4> = Frustum::of_matrix(&proj, clip.depth);
+
+        // The far plane excludes nothing
+        let far = Vec3::from_array([0., 0., -1E8]);
+        assert!(frustum.contains_point(&far));
+        assert_eq!(frustum.test_sphere(&far, 1.), Containment::Inside);
+        assert!(!frustum.contains_point(&Vec3::from_array([0., 0., -0.05])));
+
+        // The near corners are finite and the far corners are not
+        let corners = frustum.corners();
+        let top = 0.1 * 0.6_f64.tan();
+        assert!(corners[3].distance(&Vec3::from_array([top, top, -0.1])) < 1E-12);
+        assert!(corners[4..].iter().all(|c| !c[2].is_finite()));
+    }
+
+    // The planes are those of the matrix rows
+    let proj = matrix::perspective_fov4(ClipSpace::opengl(), 1.2_f64, 1., 0.1, 100.);
+    let frustum: Frustum<f64, Vec3, Vec4> =
+        Frustum::of_matrix(&FArray2::from_array(proj), DepthRange::NegOneToOne);
+    let left = frustum.planes()[0];
+    let expected = Vec4::from_array([proj[12] + proj[0], 0., proj[14] + proj[2], 0.]).normalize();
+    assert!(left.distance(&expected) < 1E-12);
+}
@@ -0,0 +1,528 @@
+//a Imports
+use geo_nd::geo2d::{
+    Bezier, FillRule, Float4, Point, Polygon, Range, Rectangle, Transform, Winding,
+};
+use geo_nd::{Geometry2D, SqMatrix, Vector};
+
+mod common;
+use common::{assert_near, assert_rect, values, vec2};
+
+//a Types
+type Vec2 = <f64 as Geometry2D<f64>>::Vec2;
+type Mat2 = <f64 as Geometry2D<f64>>::Mat2;
+type Pt = Point<f64, Vec2>;
+type Rect = Rectangle<f64, Vec2>;
+type Trans = Transform<f64, Vec2, Mat2>;
+
+//a Useful functions
+//fi transforms
+/// A set of random transformations, including shears and reflections
+fn transforms(n: usize) -> Vec<Trans> {
+    values(n * 6)
+        .chunks(6)
+        .map(|v| {
+            Trans::of_matrix(
+                Mat2::from_array([v[0], v[1], v[2], v[3]]),
+                &vec2(v[4], v[5]),
+            )
+        })
+        .collect()
+}
+
+//fi radial_polygon
+/// A simple (but not convex) polygon with vertices anticlockwise at
+/// random radii around the origin
+fn radial_polygon(n: usize) -> Polygon<f64, Vec2> {
+    let step = std::f64::consts::TAU / n as f64;
+    Polygon::new(
+        values(n)
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let (s, c) = (step * i as f64).sin_cos();
+                vec2(c, s) * (2. + r)
+            })
+            .collect(),
+    )
+}
+
+//fi triangle_area
+fn triangle_area(v: &[Vec2], t: &[usize; 3]) -> f64 {
+    Polygon::new(t.iter().map(|i| v[*i]).collect()).signed_area()
+}
+
+//fi pentagram
+/// A pentagram of unit radius, joining every other vertex of a
+/// regular pentagon
+fn pentagram() -> Polygon<f64, Vec2> {
+    Polygon::new(
+        (0..5)
+            .map(|i| {
+                let angle = std::f64::consts::TAU * (i * 2 % 5) as f64 / 5.;
+                let (s, c) = angle.sin_cos();
+                vec2(c, s)
+            })
+            .collect(),
+    )
+}
+
+//a Tests
+//ft test_point
+#[test]
+fn test_point() {
+    let p = Pt::new(1., 2.);
+    let q = Pt::new(4., 6.);
+    assert_eq!((p.x(), p.y()), (1., 2.));
+    assert!(Pt::origin().is_origin());
+    assert!(!p.is_origin());
+    assert_eq!(format!("{p}"), "(1,2)");
+
+    // The difference of points is a vector, which may be added to a
+    // point
+    let d = q - p;
+    assert_eq!(d.into_array(), [3., 4.]);
+    assert_eq!(p.distance(&q), 5.);
+    assert_eq!((p + d).as_vec().into_array(), q.as_vec().into_array());
+    assert_eq!((q - d).as_vec().into_array(), [1., 2.]);
+    let mut r = p;
+    r += d;
+    r -= vec2(1., 1.);
+    assert_eq!(r.as_vec().into_array(), [3., 5.]);
+    assert_eq!(p.mix(&q, 0.5).as_vec().into_array(), [2.5, 4.]);
+
+    // Rotation is anticlockwise
+    let r = q.rotate_around(&p, std::f64::consts::FRAC_PI_2);
+    assert_near(r.as_vec().as_ref(), &[-3., 5.]);
+    let r: Pt = vec2(1., 0.).into();
+    let r = r.rotate_around(&Pt::origin(), std::f64::consts::FRAC_PI_2);
+    assert_near(r.as_vec().as_ref(), &[0., 1.]);
+}
+
+//ft test_range
+#[test]
+fn test_range() {
+    let a = Range::new(3., 1.);
+    assert_eq!((a.min(), a.max()), (1., 3.));
+    assert_eq!(a.size(), 2.);
+    assert_eq!(a.center(), 2.);
+    assert_eq!(a.at(0.25), 1.5);
+    assert!(a.contains(1.) && a.contains(3.) && !a.contains(3.5));
+    assert_eq!(format!("{a}"), "[1,3]");
+
+    let b = Range::new(2., 5.);
+    assert_eq!(a.union(&b), Range::new(1., 5.));
+    assert_eq!(a.intersection(&b), Some(Range::new(2., 3.)));
+    assert!(a.intersects(&b));
+    let c = Range::of_value(4.);
+    assert!(!a.intersects(&c));
+    assert_eq!(a.intersection(&c), None);
+    let mut d = a;
+    d.include(4.);
+    assert_eq!(d, Range::new(1., 4.));
+    assert!(d.intersects(&c));
+
+    assert_eq!(a.expand(1.), Range::new(0., 4.));
+    assert_eq!(a.expand(-0.5), Range::new(1.5, 2.5));
+    assert_eq!(a.expand(-2.), Range::of_value(2.));
+}
+
+//ft test_rectangle
+#[test]
+fn test_rectangle() {
+    let r = Rect::new(&vec2(3., 1.), &vec2(1., 2.));
+    assert_rect(&r, [1., 1.], [3., 2.]);
+    assert_eq!((r.width(), r.height(), r.area()), (2., 1., 2.));
+    assert_eq!(r.size().into_array(), [2., 1.]);
+    assert_eq!(r.center().into_array(), [2., 1.5]);
+    assert_eq!(r.x_range(), Range::new(1., 3.));
+    assert_eq!(r.y_range(), Range::new(1., 2.));
+    assert_rect(
+        &Rect::of_ranges(&r.x_range(), &r.y_range()),
+        [1., 1.],
+        [3., 2.],
+    );
+    assert_rect(&Rect::of_cwh(&vec2(2., 1.5), 2., -1.), [1., 1.], [3., 2.]);
+    assert_eq!(
+        r.corners().map(|c| c.into_array()),
+        [[1., 1.], [3., 1.], [3., 2.], [1., 2.]]
+    );
+    assert!(r.contains(&vec2(2., 2.)) && !r.contains(&vec2(2., 2.5)));
+    assert_eq!(format!("{r}"), "[(1,1) - (3,2)]");
+
+    // Union and intersection
+    let s = Rect::from_points([vec2(2., 0.), vec2(4., 1.5)].iter()).unwrap();
+    assert_rect(&r.union(&s), [1., 0.], [4., 2.]);
+    assert_rect(&r.intersection(&s).unwrap(), [2., 1.], [3., 1.5]);
+    assert!(r.intersects(&s));
+    let far = r.translate(&vec2(5., 0.));
+    assert_rect(&far, [6., 1.], [8., 2.]);
+    assert!(!r.intersects(&far));
+    assert!(r.intersection(&far).is_none());
+    let mut t = r;
+    t.include(&vec2(0., 5.));
+    assert_rect(&t, [0., 1.], [3., 5.]);
+    assert_rect::<Rect>(&(*r.as_aabb()).into(), [1., 1.], [3., 2.]);
+
+    // Margins
+    let margins = Float4::new(0.5, 0.25, 1., 0.);
+    assert_rect(&r.expand(&margins), [0.5, 0.75], [4., 2.]);
+    assert_rect(&r.shrink(&margins), [1.5, 1.25], [2., 2.]);
+    assert_rect(&r.expand(&Float4::uniform(1.)), [0., 0.], [4., 3.]);
+    assert_rect(&r.shrink(&Float4::uniform(1.)), [2., 1.5], [2., 1.5]);
+    assert_eq!(-margins, Float4::new(-0.5, -0.25, -1., 0.));
+
+    // Transformation gives the bounds of the transformed corners
+    for t in transforms(10) {
+        let tr = r.transform(&t);
+        let corners = r.corners().map(|c| t.apply(&c));
+        assert!(corners
+            .iter()
+            .all(|c| tr.expand(&Float4::uniform(1E-12)).contains(c)));
+        for i in 0..2 {
+            assert!(corners.iter().any(|c| c[i] == tr.min()[i]));
+            assert!(corners.iter().any(|c| c[i] == tr.max()[i]));
+        }
+    }
+}
+
+//ft test_bezier
+#[test]
+fn test_bezier() {
+    let p = [vec2(0., 0.), vec2(1., 2.), vec2(3., 2.), vec2(4., 0.)];
+    let line = Bezier::line(&p[0], &p[3]);
+    let quad = Bezier::quadratic(&p[0], &p[1], &p[3]);
+    let cubic = Bezier::cubic(&p[0], &p[1], &p[2], &p[3]);
+    assert_eq!([line.degree(), quad.degree(), cubic.degree()], [1, 2, 3]);
+    assert_eq!(cubic.control_points().len(), 4);
+    assert_eq!(format!("{quad}"), "Bezier[(0,0) -> (1,2) -> (4,0)]");
+
+    for b in [line, quad, cubic] {
+        // The curve runs from its start to its end
+        assert_eq!(b.start().into_array(), [0., 0.]);
+        assert_eq!(b.end().into_array(), [4., 0.]);
+        assert_eq!(b.point_at(0.).into_array(), [0., 0.]);
+        assert_near(b.point_at(1.).as_ref(), &[4., 0.]);
+
+        // The tangent is the derivative of the point
+        for t in [0., 0.3, 0.5, 1.] {
+            let h = 1E-6;
+            let d = (b.point_at(t + h) - b.point_at(t - h)) / (2. * h);
+            assert!(d.distance(&b.tangent_at(t)) < 1E-6);
+        }
+
+        // Splitting gives two curves that together cover the original
+        let (first, second) = b.split(0.3);
+        assert_eq!(first.degree(), b.degree());
+        assert_near(first.end().as_ref(), b.point_at(0.3).as_ref());
+        assert_near(second.start().as_ref(), b.point_at(0.3).as_ref());
+        for t in [0., 0.25, 0.5, 1.] {
+            assert_near(first.point_at(t).as_ref(), b.point_at(0.3 * t).as_ref());
+            assert_near(
+                second.point_at(t).as_ref(),
+                b.point_at(0.3 + 0.7 * t).as_ref(),
+            );
+        }
+        let r = b.reversed();
+        assert_near(r.point_at(0.2).as_ref(), b.point_at(0.8).as_ref());
+
+        // The length is between the chord and the control polygon
+        let length = b.length(1E-9);
+        let (a, c) = b.bisect();
+        assert!((length - a.length(1E-9) - c.length(1E-9)).abs() < 1E-8);
+        assert!(length >= 4. - 1E-12);
+
+        // Transformation of the control points is exact
+        for t in transforms(4) {
+            let tb = b.transform(&t);
+            assert_near(
+                tb.point_at(0.4).as_ref(),
+                t.apply(&b.point_at(0.4)).as_ref(),
+            );
+        }
+    }
+    assert_eq!(line.length(1.), 4.);
+
+    // A quarter circle approximated by a cubic
+    let k = 4. * (2.0_f64.sqrt() - 1.) / 3.;
+    let arc = Bezier::cubic(&vec2(1., 0.), &vec2(1., k), &vec2(k, 1.), &vec2(0., 1.));
+    assert!((arc.length(1E-10) - std::f64::consts::FRAC_PI_2).abs() < 1E-3);
+}
+
+//ft test_polygon
+#[test]
+fn test_polygon() {
+    let centre = vec2(1., 2.);
+    let hexagon: Polygon<f64, Vec2> = Polygon::regular(&centre, 6, 2., 0.);
+    assert_eq!(hexagon.len(), 6);
+    assert!(!hexagon.is_empty());
+    assert_near(hexagon.vertices()[0].as_ref(), &[3., 2.]);
+    assert_near(hexagon.vertices()[3].as_ref(), &[-1., 2.]);
+    assert!(hexagon
+        .vertices()
+        .iter()
+        .all(|v| (v.distance(&centre) - 2.).abs() < 1E-12));
+    assert!((hexagon.perimeter() - 12.).abs() < 1E-12);
+    assert_eq!(hexagon.edges().count(), 6);
+    let bounds = hexagon.bounds().unwrap();
+    assert_rect(
+        &bounds,
+        [-1., 2. - 3.0_f64.sqrt()],
+        [3., 2. + 3.0_f64.sqrt()],
+    );
+
+    let star: Polygon<f64, Vec2> = Polygon::star(&centre, 5, 2., 1., 0.5);
+    assert_eq!(star.len(), 10);
+    for (i, v) in star.vertices().iter().enumerate() {
+        let radius = if i % 2 == 0 { 2. } else { 1. };
+        assert!((v.distance(&centre) - radius).abs() < 1E-12);
+    }
+
+    let r = Rect::new(&vec2(0., 0.), &vec2(2., 1.));
+    let square = Polygon::of_rectangle(&r);
+    assert_eq!(square.perimeter(), 6.);
+    assert_eq!(format!("{square}"), "Polygon[(0,0), (2,0), (2,1), (0,1)]");
+    let beziers = square.as_beziers();
+    assert_eq!(beziers.len(), 4);
+    assert_eq!(beziers[3].start().into_array(), [0., 1.]);
+    assert_eq!(beziers[3].end().into_array(), [0., 0.]);
+    let moved = square.translate(&vec2(1., 1.));
+    assert_rect(&moved.bounds().unwrap(), [1., 1.], [3., 2.]);
+
+    for t in transforms(4) {
+        let th = hexagon.transform(&t);
+        for (a, b) in hexagon.vertices().iter().zip(th.vertices()) {
+            assert_near(t.apply(a).as_ref(), b.as_ref());
+        }
+    }
+    let empty: Polygon<f64, Vec2> = Polygon::new(vec![]);
+    assert!(empty.is_empty());
+    assert!(empty.bounds().is_none());
+    assert_eq!(empty.perimeter(), 0.);
+}
+
+//ft test_polygon_area
+#[test]
+fn test_polygon_area() {
+    let r = Rect::new(&vec2(1., 1.), &vec2(4., 3.));
+    let rect = Polygon::of_rectangle(&r);
+    assert_eq!(rect.signed_area(), 6.);
+    assert_eq!(rect.winding(), Some(Winding::Anticlockwise));
+    assert_near(rect.centroid().unwrap().as_ref(), &[2.5, 2.]);
+    let reversed = rect.reversed();
+    assert_eq!(reversed.signed_area(), -6.);
+    assert_eq!(reversed.area(), 6.);
+    assert_eq!(reversed.winding(), Some(Winding::Clockwise));
+    assert_near(reversed.centroid().unwrap().as_ref(), &[2.5, 2.]);
+
+    // An L shape, whose centroid is that of its two rectangles
+    let l = Polygon::new(vec![
+        vec2(0., 0.),
+        vec2(3., 0.),
+        vec2(3., 1.),
+        vec2(1., 1.),
+        vec2(1., 2.),
+        vec2(0., 2.),
+    ]);
+    assert_eq!(l.area(), 4.);
+    assert_near(l.centroid().unwrap().as_ref(), &[1.25, 0.75]);
+    assert!(!l.is_convex());
+    assert!(rect.is_convex());
+    assert!(reversed.is_convex());
+
+    let hexagon: Polygon<f64, Vec2> = Polygon::regular(&vec2(1., 2.), 6, 2., 0.3);
+    assert!((hexagon.area() - 6. * 3.0_f64.sqrt()).abs() < 1E-12);
+    assert_near(hexagon.centroid().unwrap().as_ref(), &[1., 2.]);
+    assert!(hexagon.is_convex());
+    assert!(!Polygon::star(&vec2(0., 0.), 5, 2., 1., 0.).is_convex());
+
+    // A pentagram turns the same way at every vertex, but winds twice
+    assert!(!pentagram().is_convex());
+
+    // Collinear vertices are permitted in a convex polygon, but a
+    // polygon with no area is neither convex nor has a winding
+    let with_midpoint = Polygon::new(vec![vec2(0., 0.), vec2(1., 0.), vec2(2., 0.), vec2(2., 2.)]);
+    assert!(with_midpoint.is_convex());
+    let flat = Polygon::new(vec![vec2(0., 0.), vec2(1., 1.), vec2(2., 2.)]);
+    assert!(!flat.is_convex());
+    assert_eq!(flat.winding(), None);
+    assert!(flat.centroid().is_none());
+}
+
+//ft test_polygon_contains
+#[test]
+fn test_polygon_contains() {
+    let l = Polygon::new(vec![
+        vec2(0., 0.),
+        vec2(3., 0.),
+        vec2(3., 1.),
+        vec2(1., 1.),
+        vec2(1., 2.),
+        vec2(0., 2.),
+    ]);
+    for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+        assert!(l.contains(&vec2(0.5, 1.5), rule));
+        assert!(l.contains(&vec2(2.5, 0.5), rule));
+        assert!(!l.contains(&vec2(2., 1.5), rule));
+        assert!(!l.contains(&vec2(-1., 0.5), rule));
+        assert!(l.reversed().contains(&vec2(0.5, 1.5), rule));
+    }
+    assert_eq!(l.winding_number(&vec2(0.5, 0.5)), 1);
+    assert_eq!(l.reversed().winding_number(&vec2(0.5, 0.5)), -1);
+
+    // The centre of a pentagram is wound around twice
+    let pentagram = pentagram();
+    let centre = vec2(0., 0.);
+    assert_eq!(pentagram.winding_number(&centre), 2);
+    assert!(pentagram.contains(&centre, FillRule::NonZero));
+    assert!(!pentagram.contains(&centre, FillRule::EvenOdd));
+    let point = vec2(0.7, 0.);
+    assert_eq!(pentagram.winding_number(&point), 1);
+    assert!(pentagram.contains(&point, FillRule::EvenOdd));
+
+    // For a simple polygon the rules agree with its triangulation
+    let p = radial_polygon(24);
+    let triangles = p.triangulate();
+    for xy in values(400)[..].chunks(2) {
+        let q = vec2(xy[0] * 3., xy[1] * 3.);
+        let in_triangle = triangles.iter().any(|t| {
+            let tri = Polygon::new(t.iter().map(|i| p.vertices()[*i]).collect());
+            tri.contains(&q, FillRule::NonZero)
+        });
+        assert_eq!(p.contains(&q, FillRule::NonZero), in_triangle);
+        assert_eq!(p.contains(&q, FillRule::EvenOdd), in_triangle);
+    }
+}
+
+//ft test_polygon_triangulate
+#[test]
+fn test_polygon_triangulate() {
+    let star: Polygon<f64, Vec2> = Polygon::star(&vec2(1., 2.), 5, 2., 1., 0.5);
+    for p in [star.clone(), star.reversed(), radial_polygon(30)] {
+        let triangles = p.triangulate();
+        assert_eq!(triangles.len(), p.len() - 2);
+        let mut total = 0.;
+        for t in &triangles {
+            let area = triangle_area(p.vertices(), t);
+            assert!(area * p.signed_area() > 0.);
+            total += area;
+        }
+        assert!((total - p.signed_area()).abs() < 1E-12);
+    }
+
+    // Collinear vertices give no degenerate triangles
+    let with_midpoints = Polygon::new(vec![
+        vec2(0., 0.),
+        vec2(1., 0.),
+        vec2(2., 0.),
+        vec2(2., 1.),
+        vec2(2., 2.),
+        vec2(0., 2.),
+    ]);
+    let triangles = with_midpoints.triangulate();
+    assert!(triangles
+        .iter()
+        .all(|t| triangle_area(with_midpoints.vertices(), t) > 0.));
+    let total: f64 = triangles
+        .iter()
+        .map(|t| triangle_area(with_midpoints.vertices(), t))
+        .sum();
+    assert_eq!(total, 4.);
+
+    let flat = Polygon::new(vec![vec2(0., 0.), vec2(1., 1.), vec2(2., 2.)]);
+    assert!(flat.triangulate().is_empty());
+    assert!(Polygon::<f64, Vec2>::new(vec![]).triangulate().is_empty());
+}
+
+//ft test_polygon_simplify
+#[test]
+fn test_polygon_simplify() {
+    // A square with vertices along its edges, slightly displaced
+    let noisy = Polygon::new(vec![
+        vec2(0., 0.),
+        vec2(1., 0.01),
+        vec2(2., 0.),
+        vec2(2.01, 1.),
+        vec2(2., 2.),
+        vec2(1., 1.99),
+        vec2(0., 2.),
+        vec2(-0.01, 1.),
+    ]);
+    let simple = noisy.simplify(0.1);
+    assert_eq!(
+        simple
+            .vertices()
+            .iter()
+            .map(|v| v.into_array())
+            .collect::<Vec<_>>(),
+        vec![[0., 0.], [2., 0.], [2., 2.], [0., 2.]]
+    );
+    assert_eq!(noisy.simplify(0.001).len(), 8);
+
+    // Every removed vertex is within the tolerance of the simplified
+    // polygon
+    let p = radial_polygon(100);
+    let simple = p.simplify(0.5);
+    assert!(simple.len() < p.len());
+    assert!(simple.len() >= 3);
+    for v in p.vertices() {
+        let closest = simple
+            .edges()
+            .map(|(a, b)| {
+                let ab = b - a;
+                let t = ((*v - a).dot(&ab) / ab.length_sq()).clamp(0., 1.);
+                v.distance(&(a + ab * t))
+            })
+            .fold(f64::MAX, f64::min);
+        assert!(closest <= 0.5 + 1E-12);
+    }
+}
+
+//ft test_transform
+#[test]
+fn test_transform() {
+    let half_pi = std::f64::consts::FRAC_PI_2;
+    let p = vec2(1., 0.);
+    assert_eq!(Trans::identity().apply(&p).into_array(), [1., 0.]);
+    assert_eq!(Trans::default().apply(&p).into_array(), [1., 0.]);
+    let t = Trans::of_translation(&vec2(2., 3.));
+    assert_eq!(t.apply(&p).into_array(), [3., 3.]);
+    assert_eq!(t.apply_vector(&p).into_array(), [1., 0.]);
+    assert_near(Trans::of_rotation(half_pi).apply(&p).as_ref(), &[0., 1.]);
+    assert_eq!(
+        Trans::of_scale(2., 3.).apply(&vec2(1., 1.)).into_array(),
+        [2., 3.]
+    );
+    assert_eq!(Trans::of_scale(2., 3.).determinant(), 6.);
+
+    // of_trs scales, then rotates, then translates
+    let trs = Trans::of_trs(&vec2(2., 3.), half_pi, 2.);
+    let expected = Trans::of_translation(&vec2(2., 3.))
+        * Trans::of_rotation(half_pi)
+        * Trans::of_scale(2., 2.);
+    assert_near(&trs.as_mat3(), &expected.as_mat3());
+    assert_near(trs.apply(&p).as_ref(), &[2., 5.]);
+    assert_near(&trs.as_mat3(), &[0., -2., 2., 2., 0., 3., 0., 0., 1.]);
+
+    let ts = transforms(12);
+    for pair in ts.chunks(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let q = b.translation();
+
+        // a * b applies b then a
+        let ab = a * b;
+        assert_near(ab.apply(&q).as_ref(), a.apply(&b.apply(&q)).as_ref());
+        let mut c = a;
+        c *= b;
+        assert_near(&c.as_mat3(), &ab.as_mat3());
+        assert_near(ab.matrix().as_ref(), (a.matrix() * b.matrix()).as_ref());
+
+        // The inverse undoes the transformation
+        let inverse = a.try_inverse(1E-10).unwrap();
+        assert_near(inverse.apply(&a.apply(&q)).as_ref(), q.as_ref());
+        assert_near(&(a * inverse).as_mat3(), &Trans::identity().as_mat3());
+        assert_near(&a.inverse().as_mat3(), &inverse.as_mat3());
+    }
+    let singular = Trans::of_scale(1., 0.);
+    assert!(singular.try_inverse(1E-10).is_err());
+    assert_eq!(format!("{t}"), "[1,0,0,1] + (2,3)");
+}
@@ -0,0 +1,226 @@
+//a Imports
+use std::collections::HashMap;
+
+use geo_nd::{hull2, hull3, FArray, HullFace, Vector, Vector3};
+
+mod common;
+use common::{values, vec2};
+
+//a Types
+type Vec2 = FArray<f64, 2>;
+type Vec3 = FArray<f64, 3>;
+
+//a Useful functions
+//fi vec3
+fn vec3(x: f64, y: f64, z: f64) -> Vec3 {
+    Vec3::from_array([x, y, z])
+}
+
+//fi cross2
+fn cross2(o: &Vec2, a: &Vec2, b: &Vec2) -> f64 {
+    (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+}
+
+//fi check_hull2
+/// Check that a hull is convex and anticlockwise, and contains all of
+/// the points
+fn check_hull2(points: &[Vec2], hull: &[usize]) {
+    let n = hull.len();
+    for i in 0..n {
+        let (a, b) = (&points[hull[i]], &points[hull[(i + 1) % n]]);
+        assert!(cross2(a, b, &points[hull[(i + 2) % n]]) > 0.);
+        for p in points {
+            assert!(cross2(a, b, p) >= -1E-12);
+        }
+    }
+}
+
+//fi check_hull3
+/// Check that every edge of a hull is shared by two faces, that the
+/// faces are anticlockwise viewed from outside, and that the hull
+/// contains all of the points
+fn check_hull3(points: &[Vec3], faces: &[HullFace<f64, Vec3>]) {
+    let mut edges = HashMap::new();
+    for f in faces {
+        let [a, b, c] = f.vertices.map(|i| points[i]);
+        assert!((f.normal.length() - 1.).abs() < 1E-12);
+        assert!((b - a).cross_product(&(c - a)).dot(&f.normal) > 0.);
+        for v in [a, b, c] {
+            assert!((f.normal.dot(&v) - f.offset).abs() < 1E-12);
+        }
+        for p in points {
+            assert!(f.normal.dot(p) <= f.offset + 1E-12);
+        }
+        for k in 0..3 {
+            let edge = (f.vertices[k], f.vertices[(k + 1) % 3]);
+            assert!(edges.insert(edge, ()).is_none());
+        }
+    }
+    for (a, b) in edges.keys() {
+        assert!(edges.contains_key(&(*b, *a)));
+    }
+}
+
+//fi vertices3
+/// Get the sorted indices of the vertices of a hull
+fn vertices3(faces: &[HullFace<f64, Vec3>]) -> Vec<usize> {
+    let mut v: Vec<usize> = faces.iter().flat_map(|f| f.vertices).collect();
+    v.sort();
+    v.dedup();
+    v
+}
+
+//a Tests
+//ft test_hull2
+#[test]
+fn test_hull2() {
+    // A square with points inside, on its edges, and repeated
+    let points = [
+        vec2(0.5, 0.5),
+        vec2(0., 0.),
+        vec2(1., 0.),
+        vec2(0.5, 0.),
+        vec2(1., 1.),
+        vec2(1., 0.25),
+        vec2(0., 1.),
+        vec2(0., 0.),
+        vec2(0.3, 0.7),
+        vec2(0.25, 1.),
+    ];
+    assert_eq!(hull2(&points), vec![1, 2, 4, 6]);
+
+    // A lattice, with many collinear points, scaled so that they are
+    // not exact
+    let lattice: Vec<Vec2> = (0..49)
+        .map(|i| vec2((i % 7) as f64, (i / 7) as f64) * 0.1)
+        .collect();
+    assert_eq!(hull2(&lattice), vec![0, 6, 48, 42]);
+
+    // Collinear and coincident points
+    let line: Vec<Vec2> = (0..10).map(|i| vec2(1., 0.1) * (i % 7) as f64).collect();
+    assert_eq!(hull2(&line), vec![0, 6]);
+    assert_eq!(hull2(&[vec2(1., 2.), vec2(1., 2.)]), vec![0]);
+    assert!(hull2::<f64, Vec2>(&[]).is_empty());
+
+    // Points that are not finite are ignored
+    let mut with_nan = points.to_vec();
+    for i in 0..30 {
+        let x = [f64::NAN, f64::INFINITY, -f64::INFINITY][i % 3];
+        with_nan.push(vec2(x, i as f64 * 0.1));
+        with_nan.push(vec2(0.5, x));
+    }
+    assert_eq!(hull2(&with_nan), vec![1, 2, 4, 6]);
+
+    // Random points, with some on a circle and some inside it
+    let points: Vec<Vec2> = values(400)
+        .chunks(2)
+        .enumerate()
+        .map(|(i, xy)| {
+            let p = vec2(xy[0], xy[1]);
+            if i % 4 == 0 {
+                p.normalize()
+            } else {
+                p * 0.5
+            }
+        })
+        .collect();
+    let hull = hull2(&points);
+    check_hull2(&points, &hull);
+    assert!(hull.iter().all(|i| i % 4 == 0));
+    assert_eq!(hull.len(), 50);
+}
+
+//ft test_hull3
+#[test]
+fn test_hull3() {
+    // A cube, with points inside, on its faces and edges, and repeated
+    let mut points = vec![];
+    for i in 0..8 {
+        points.push(vec3((i & 1) as f64, ((i >> 1) & 1) as f64, (i >> 2) as f64));
+    }
+    points.push(vec3(0.5, 0.5, 0.5));
+    points.push(vec3(0.5, 0.5, 0.));
+    points.push(vec3(1., 0.3, 0.6));
+    points.push(vec3(0.5, 0., 0.));
+    points.push(vec3(0., 1., 0.2));
+    points.push(vec3(1., 1., 1.));
+    let faces = hull3(&points);
+    check_hull3(&points, &faces);
+    assert_eq!(faces.len(), 12);
+    assert_eq!(vertices3(&faces), (0..8).collect::<Vec<_>>());
+    for f in &faces {
+        let n = f.normal.into_array();
+        assert_eq!(n.iter().filter(|x| x.abs() == 1.).count(), 1);
+        assert_eq!(n.iter().filter(|x| **x == 0.).count(), 2);
+    }
+
+    // A lattice, with many coplanar and collinear points, scaled so
+    // that they are not exact
+    let lattice: Vec<Vec3> = (0..125)
+        .map(|i| vec3((i % 5) as f64, ((i / 5) % 5) as f64, (i / 25) as f64) * 0.3)
+        .collect();
+    let faces = hull3(&lattice);
+    check_hull3(&lattice, &faces);
+    assert_eq!(faces.len(), 12);
+    assert_eq!(vertices3(&faces), vec![0, 4, 20, 24, 100, 104, 120, 124]);
+
+    // Random points, with some on a sphere and some inside it, give a
+    // closed hull of the points on the sphere
+    let points: Vec<Vec3> = values(600)
+        .chunks(3)
+        .enumerate()
+        .map(|(i, xyz)| {
+            let p = vec3(xyz[0], xyz[1], xyz[2]);
+            if i % 4 == 0 {
+                p.normalize()
+            } else {
+                p * 0.5
+            }
+        })
+        .collect();
+    let faces = hull3(&points);
+    check_hull3(&points, &faces);
+    let vertices = vertices3(&faces);
+    assert!(vertices.iter().all(|i| i % 4 == 0));
+    assert_eq!(vertices.len(), 50);
+    assert_eq!(faces.len(), 2 * vertices.len() - 4);
+}
+
+//ft test_hull3_degenerate
+#[test]
+fn test_hull3_degenerate() {
+    // Coplanar points (on a tilted plane) give a flat hull, two-sided
+    let (u, v) = (vec3(1., 2., 0.5), vec3(-1., 0.5, 2.));
+    let points: Vec<Vec3> = values(60)
+        .chunks(2)
+        .map(|xy| vec3(1., 1., 1.) + u * xy[0] + v * xy[1])
+        .chain([vec3(1., 1., 1.) + u * 2., vec3(1., 1., 1.) + v * 2.])
+        .collect();
+    let faces = hull3(&points);
+    check_hull3(&points, &faces);
+    let outline = vertices3(&faces);
+    assert!(outline.contains(&30) && outline.contains(&31));
+    assert_eq!(faces.len(), 2 * (outline.len() - 2));
+    let normal = u.cross_product(&v).normalize();
+    for f in &faces {
+        assert!((f.normal.dot(&normal).abs() - 1.).abs() < 1E-12);
+    }
+
+    // Collinear or coincident points have no hull
+    let line: Vec<Vec3> = (0..5).map(|i| vec3(1., 2., 3.) * i as f64).collect();
+    assert!(hull3(&line).is_empty());
+    assert!(hull3(&[vec3(1., 2., 3.); 4]).is_empty());
+    assert!(hull3::<f64, Vec3>(&[]).is_empty());
+
+    // Points that are not finite are ignored
+    let mut points: Vec<Vec3> = (0..8)
+        .map(|i| vec3((i & 1) as f64, ((i >> 1) & 1) as f64, (i >> 2) as f64))
+        .collect();
+    for i in 0..40 {
+        let x = [f64::NAN, f64::INFINITY, -f64::INFINITY][i % 3];
+        points.insert(i % 7, vec3(0.5, x, i as f64 * 0.02));
+    }
+    let faces = hull3(&points);
+    assert_eq!(faces.len(), 12);
+    assert!(vertices3(&faces).iter().all(|i| points[*i][1].is_finite()));
+}
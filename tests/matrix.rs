@@ -0,0 +1,936 @@
+//a Imports
+use geo_nd::matrix;
+use geo_nd::{ClipSpace, DepthRange, FArray, FArray2, FArrayRC, FQArrayTrans, Handedness};
+use geo_nd::{Geometry3D, MatrixDecomposition, QArray};
+use geo_nd::{Matrix, SqMatrix, SqMatrix3, SqMatrix4, Transform, Vector};
+use geo_nd::{Quaternion, SingularMatrix};
+
+mod common;
+use common::{assert_near, values};
+
+//a Useful functions
+//fi random_matrix
+/// A random D-by-D matrix, which is diagonally weighted if `weight` is nonzero
+fn random_matrix<const D2: usize, const D: usize>(n: usize, weight: f64) -> [f64; D2] {
+    let mut m: [f64; D2] = values((n + 1) * D2)[n * D2..].try_into().unwrap();
+    for i in 0..D {
+        m[i * (D + 1)] += weight;
+    }
+    m
+}
+
+//a Tests
+//ft test_try_inverse_fns
+#[test]
+fn test_try_inverse_fns() {
+    let m2 = [1., 2., 7., 2.];
+    let m3 = [1., 3., 2., 0., 2., 3., -1., 2., 3.];
+    let m4 = [
+        1., 3., 2., 1., 0., 2., 3., 3., -1., 2., 3., 2., 0., 0., 2., 1.,
+    ];
+    assert_near(
+        &matrix::try_inverse2(&m2, 1E-10).unwrap(),
+        &matrix::inverse2(&m2),
+    );
+    assert_near(
+        &matrix::try_inverse3(&m3, 1E-10).unwrap(),
+        &matrix::inverse3(&m3),
+    );
+    assert_near(
+        &matrix::try_inverse4(&m4, 1E-10).unwrap(),
+        &matrix::inverse4(&m4),
+    );
+
+    // The error carries the determinant, and the epsilon is configurable
+    let d4 = matrix::determinant4(&m4);
+    assert_eq!(
+        matrix::try_inverse4(&m4, d4.abs() * 2.),
+        Err(SingularMatrix { determinant: d4 })
+    );
+    let small = [1E-5, 0., 0., 1E-5];
+    assert!(matrix::try_inverse2(&small, 1E-12).is_ok());
+    assert_eq!(
+        matrix::try_inverse2(&small, 1E-8).unwrap_err().determinant,
+        matrix::determinant2(&small)
+    );
+
+    // Singular matrices always fail
+    let s3 = [1., 3., 2., 0., 2., 3., 1., 5., 5.];
+    assert_eq!(
+        matrix::try_inverse3(&s3, 0.),
+        Err(SingularMatrix { determinant: 0. })
+    );
+    assert_eq!(matrix::inverse3(&s3), [0.; 9]);
+}
+
+//ft test_try_inverse_sqmatrix
+#[test]
+fn test_try_inverse_sqmatrix() {
+    let m = FArray2::<f64, 3, 9>::from_array([1., 3., 2., 0., 2., 3., -1., 2., 3.]);
+    let m_inv = m.try_inverse(1E-10).unwrap();
+    assert_near(
+        (m * m_inv).as_ref(),
+        FArray2::<f64, 3, 9>::identity().as_ref(),
+    );
+
+    let s = FArray2::<f64, 2, 4>::from_array([1., 2., 2., 4.]);
+    assert_eq!(s.try_inverse(1E-10).unwrap_err().determinant, 0.);
+    assert!(s.inverse().is_zero());
+
+    let err = FArray2::<f64, 4, 16>::zero().try_inverse(0.).unwrap_err();
+    assert_eq!(format!("{err}"), "matrix is singular (determinant 0)");
+}
+
+//ft test_try_inverse_transform
+#[test]
+fn test_try_inverse_transform() {
+    let t: FArray<f64, 3> = [1., 2., 3.].into();
+    let q = QArray::<f64, FArray<f64, 3>, FArray<f64, 4>>::of_axis_angle(&t.normalize(), 0.3);
+    let trans = FQArrayTrans::of_trs(t, q, 2.);
+    let inv = trans.try_inverse(1E-10).unwrap();
+    assert_eq!(inv.scale(), 0.5);
+    let back = inv.try_inverse(1E-10).unwrap();
+    assert_near(back.translation().as_ref(), t.as_ref());
+    assert_near(back.rotation().as_ref(), q.as_ref());
+
+    let flat = FQArrayTrans::of_trs(t, q, 1E-3);
+    assert!(flat.try_inverse(1E-6).is_ok());
+    let err = flat.try_inverse(1E-2).unwrap_err();
+    assert!((err.determinant - 1E-9).abs() < 1E-20);
+
+    let zero = FQArrayTrans::of_trs(t, q, 0.);
+    assert!(zero.try_inverse(0.).is_err());
+}
+
+//ft test_lu_small
+/// Test the generic LU-based functions against the specific 2, 3 and 4 versions
+#[test]
+fn test_lu_small() {
+    for n in 0..20 {
+        let m2 = random_matrix::<4, 2>(n, 0.);
+        let m3 = random_matrix::<9, 3>(n, 0.);
+        let m4 = random_matrix::<16, 4>(n, 0.);
+        assert_near(
+            &[matrix::determinant::<f64, 4, 2>(&m2)],
+            &[matrix::determinant2(&m2)],
+        );
+        assert_near(
+            &[matrix::determinant::<f64, 9, 3>(&m3)],
+            &[matrix::determinant3(&m3)],
+        );
+        assert_near(
+            &[matrix::determinant::<f64, 16, 4>(&m4)],
+            &[matrix::determinant4(&m4)],
+        );
+        assert_near(&matrix::inverse::<f64, 4, 2>(&m2), &matrix::inverse2(&m2));
+        assert_near(&matrix::inverse::<f64, 9, 3>(&m3), &matrix::inverse3(&m3));
+        assert_near(&matrix::inverse::<f64, 16, 4>(&m4), &matrix::inverse4(&m4));
+    }
+
+    // Singular matrices
+    let s3 = [1., 3., 2., 0., 2., 3., 1., 5., 5.];
+    assert_eq!(matrix::determinant::<f64, 9, 3>(&s3), 0.);
+    assert_eq!(matrix::lu_solve::<f64, 9, 3>(&s3, &[1., 2., 3.]), None);
+    assert_eq!(matrix::inverse::<f64, 9, 3>(&s3), [0.; 9]);
+    assert!(matrix::try_inverse::<f64, 9, 3>(&s3, 0.).is_err());
+
+    // Matrices that are singular but for rounding errors, at any scale
+    for scale in [1E-90, 1., 1E90] {
+        let s3 = [1., 2., 3., 4., 5., 6., 7., 8., 9.].map(|x| x * scale * 0.1);
+        assert_eq!(matrix::determinant::<f64, 9, 3>(&s3), 0.);
+        assert_eq!(matrix::lu_solve::<f64, 9, 3>(&s3, &[1., 2., 3.]), None);
+        assert!(matrix::try_inverse::<f64, 9, 3>(&s3, 0.).is_err());
+    }
+
+    // Rows of very different magnitudes are not singular
+    let m3 = [1E20, 0., 0., 0., 1., 1., 0., 0., 1E-20];
+    let x = matrix::lu_solve::<f64, 9, 3>(&m3, &[1E20, 2., 1E-20]).unwrap();
+    assert_near(&x, &[1., 1., 1.]);
+}
+
+//ft test_lu_solve
+/// Solve 6-by-6 and 9-by-9 systems, and check the residual
+#[test]
+fn test_lu_solve() {
+    for n in 0..10 {
+        let m = random_matrix::<36, 6>(n, 2.);
+        let b: [f64; 6] = values(6 * (n + 1))[6 * n..].try_into().unwrap();
+        let x = matrix::lu_solve::<f64, 36, 6>(&m, &b).unwrap();
+        assert_near(&matrix::transform_vec::<f64, 36, 6, 6>(&m, &x), &b);
+
+        let m = random_matrix::<81, 9>(n, 3.);
+        let b: [f64; 9] = values(9 * (n + 1))[9 * n..].try_into().unwrap();
+        let x = matrix::lu_solve::<f64, 81, 9>(&m, &b).unwrap();
+        assert_near(&matrix::transform_vec::<f64, 81, 9, 9>(&m, &x), &b);
+
+        // The decomposition can be reused
+        let (lu, perm, _) = matrix::lu_decompose::<f64, 81, 9>(&m);
+        assert_near(&matrix::lu_substitute(&lu, &perm, &b), &x);
+    }
+}
+
+//ft test_sqmatrix_large
+/// Test the SqMatrix implementations of FArray2 for sizes above 4
+#[test]
+fn test_sqmatrix_large() {
+    for n in 0..10 {
+        let m = FArray2::<f64, 6, 36>::from_array(random_matrix::<36, 6>(n, 0.5));
+        let i = FArray2::<f64, 6, 36>::identity();
+        assert_near((m * m.inverse()).as_ref(), i.as_ref());
+        assert_near((m.try_inverse(1E-10).unwrap() * m).as_ref(), i.as_ref());
+        assert_near(
+            &[m.determinant() * m.transpose().inverse().determinant()],
+            &[1.],
+        );
+        let v = FArray::<f64, 6>::from_array([1., 2., 3., 4., 5., 6.]);
+        assert_near(m.inverse().transform(&m.transform(&v)).as_ref(), v.as_ref());
+
+        let m = FArray2::<f64, 9, 81>::from_array(random_matrix::<81, 9>(n, 1.));
+        let i = FArray2::<f64, 9, 81>::identity();
+        assert_near((m * m.inverse()).as_ref(), i.as_ref());
+    }
+    assert!(FArray2::<f64, 7, 49>::zero().try_inverse(0.).is_err());
+    assert_eq!(FArray2::<f32, 5, 25>::identity().determinant(), 1.);
+    assert_eq!(FArray2::<f32, 8, 64>::identity().determinant(), 1.);
+}
+
+//ft test_farrayrc
+/// Test the non-square FArrayRC matrices
+#[test]
+fn test_farrayrc() {
+    type M23 = FArrayRC<f64, 2, 3, 6>;
+    type M34 = FArrayRC<f64, 3, 4, 12>;
+    type M32 = FArrayRC<f64, 3, 2, 6>;
+    let a = M23::from_array([1., 2., 3., 4., 5., 6.]);
+    let b = M34::from_array([1., 0., 2., 0., 0., 1., 0., 2., 1., 1., 1., 1.]);
+
+    assert_eq!(a.element(1, 2), 6.);
+    assert_eq!(a.row(1), [4., 5., 6.]);
+    assert_eq!(a.column(1), [2., 5.]);
+    assert_eq!(format!("{a}"), "[1,2,3 4,5,6]");
+
+    // Typed multiplication: 2x3 times 3x4 is 2x4
+    let ab: FArrayRC<f64, 2, 4, 8> = a * b;
+    assert_eq!(ab.into_array(), [4., 5., 5., 7., 10., 11., 14., 16.]);
+    assert_eq!(a.multiply::<4, 12, 8>(&b), ab);
+
+    // Transpose: (AB)' = B'A'
+    let at: M32 = a.transpose();
+    assert_eq!(at.into_array(), [1., 4., 2., 5., 3., 6.]);
+    let abt: FArrayRC<f64, 4, 2, 8> = b.transpose() * at;
+    assert_eq!(abt, ab.transpose());
+
+    // Vectors, as an FArray or as a C-by-1 matrix
+    let v = FArray::<f64, 3>::from_array([1., -1., 2.]);
+    assert_eq!((a * v).into_array(), [5., 11.]);
+    assert_eq!(a.transform_vec(v.as_ref()), [5., 11.]);
+    let v: FArrayRC<f64, 3, 1, 3> = v.into_array().into();
+    assert_eq!((a * v).into_array(), [5., 11.]);
+
+    // Arithmetic
+    assert_eq!((a + a).into_array(), (a * 2.).into_array());
+    assert!((a - a).is_zero());
+    assert_eq!((-a / 2.).into_array(), [-0.5, -1., -1.5, -2., -2.5, -3.]);
+    let mut c = a;
+    c += a;
+    c *= 3.;
+    c -= a;
+    c /= 5.;
+    assert_eq!(c, a);
+    c.set_zero();
+    assert_eq!(c, M23::zero());
+
+    // Square matrices interoperate with FArray2
+    let sq = FArray2::<f64, 3, 9>::from_array([1., 3., 2., 0., 2., 3., -1., 2., 3.]);
+    let sq_rc: FArrayRC<f64, 3, 3, 9> = sq.into();
+    let a_sq: M23 = a * sq_rc;
+    assert_eq!(a_sq.row(0), [-2., 13., 17.]);
+    let back: FArray2<f64, 3, 9> = (sq_rc * sq_rc).into();
+    assert_eq!(back.into_array(), (sq * sq).into_array());
+}
+
+//ft test_farrayrc_serde
+#[test]
+fn test_farrayrc_serde() {
+    use serde::de::value::{Error, SeqDeserializer};
+    use serde::Deserialize;
+    let de = SeqDeserializer::<_, Error>::new(vec![1., 2., 3., 4., 5., 6.].into_iter());
+    let m = FArrayRC::<f64, 3, 2, 6>::deserialize(de).unwrap();
+    assert_eq!(m.row(2), [5., 6.]);
+    let de = SeqDeserializer::<_, Error>::new(vec![1., 2., 3.].into_iter());
+    assert!(FArrayRC::<f64, 3, 2, 6>::deserialize(de).is_err());
+}
+
+//fi check_eigen
+/// Check an eigen-decomposition of a symmetric matrix
+fn check_eigen<const D2: usize, const D: usize>(m: &[f64; D2], l: &[f64; D], e: &[f64; D2]) {
+    let et = matrix::transpose::<f64, D2, D, D>(*e);
+    let ete = matrix::multiply::<f64, D2, D2, D2, D, D, D>(&et, e);
+    assert_near(&ete, &matrix::identity::<f64, D2, D>());
+    let mut d = [0.; D2];
+    for i in 0..D {
+        d[i * (D + 1)] = l[i];
+        if i > 0 {
+            assert!(l[i] <= l[i - 1], "Eigenvalues must be decreasing");
+        }
+    }
+    let ed = matrix::multiply::<f64, D2, D2, D2, D, D, D>(e, &d);
+    assert_near(&matrix::multiply::<f64, D2, D2, D2, D, D, D>(&ed, &et), m);
+}
+
+//fi random_symmetric
+fn random_symmetric<const D2: usize, const D: usize>(n: usize) -> [f64; D2] {
+    let m = random_matrix::<D2, D>(n, 0.);
+    let mt = matrix::transpose::<f64, D2, D, D>(m);
+    matrix::add(m, &mt, 1.)
+}
+
+//ft test_symmetric_eigen
+#[test]
+fn test_symmetric_eigen() {
+    for n in 0..20 {
+        let m = random_symmetric::<4, 2>(n);
+        let (l, e) = matrix::symmetric_eigen2(&m);
+        check_eigen(&m, &l, &e);
+        let m = random_symmetric::<9, 3>(n);
+        let (l, e) = matrix::symmetric_eigen3(&m);
+        check_eigen(&m, &l, &e);
+        let m = random_symmetric::<16, 4>(n);
+        let (l, e) = matrix::symmetric_eigen4(&m);
+        check_eigen(&m, &l, &e);
+        let m = random_symmetric::<36, 6>(n);
+        let (l, e) = matrix::symmetric_eigen::<f64, 36, 6>(&m);
+        check_eigen(&m, &l, &e);
+    }
+
+    // Repeated eigenvalues, and an already-diagonal matrix
+    let m = [2., 0., 0., 0., 5., 0., 0., 0., 2.];
+    let (l, e) = matrix::symmetric_eigen3(&m);
+    assert_eq!(l, [5., 2., 2.]);
+    check_eigen(&m, &l, &e);
+    let m = [1., 1., 1., 1., 1., 1., 1., 1., 1.];
+    let (l, e) = matrix::symmetric_eigen3(&m);
+    assert_near(&l, &[3., 0., 0.]);
+    check_eigen(&m, &l, &e);
+
+    // Through the SqMatrix trait
+    let m = FArray2::<f64, 3, 9>::from_array([4., 1., 2., 1., 3., 0., 2., 0., 5.]);
+    let (l, e) = m.symmetric_eigen();
+    for i in 0..3 {
+        let ei =
+            FArray::<f64, 3>::from_array(Matrix::column(&FArrayRC::<f64, 3, 3, 9>::from(e), i));
+        assert_near(m.transform(&ei).as_ref(), (ei * l[i]).as_ref());
+    }
+}
+
+//fi check_svd
+/// Check U.diag(s).V' = M, and that U and V have orthonormal columns
+fn check_svd<const RC: usize, const CC: usize, const R: usize, const C: usize>(
+    m: &[f64; RC],
+    u: &[f64; RC],
+    s: &[f64; C],
+    v: &[f64; CC],
+) {
+    let ut = matrix::transpose::<f64, RC, R, C>(*u);
+    let vt = matrix::transpose::<f64, CC, C, C>(*v);
+    let i = matrix::identity::<f64, CC, C>();
+    assert_near(&matrix::multiply::<f64, RC, RC, CC, C, R, C>(&ut, u), &i);
+    assert_near(&matrix::multiply::<f64, CC, CC, CC, C, C, C>(&vt, v), &i);
+    for i in 0..C {
+        assert!(s[i] >= 0.);
+        if i > 0 {
+            assert!(s[i] <= s[i - 1], "Singular values must be decreasing");
+        }
+    }
+    let mut d = [0.; CC];
+    for i in 0..C {
+        d[i * (C + 1)] = s[i];
+    }
+    let ud = matrix::multiply::<f64, RC, CC, RC, R, C, C>(u, &d);
+    assert_near(&matrix::multiply::<f64, RC, CC, RC, R, C, C>(&ud, &vt), m);
+}
+
+//ft test_svd
+#[test]
+fn test_svd() {
+    for n in 0..20 {
+        let m = random_matrix::<9, 3>(n, 0.);
+        let (u, s, v) = matrix::svd3(&m);
+        check_svd::<9, 9, 3, 3>(&m, &u, &s, &v);
+        let m = random_matrix::<16, 4>(n, 0.);
+        let (u, s, v) = matrix::svd::<f64, 16, 16, 4, 4>(&m);
+        check_svd::<16, 16, 4, 4>(&m, &u, &s, &v);
+        let m: [f64; 12] = values(12 * (n + 1))[12 * n..].try_into().unwrap();
+        let (u, s, v) = matrix::svd::<f64, 12, 9, 4, 3>(&m);
+        check_svd::<12, 9, 4, 3>(&m, &u, &s, &v);
+    }
+
+    // Rank-deficient and zero matrices still have orthonormal U and V
+    for m in [
+        [1., 3., 2., 0., 2., 3., 1., 5., 5.],
+        [1., 2., 3., 2., 4., 6., -1., -2., -3.],
+        [0.; 9],
+    ] {
+        let (u, s, v) = matrix::svd3(&m);
+        check_svd::<9, 9, 3, 3>(&m, &u, &s, &v);
+        assert!(s[2].abs() < 1E-12);
+    }
+
+    // Through the SqMatrix3 trait
+    let m = FArray2::<f64, 3, 9>::from_array([1., 3., 2., 0., 2., 3., -1., 2., 3.]);
+    let (u, s, v) = m.svd();
+    check_svd::<9, 9, 3, 3>(m.as_ref(), u.as_ref(), s.as_ref(), v.as_ref());
+    assert_near(
+        &[s[0] * s[1] * s[2]],
+        &[(u.determinant() * m.determinant() * v.determinant()).abs()],
+    );
+}
+
+//ft test_pseudo_inverse
+#[test]
+fn test_pseudo_inverse() {
+    for n in 0..10 {
+        // An invertible matrix has its inverse as its pseudo-inverse
+        let m = random_matrix::<16, 4>(n, 1.);
+        assert_near(
+            &matrix::pseudo_inverse::<f64, 16, 4, 4>(&m, 1E-12),
+            &matrix::inverse4(&m),
+        );
+
+        // Tall and wide matrices satisfy the Moore-Penrose conditions
+        let a: [f64; 6] = values(6 * (n + 1))[6 * n..].try_into().unwrap();
+        let p = matrix::pseudo_inverse::<f64, 6, 2, 3>(&a, 1E-12);
+        let ap = matrix::multiply::<f64, 6, 6, 4, 2, 3, 2>(&a, &p);
+        let pa = matrix::multiply::<f64, 6, 6, 9, 3, 2, 3>(&p, &a);
+        assert_near(&matrix::multiply::<f64, 4, 6, 6, 2, 2, 3>(&ap, &a), &a);
+        assert_near(&matrix::multiply::<f64, 9, 6, 6, 3, 3, 2>(&pa, &p), &p);
+        assert_near(&ap, &matrix::transpose::<f64, 4, 2, 2>(ap));
+        assert_near(&pa, &matrix::transpose::<f64, 9, 3, 3>(pa));
+
+        let at = matrix::transpose::<f64, 6, 2, 3>(a);
+        let pt = matrix::pseudo_inverse::<f64, 6, 3, 2>(&at, 1E-12);
+        assert_near(&pt, &matrix::transpose::<f64, 6, 3, 2>(p));
+    }
+
+    // A singular matrix
+    let m = [1., 2., 2., 4.];
+    let p = matrix::pseudo_inverse::<f64, 4, 2, 2>(&m, 1E-12);
+    assert_near(&p, &[0.04, 0.08, 0.08, 0.16]);
+}
+
+//ft test_nearest_rotation3
+#[test]
+fn test_nearest_rotation3() {
+    let axis: FArray<f64, 3> = [1., 2., 3.].into();
+    let q = QArray::<f64, FArray<f64, 3>, FArray<f64, 4>>::of_axis_angle(&axis.normalize(), 0.7);
+    let mut r = FArray2::<f64, 3, 9>::default();
+    q.set_rotation3(&mut r);
+    assert_near(&matrix::nearest_rotation3(r.as_ref()), r.as_ref());
+
+    for n in 0..10 {
+        let noise = random_matrix::<9, 3>(n, 0.);
+        let drifted = matrix::add(*r.as_ref(), &noise, 0.01);
+        let n_r = matrix::nearest_rotation3(&drifted);
+        let n_rt = matrix::transpose::<f64, 9, 3, 3>(n_r);
+        assert_near(&matrix::multiply3(&n_r, &n_rt), &matrix::identity3());
+        assert_near(&[matrix::determinant3(&n_r)], &[1.]);
+        let d = matrix::sub(n_r, r.as_ref(), 1.);
+        assert!(matrix::absmax(&d) < 0.05);
+
+        // A reflection is made a rotation
+        let reflected = matrix::scale(drifted, -1.);
+        assert_near(
+            &[matrix::determinant3(&matrix::nearest_rotation3(&reflected))],
+            &[1.],
+        );
+    }
+}
+
+//ft test_fit_points
+#[test]
+fn test_fit_points() {
+    let v = values(64);
+    let src: Vec<FArray<f64, 3>> = v[..30]
+        .chunks(3)
+        .map(|p| FArray::from_array([p[0] * 4., p[1] * 3., p[2] * 2.]))
+        .collect();
+    let axes = [[0., 0., 1.], [1., -1., 0.], [v[40], v[41], v[42]]];
+    let angles = [0.3, std::f64::consts::PI, -2.5];
+    for (axis, angle) in axes.iter().zip(angles.iter()) {
+        let axis = FArray::from_array(*axis).normalize();
+        let q = QArray::of_axis_angle(&axis, *angle);
+        let t = FArray::from_array([v[50] * 10., v[51] * 10., v[52] * 10.]);
+        let dst: Vec<FArray<f64, 3>> = src.iter().map(|p| q.apply3(p) * 1.5 + t).collect();
+
+        // Similarity transform
+        let (fit, rms) = FQArrayTrans::fit_points(&src, &dst, None, true).unwrap();
+        assert!(rms < 1E-10, "{rms}");
+        assert!((fit.scale() - 1.5).abs() < 1E-10);
+        assert_near(fit.translation().as_ref(), t.as_ref());
+        assert!(
+            fit.rotation()
+                .distance_sq(&q)
+                .min((fit.rotation() + q).length_sq())
+                < 1E-16
+        );
+
+        // Rigid transform of the same points
+        let dst: Vec<FArray<f64, 3>> = src.iter().map(|p| q.apply3(p) + t).collect();
+        let (fit, rms) = FQArrayTrans::fit_points(&src, &dst, None, false).unwrap();
+        assert!(rms < 1E-10, "{rms}");
+        assert_eq!(fit.scale(), 1.);
+        assert_near(fit.translation().as_ref(), t.as_ref());
+
+        // A zero-weighted outlier is ignored
+        let mut dst = dst;
+        dst[3] += FArray::from_array([5., 0., 0.]);
+        let mut weights = vec![1.; src.len()];
+        weights[3] = 0.;
+        let (fit, rms) = FQArrayTrans::fit_points(&src, &dst, Some(&weights), false).unwrap();
+        assert!(rms < 1E-10, "{rms}");
+        assert_near(fit.translation().as_ref(), t.as_ref());
+        let (_, rms) = FQArrayTrans::fit_points(&src, &dst, None, false).unwrap();
+        assert!(rms > 1.);
+    }
+
+    // A reflection is fitted with a proper rotation, leaving a residual
+    let dst: Vec<FArray<f64, 3>> = src
+        .iter()
+        .map(|p| FArray::from_array([p[0], p[1], -p[2]]))
+        .collect();
+    let (fit, rms) = FQArrayTrans::fit_points(&src, &dst, None, false).unwrap();
+    assert!(rms > 0.1);
+    assert!((fit.rotation().length_sq() - 1.).abs() < 1E-12);
+
+    // Collinear points still produce a fit
+    let line: Vec<FArray<f64, 3>> = (0..5)
+        .map(|i| FArray::from_array([i as f64, 0., 0.]))
+        .collect();
+    let q: QArray<f64, FArray<f64, 3>, FArray<f64, 4>> =
+        QArray::of_axis_angle(&FArray::from_array([0., 1., 0.]), 0.7);
+    let dst: Vec<FArray<f64, 3>> = line.iter().map(|p| q.apply3(p) * 2.).collect();
+    let (fit, rms) = FQArrayTrans::fit_points(&line, &dst, None, true).unwrap();
+    assert!(rms < 1E-10, "{rms}");
+    assert!((fit.scale() - 2.).abs() < 1E-10);
+
+    // Degenerate inputs
+    assert!(FQArrayTrans::<f64>::fit_points(&[], &[], None, false).is_none());
+    let points = [FArray::from_array([1., 2., 3.]); 3];
+    assert!(FQArrayTrans::fit_points(&points, &points, None, true).is_none());
+    let (fit, _) = FQArrayTrans::fit_points(&points, &points, None, false).unwrap();
+    assert_eq!(fit.scale(), 1.);
+}
+
+//ft test_euler
+#[test]
+fn test_euler() {
+    use geo_nd::{quat, EulerAxes, EulerOrder};
+    use std::f64::consts::{FRAC_PI_2, PI};
+    let all_axes = [
+        EulerAxes::Xyz,
+        EulerAxes::Xzy,
+        EulerAxes::Yxz,
+        EulerAxes::Yzx,
+        EulerAxes::Zxy,
+        EulerAxes::Zyx,
+        EulerAxes::Xyx,
+        EulerAxes::Xzx,
+        EulerAxes::Yxy,
+        EulerAxes::Yzy,
+        EulerAxes::Zxz,
+        EulerAxes::Zyz,
+    ];
+    let axis_rotation = |axis: usize, angle: f64| {
+        let mut v = [0.; 3];
+        v[axis] = 1.;
+        let mut m = [0.; 9];
+        quat::to_rotation3(&quat::of_axis_angle(&v, angle), &mut m);
+        m
+    };
+    let v = values(30);
+    for axes in all_axes {
+        let [i, j, k] = axes.indices();
+        for n in 0..10 {
+            let mut angles = [v[n * 3] * 3., v[n * 3 + 1] * 1.5, v[n * 3 + 2] * 3.];
+            if axes.is_proper() {
+                angles[1] = angles[1].abs() + 0.05;
+            }
+
+            // Intrinsic rotations are applied about the rotated axes
+            let order = EulerOrder::intrinsic(axes);
+            let m = matrix::of_euler3(order, &angles);
+            let expected = matrix::multiply3(
+                &matrix::multiply3(&axis_rotation(i, angles[0]), &axis_rotation(j, angles[1])),
+                &axis_rotation(k, angles[2]),
+            );
+            assert_near(&m, &expected);
+            assert_near(&matrix::to_euler3(order, &m), &angles);
+            let q = quat::of_euler(order, &angles);
+            assert_near(&quat::to_euler_ordered(order, &q), &angles);
+            let mut mq = [0.; 9];
+            quat::to_rotation3(&q, &mut mq);
+            assert_near(&mq, &m);
+
+            // Extrinsic rotations are applied about the fixed axes
+            let order = EulerOrder::extrinsic(axes);
+            let m = matrix::of_euler3(order, &angles);
+            let expected = matrix::multiply3(
+                &matrix::multiply3(&axis_rotation(k, angles[2]), &axis_rotation(j, angles[1])),
+                &axis_rotation(i, angles[0]),
+            );
+            assert_near(&m, &expected);
+            assert_near(&matrix::to_euler3(order, &m), &angles);
+            let q = quat::of_euler(order, &angles);
+            assert_near(&quat::to_euler_ordered(order, &q), &angles);
+            let reversed = [angles[2], angles[1], angles[0]];
+            assert_near(&matrix::of_euler3(order.reversed(), &reversed), &m);
+        }
+
+        // In gimbal lock the rotation is preserved with the last angle zero
+        let order = EulerOrder::intrinsic(axes);
+        let middles = if axes.is_proper() {
+            [0., PI]
+        } else {
+            [FRAC_PI_2, -FRAC_PI_2]
+        };
+        for middle in middles {
+            let m = matrix::of_euler3(order, &[0.3, middle, 0.4]);
+            let angles = matrix::to_euler3(order, &m);
+            assert!(angles[2].abs() < 1E-12);
+            assert_near(&matrix::of_euler3(order, &angles), &m);
+        }
+    }
+}
+
+//ft test_decompose
+#[test]
+fn test_decompose() {
+    type Quat = QArray<f64, FArray<f64, 3>, FArray<f64, 4>>;
+    type Decomposition = MatrixDecomposition<FArray<f64, 3>, Quat, FArray<f64, 4>>;
+    let vs = values(16 * 8);
+    for v in vs.chunks(16) {
+        let rotation = Quat::of_rijk(v[0], v[1], v[2], v[3]).normalize();
+        let translation = FArray::from_array([v[4], v[5], v[6]]) * 4.;
+        let sign = if v[7] < 0. { -1. } else { 1. };
+        let scale = FArray::from_array([(1.5 + v[8]) * sign, 1.5 + v[9], 1.5 + v[10]]);
+        let shear = FArray::from_array([v[11], v[12], v[13]]) * 0.5;
+        let perspective =
+            FArray::from_array([v[14] * 0.2, v[15] * 0.2, v[0] * 0.2, 1. + v[1] * 0.1]);
+        let d = Decomposition {
+            translation,
+            rotation,
+            scale,
+            shear,
+            perspective,
+        };
+
+        // Compose matches the product of the separate matrices
+        let m = FArray2::<f64, 4, 16>::compose(&d);
+        let mut p = FArray2::<f64, 4, 16>::identity();
+        for c in 0..4 {
+            p[12 + c] = perspective[c];
+        }
+        let mut t = FArray2::<f64, 4, 16>::identity();
+        t.translate3(&translation);
+        let mut r = FArray2::<f64, 4, 16>::default();
+        rotation.set_rotation4(&mut r);
+        let mut s = FArray2::<f64, 4, 16>::identity();
+        s[0] = scale[0];
+        s[5] = scale[1];
+        s[10] = scale[2];
+        let mut h = FArray2::<f64, 4, 16>::identity();
+        h[1] = shear[0];
+        h[2] = shear[1];
+        h[6] = shear[2];
+        assert_near(m.as_ref(), (p * t * r * s * h).as_ref());
+
+        // Decompose recovers the parts, with any reflection in the X
+        // scaling, and hence round-trips
+        let d2: Decomposition = m.decompose().unwrap();
+        assert_near(d2.translation.as_ref(), translation.as_ref());
+        assert_near(d2.scale.as_ref(), scale.as_ref());
+        assert_near(d2.shear.as_ref(), shear.as_ref());
+        assert_near(d2.perspective.as_ref(), perspective.as_ref());
+        let q_err = d2
+            .rotation
+            .distance(&rotation)
+            .min(d2.rotation.distance(&-rotation));
+        assert!(q_err < 1E-8, "{} != {}", d2.rotation, rotation);
+        assert_near(FArray2::<f64, 4, 16>::compose(&d2).as_ref(), m.as_ref());
+
+        // The perspective row is accurate at small scales
+        let small = Decomposition {
+            scale: scale * 1E-6,
+            ..d
+        };
+        let m = FArray2::<f64, 4, 16>::compose(&small);
+        let d2: Decomposition = m.decompose().unwrap();
+        assert_near(d2.perspective.as_ref(), perspective.as_ref());
+        assert_near(FArray2::<f64, 4, 16>::compose(&d2).as_ref(), m.as_ref());
+
+        // An arbitrary matrix with a nonsingular 3-by-3 part round-trips
+        let a: [f64; 16] = v.try_into().unwrap();
+        let mut a = a;
+        for i in 0..3 {
+            a[i * 5] += 2.;
+        }
+        let d3 = matrix::decompose4(&a).unwrap();
+        assert_near(&matrix::compose4(&d3), &a);
+    }
+
+    // Affine matrices have a perspective row of (0, 0, 0, 1)
+    let trans = FQArrayTrans::of_trs(
+        FArray::from_array([1., 2., 3.]),
+        Quat::of_axis_angle(&FArray::from_array([0., 0., 1.]), 0.5),
+        2.,
+    );
+    let d = matrix::decompose4(trans.as_mat().as_ref()).unwrap();
+    assert_near(&d.perspective, &[0., 0., 0., 1.]);
+    assert_near(&d.scale, &[2., 2., 2.]);
+    assert_near(&d.shear, &[0., 0., 0.]);
+    assert_near(&d.rotation, trans.rotation().as_ref());
+
+    // Including those with a small scale, whose affine part has a tiny
+    // determinant
+    let small = (trans * 1E-3).as_mat().into_array().map(|x| x as f32);
+    let d = matrix::decompose4(&small).unwrap();
+    assert_eq!(d.perspective, [0., 0., 0., 1.]);
+    let m = matrix::compose4(&d);
+    for i in 0..16 {
+        assert!((m[i] - small[i]).abs() < 1E-6, "{m:?} != {small:?}");
+    }
+
+    // Singular matrices cannot be decomposed
+    let mut singular = trans.as_mat().into_array();
+    for c in 0..4 {
+        singular[8 + c] = singular[c] + singular[4 + c];
+    }
+    assert!(matrix::decompose4(&singular).is_none());
+    assert!(matrix::decompose4(&[0.; 16]).is_none());
+}
+
+//fi project_point
+/// Apply a projection matrix to a point and divide by w
+fn project_point(m: &[f64; 16], p: &[f64; 3]) -> [f64; 3] {
+    let c = matrix::transform_vec4(m, &[p[0], p[1], p[2], 1.]);
+    [c[0] / c[3], c[1] / c[3], c[2] / c[3]]
+}
+
+//ft test_projection
+#[test]
+fn test_projection() {
+    type Mat4 = FArray2<f64, 4, 16>;
+    let spaces = [
+        ClipSpace::opengl(),
+        ClipSpace::vulkan(),
+        ClipSpace::direct3d(),
+        ClipSpace::vulkan().with_depth(DepthRange::OneToZero),
+        ClipSpace::opengl().with_handedness(Handedness::Left),
+    ];
+    let (l, r, b, t, n, f) = (-0.3, 0.5, -0.2, 0.4, 0.5, 20.);
+    for clip in spaces {
+        let (z_near, z_far): (f64, f64) = clip.depth.near_far();
+        let forward: f64 = clip.handedness.forward();
+
+        // The corners of the frustum map to the corners of the clip space
+        let m = matrix::frustum4(clip, l, r, b, t, n, f);
+        let k = f / n;
+        for (x, y, nx, ny) in [
+            (l, b, -1., -1.),
+            (r, b, 1., -1.),
+            (l, t, -1., 1.),
+            (r, t, 1., 1.),
+        ] {
+            let p = project_point(&m, &[x, y, n * forward]);
+            assert_near(&p, &[nx, ny, z_near]);
+            let p = project_point(&m, &[x * k, y * k, f * forward]);
+            assert_near(&p, &[nx, ny, z_far]);
+        }
+        assert_near(
+            Mat4::frustum(clip, l, r, b, t, n, f).as_ref(),
+            &matrix::frustum4(clip, l, r, b, t, n, f),
+        );
+
+        // The corners of the box map to the corners of the clip space
+        let m = matrix::orthographic4(clip, l, r, b, t, n, f);
+        for (x, y, nx, ny) in [(l, b, -1., -1.), (r, t, 1., 1.)] {
+            assert_near(&project_point(&m, &[x, y, n * forward]), &[nx, ny, z_near]);
+            assert_near(&project_point(&m, &[x, y, f * forward]), &[nx, ny, z_far]);
+        }
+        assert_near(
+            Mat4::orthographic(clip, l, r, b, t, n, f).as_ref(),
+            &matrix::orthographic4(clip, l, r, b, t, n, f),
+        );
+
+        // A symmetric perspective is a symmetric frustum
+        let (fov, aspect) = (1.2_f64, 1.6);
+        let top = n * (fov / 2.).tan();
+        let m = matrix::perspective_fov4(clip, fov, aspect, n, f);
+        assert_near(
+            &m,
+            &matrix::frustum4(clip, -top * aspect, top * aspect, -top, top, n, f),
+        );
+        assert_near(Mat4::perspective_fov(clip, fov, aspect, n, f).as_ref(), &m);
+
+        // An infinite perspective is the limit of a perspective
+        let m = matrix::infinite_perspective4(clip, fov, aspect, n);
+        assert_near(&m, &matrix::perspective_fov4(clip, fov, aspect, n, 1E12));
+        assert_near(
+            &project_point(&m, &[0., top, n * forward]),
+            &[0., 1., z_near],
+        );
+        let p = project_point(&m, &[0., 0., 1E12 * forward]);
+        assert!((p[2] - z_far).abs() < 1E-9);
+        assert_near(
+            Mat4::infinite_perspective(clip, fov, aspect, n).as_ref(),
+            &m,
+        );
+    }
+
+    // The OpenGL perspective matches gluPerspective, and the original
+    // perspective is its transpose
+    let m = matrix::perspective_fov4(ClipSpace::opengl(), 1.2_f64, 1.6, 0.5, 20.);
+    let legacy = matrix::perspective4(1.2_f64, 1.6, 0.5, 20.);
+    assert_near(&matrix::transpose::<f64, 16, 4, 4>(legacy), &m);
+    assert_near(Mat4::perspective(1.2, 1.6, 0.5, 20.).as_ref(), &legacy);
+    let f = 1. / 0.6_f64.tan();
+    assert_near(
+        &m,
+        &[
+            f / 1.6,
+            0.,
+            0.,
+            0., //
+            0.,
+            f,
+            0.,
+            0., //
+            0.,
+            0.,
+            -20.5 / 19.5,
+            -20. / 19.5, //
+            0.,
+            0.,
+            -1.,
+            0.,
+        ],
+    );
+}
+
+//fi check_pick_ray
+/// Check project, unproject and pick_ray on the types of a [Geometry3D]
+fn check_pick_ray<G: Geometry3D<f32>>(clip: ClipSpace) {
+    type Mat4<G> = <G as Geometry3D<f32>>::Mat4;
+    type Vec3<G> = <G as Geometry3D<f32>>::Vec3;
+    type Vec4<G> = <G as Geometry3D<f32>>::Vec4;
+    let forward: f32 = clip.handedness.forward();
+    let proj = Mat4::<G>::perspective_fov(clip, 1.0, 1.5, 0.5, 50.);
+    let view = Mat4::<G>::from_array(
+        FQArrayTrans::<f32>::of_trs(
+            FArray::from_array([0.3, -0.5, 3. * forward]),
+            QArray::of_axis_angle(&FArray::from_array([0.6, 0.8, 0.]), 0.4),
+            1.,
+        )
+        .as_mat()
+        .into_array(),
+    );
+    let view_proj = proj * view;
+    let viewport = Vec4::<G>::from_array([10., 20., 600., 400.]);
+    let obj = Vec3::<G>::from_array([0.5, -1.0, 10. * forward]);
+
+    let win = view_proj.project(&obj, &viewport, clip.depth).unwrap();
+    assert!(
+        win[0] > 10. && win[0] < 610. && win[1] > 20. && win[1] < 420.,
+        "{win:?}"
+    );
+    assert!(win[2] > 0. && win[2] < 1.);
+    let back = view_proj.unproject(&win, &viewport, clip.depth).unwrap();
+    assert!(back.distance(&obj) < 1E-3, "{back:?} != {obj:?}");
+
+    // The pick ray through the window point passes through the object
+    let x = (win[0] - 10.) / 300. - 1.;
+    let y = (win[1] - 20.) / 200. - 1.;
+    let (origin, direction) = view_proj.inverse().pick_ray(x, y, clip.depth);
+    assert!((direction.length() - 1.).abs() < 1E-5);
+    let t = (obj - origin).dot(&direction);
+    assert!(t > 0.);
+    assert!((origin + direction * t).distance(&obj) < 1E-3);
+    let win_near = match clip.depth {
+        DepthRange::OneToZero => 1.,
+        _ => 0.,
+    };
+    let near = Vec3::<G>::from_array([win[0], win[1], win_near]);
+    let near = view_proj.unproject(&near, &viewport, clip.depth);
+    assert!(near.unwrap().distance(&origin) < 1E-3);
+}
+
+//ft test_project
+#[test]
+fn test_project() {
+    let viewport = [0., 0., 640., 480.];
+    for clip in [
+        ClipSpace::opengl(),
+        ClipSpace::vulkan(),
+        ClipSpace::direct3d(),
+        ClipSpace::vulkan().with_depth(DepthRange::OneToZero),
+    ] {
+        let forward: f64 = clip.handedness.forward();
+        let proj = matrix::perspective_fov4(clip, 0.9, 640. / 480., 0.5, 100.);
+        let model = FQArrayTrans::<f64>::of_trs(
+            FArray::from_array([0.5, 0.2, 4. * forward]),
+            QArray::of_axis_angle(&FArray::from_array([0., 1., 0.]), 0.3),
+            2.,
+        )
+        .as_mat()
+        .into_array();
+
+        // The window depth is 0 at the near plane and 1 at the far plane
+        let (z_near, z_far) = match clip.depth {
+            DepthRange::OneToZero => (1., 0.),
+            _ => (0., 1.),
+        };
+        let eye = matrix::identity4();
+        let p = matrix::project(&[0., 0., 0.5 * forward], &eye, &proj, &viewport, clip.depth);
+        assert_near(&p.unwrap(), &[320., 240., z_near]);
+        let p = matrix::project(
+            &[0., 0., 100. * forward],
+            &eye,
+            &proj,
+            &viewport,
+            clip.depth,
+        );
+        assert_near(&p.unwrap(), &[320., 240., z_far]);
+
+        // A point on the plane of the viewer has no window coordinates
+        let p = matrix::project(&[1., 2., 0.], &eye, &proj, &viewport, clip.depth);
+        assert!(p.is_none());
+
+        // Project and unproject are inverses
+        for v in values(30).chunks(3) {
+            let obj = [v[0], v[1], v[2]];
+            let win = matrix::project(&obj, &model, &proj, &viewport, clip.depth).unwrap();
+            let back = matrix::unproject(&win, &model, &proj, &viewport, clip.depth).unwrap();
+            assert_near(&back, &obj);
+        }
+    }
+
+    // A pick ray of an infinite reversed-Z perspective
+    let clip = ClipSpace::vulkan().with_depth(DepthRange::OneToZero);
+    let proj = matrix::infinite_perspective4(clip, 0.9, 1., 0.5);
+    let (origin, direction) = matrix::pick_ray(0., 0., &matrix::inverse4(&proj), clip.depth);
+    assert_near(&origin, &[0., 0., -0.5]);
+    assert_near(&direction, &[0., 0., -1.]);
+    let win = [0., 0., 0.];
+    assert!(matrix::unproject(
+        &win,
+        &matrix::identity4(),
+        &proj,
+        &[0., 0., 1., 1.],
+        clip.depth
+    )
+    .is_none());
+
+    // The trait methods on the types of a Geometry3D
+    check_pick_ray::<f32>(ClipSpace::opengl());
+    check_pick_ray::<f32>(ClipSpace::direct3d().with_depth(DepthRange::OneToZero));
+}
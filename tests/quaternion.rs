@@ -2,6 +2,9 @@
 use geo_nd::{quat, vector};
 use geo_nd::{FArray, Float, Geometry3D, QArray, Quaternion, SqMatrix, Vector};
 
+mod common;
+use common::values;
+
 //a Test type
 //ti Banana
 // This type is required so that implementations of the associated types of V (e.g. Vec2/3/4) can be used within
@@ -31,9 +34,7 @@ fn vec3_eq(v: &[f32; 3], v2: &[f32; 3]) -> bool {
 fn quat_eq(q: &Quat, q2: &Quat) -> bool {
     let d_sub = *q2 - *q;
     let d_add = *q2 + *q;
-    if d_sub.length_sq() < f32::frac(1, 1000) {
-        true
-    } else if d_add.length_sq() < f32::frac(1, 1000) {
+    if d_sub.length_sq() < f32::frac(1, 1000) || d_add.length_sq() < f32::frac(1, 1000) {
         true
     } else {
         dbg!(q, q2, d_sub, d_sub.length_sq(), d_add, d_add.length_sq());
@@ -348,3 +349,175 @@ fn test_look_at() {
     let t = q.apply3(&z);
     assert!(vec3_eq(t.as_ref(), &[-1., 0., 0.]));
 }
+#[test]
+fn test_weighted_average_markley() {
+    let axis = Vec3::from_array([1., 2., 3.]).normalize();
+    let qs: Vec<(f32, Quat)> = [(1., 0.1), (2., 0.3), (0.5, -0.2), (1.5, 0.25)]
+        .iter()
+        .map(|(w, a)| (*w, Quat::of_axis_angle(&axis, *a)))
+        .collect();
+
+    // Small rotations about a single axis average to (nearly) the weighted average angle
+    let av = Quat::weighted_average_markley(qs.iter().copied()).unwrap();
+    let angle = (0.1 + 0.6 - 0.1 + 0.375) / 5.;
+    assert!(quat_eq(&av, &Quat::of_axis_angle(&axis, angle)));
+
+    // The result does not depend on order or the signs of the quaternions
+    let rev = qs.iter().rev().map(|(w, q)| (*w, -*q));
+    let av_rev = Quat::weighted_average_markley(rev).unwrap();
+    assert!((av - av_rev).length_sq() < 1E-10);
+    assert!(av.as_rijk().0 >= 0.);
+
+    // For two quaternions it is the same as the pair average
+    let a = Quat::of_axis_angle(&axis, 0.3);
+    let b = Quat::of_axis_angle(&Vec3::from_array([0., 1., 0.]), 1.2);
+    let pair = a.weighted_average_pair(0.25, &b, 0.75);
+    let markley = Quat::weighted_average_markley([(1., a), (3., b)].into_iter()).unwrap();
+    assert!(quat_eq(&pair, &markley));
+
+    assert!(Quat::weighted_average_markley(std::iter::empty()).is_none());
+    assert!(Quat::weighted_average_markley([(0., a)].into_iter()).is_none());
+    let q = quat::weighted_average_markley([(2., *b.as_ref())].into_iter()).unwrap();
+    assert!(quat_eq(&Quat::from_array(q), &b));
+}
+#[test]
+fn test_slerp() {
+    let axis = Vec3::from_array([1., 2., 3.]).normalize();
+    let q0 = Quat::of_axis_angle(&axis, 0.2);
+    let q1 = Quat::of_axis_angle(&axis, 1.4);
+
+    // Constant angular velocity, with the ends exact
+    assert!(quat_eq(&q0.slerp(&q1, 0.), &q0));
+    assert!(quat_eq(&q0.slerp(&q1, 1.), &q1));
+    for t in [0.1, 0.25, 0.5, 0.9] {
+        let q = q0.slerp(&q1, t);
+        assert!((q - Quat::of_axis_angle(&axis, 0.2 + 1.2 * t)).length_sq() < 1E-10);
+    }
+
+    // The shorter path is taken
+    let q = q0.slerp(&(-q1), 0.5);
+    assert!(quat_eq(&q, &Quat::of_axis_angle(&axis, 0.8)));
+    let q = Quat::of_axis_angle(&axis, 0.).slerp(&Quat::of_axis_angle(&axis, 5.), 0.5);
+    assert!(quat_eq(&q, &Quat::of_axis_angle(&axis, -0.6)));
+
+    // Nearly identical quaternions still give unit quaternions
+    let q2 = Quat::of_axis_angle(&axis, 0.2001);
+    let q = q0.slerp(&q2, 0.3);
+    assert!((q.length() - 1.).abs() < 1E-6);
+    assert!(quat_eq(&q, &q0));
+
+    // The free function matches the method
+    let q = quat::slerp(0.3, q0.as_ref(), q1.as_ref());
+    assert!(quat_eq(&Quat::from_array(q), &q0.slerp(&q1, 0.3)));
+}
+#[test]
+fn test_squad() {
+    let x = Vec3::from_array([1., 0., 0.]);
+    let y = Vec3::from_array([0., 1., 0.]);
+    let keys = [
+        Quat::of_rijk(1., 0., 0., 0.),
+        Quat::of_axis_angle(&x, 0.8),
+        -(Quat::of_axis_angle(&x, 0.8) * Quat::of_axis_angle(&y, 0.6)),
+        Quat::of_axis_angle(&y, 1.0),
+    ];
+    let controls: Vec<Quat> = (0..4)
+        .map(|i| Quat::squad_control(&keys[i.max(1) - 1], &keys[i], &keys[(i + 1).min(3)]))
+        .collect();
+    let spline = |i: usize, t: f32| keys[i].squad(&keys[i + 1], &controls[i], &controls[i + 1], t);
+
+    // The spline passes through the keyframes, and is unit length
+    for i in 0..3 {
+        assert!(quat_eq(&spline(i, 0.), &keys[i]));
+        assert!(quat_eq(&spline(i, 1.), &keys[i + 1]));
+        for t in [0.2, 0.5, 0.7] {
+            assert!((spline(i, t).length() - 1.).abs() < 1E-5);
+        }
+    }
+
+    // The angular velocity is continuous across a keyframe
+    let dt = 0.002;
+    for (i, key) in keys.iter().enumerate().take(3).skip(1) {
+        let before = (spline(i - 1, 1. - dt).conjugate() * *key).as_rijk();
+        let after = (key.conjugate() * spline(i, dt)).as_rijk();
+        let sign = if before.0 * after.0 < 0. { -1. } else { 1. };
+        let w_before = Vec3::from_array([before.1, before.2, before.3]);
+        let w_after = Vec3::from_array([after.1, after.2, after.3]) * sign;
+        assert!((w_before - w_after).length() < 0.02 * w_before.length());
+    }
+}
+#[test]
+fn test_euler() {
+    use geo_nd::{EulerAxes, EulerOrder};
+    let x = Vec3::from_array([1., 0., 0.]);
+    let y = Vec3::from_array([0., 1., 0.]);
+    let z = Vec3::from_array([0., 0., 1.]);
+
+    // Aerospace yaw, pitch and roll are intrinsic rotations about Z, Y then X
+    let order = EulerOrder::intrinsic(EulerAxes::Zyx);
+    let q = Quat::of_euler(order, &[0.5, 0.2, -0.3]);
+    let expected =
+        Quat::of_axis_angle(&z, 0.5) * Quat::of_axis_angle(&y, 0.2) * Quat::of_axis_angle(&x, -0.3);
+    assert!(quat_eq(&q, &expected));
+    let angles = q.as_euler(order);
+    assert!(vec3_eq(&angles, &[0.5, 0.2, -0.3]));
+
+    // The same rotation as extrinsic rotations about X, Y then Z
+    let angles = q.as_euler(EulerOrder::extrinsic(EulerAxes::Xyz));
+    assert!(vec3_eq(&angles, &[-0.3, 0.2, 0.5]));
+
+    // A yaw of 90 degrees maps X to Y
+    let q = Quat::of_euler(order, &[std::f32::consts::FRAC_PI_2, 0., 0.]);
+    assert!(vec3_eq(q.apply3(&x).as_ref(), &[0., 1., 0.]));
+}
+#[test]
+fn test_exp_ln() {
+    fn near(a: &[f64; 4], b: &[f64; 4]) -> bool {
+        vector::distance_sq(a, b) < 1E-20
+    }
+
+    // exp(ln(q)) == q for random unit and non-unit quaternions
+    for v in values(400).chunks(4) {
+        let q = vector::normalize([v[0], v[1], v[2], v[3]]);
+        assert!(near(&quat::exp(&quat::ln(&q)), &q), "{q:?}");
+        assert!(quat::ln(&q)[3].abs() < 1E-15);
+        let q = vector::scale(q, 2.5);
+        assert!(near(&quat::exp(&quat::ln(&q)), &q), "{q:?}");
+
+        // pow is consistent with multiplication
+        let q = vector::normalize(q);
+        assert!(near(&quat::pow(&q, 2.), &quat::multiply(&q, &q)));
+        let root = quat::pow(&q, 0.5);
+        assert!(near(&quat::multiply(&root, &root), &q));
+        assert!(near(&quat::pow(&q, 0.), &quat::identity()));
+    }
+
+    // Small angles are accurate, and the special cases are handled
+    let axis = vector::normalize([1., -2., 2.]);
+    let q = quat::of_axis_angle(&axis, 2E-9);
+    let l = quat::ln(&q);
+    assert!(vector::distance_sq(&[l[0], l[1], l[2]], &vector::scale(axis, 1E-9)) < 1E-36);
+    assert!(near(&quat::exp(&l), &q));
+    assert_eq!(quat::ln(&[0., 0., 0., 1.]), [0., 0., 0., 0.]);
+    assert_eq!(quat::exp(&[0., 0., 0., 0.]), [0., 0., 0., 1.]);
+    let l = quat::ln(&[0., 0., 0., -1.]);
+    assert!(near(&l, &[std::f64::consts::PI, 0., 0., 0.]));
+    assert!(near(&quat::exp(&l), &[0., 0., 0., -1.]));
+    assert_eq!(quat::ln(&[0., 0., 0., 0.])[3], f64::NEG_INFINITY);
+}
+#[test]
+fn test_integrate_angular_velocity() {
+    let axis = Vec3::from_array([2., 3., 6.]).normalize();
+    let q0 = Quat::of_axis_angle(&Vec3::from_array([1., 0., 0.]), 0.4);
+    let omega = axis * 1.5;
+    let mut q = q0;
+    for _ in 0..100 {
+        q = q.integrate_angular_velocity(&omega, 0.01);
+    }
+    let expected = Quat::of_axis_angle(&axis, 1.5) * q0;
+    assert!((q - expected).length_sq() < 1E-10);
+    assert!((q0.ln().exp() - q0).length_sq() < 1E-10);
+    assert!(
+        (q0.pow(0.5) - Quat::of_axis_angle(&Vec3::from_array([1., 0., 0.]), 0.2)).length_sq()
+            < 1E-10
+    );
+}
@@ -0,0 +1,238 @@
+//a Imports
+use geo_nd::{Aabb, FArray, FQArrayTrans, QArray, Quaternion};
+use geo_nd::{Ray, Transform, Vector};
+
+mod common;
+use common::{assert_near, values};
+
+//a Types
+type Vec3 = FArray<f64, 3>;
+type Vec4 = FArray<f64, 4>;
+type Quat = QArray<f64, Vec3, Vec4>;
+type Box3 = Aabb<f64, Vec3, 3>;
+
+//a Useful functions
+//fi vec3
+fn vec3(x: f64, y: f64, z: f64) -> Vec3 {
+    Vec3::from_array([x, y, z])
+}
+
+//fi rays
+/// A set of random rays, from outside a box of size 4 at the origin
+/// towards a random point within a box of size 2
+fn rays(n: usize) -> Vec<Ray<f64, Vec3>> {
+    values(n * 6)
+        .chunks(6)
+        .map(|v| {
+            let origin = vec3(v[0], v[1], v[2]).normalize() * 5.;
+            let target = vec3(v[3], v[4], v[5]);
+            Ray::new(origin, target - origin)
+        })
+        .collect()
+}
+
+//fi on_boundary
+/// Return true if a point is on the boundary of a box
+fn on_boundary(b: &Box3, p: &Vec3) -> bool {
+    b.expand(1E-9).contains(p)
+        && (0..3).any(|i| (p[i] - b.min()[i]).abs() < 1E-9 || (p[i] - b.max()[i]).abs() < 1E-9)
+}
+
+//a Tests
+//ft test_ray_plane
+#[test]
+fn test_ray_plane() {
+    let ray = Ray::new(vec3(1., 2., 3.), vec3(0., 0., -2.));
+    assert_eq!(ray.at(0.5).into_array(), [1., 2., 2.]);
+    assert_eq!(format!("{ray}"), "(1,2,3) + t * (0,0,-2)");
+
+    // The plane z = 1, with a normal that is not a unit vector
+    let plane = Vec4::from_array([0., 0., 2., -2.]);
+    let hit = ray.intersect_plane(&plane).unwrap();
+    assert_eq!(hit.distance, 1.);
+    assert_eq!(hit.normal.into_array(), [0., 0., 1.]);
+
+    // The plane is behind a ray in the opposite direction, and a ray
+    // parallel to it does not hit it
+    let away = Ray::new(vec3(1., 2., 3.), vec3(0., 0., 1.));
+    assert!(away.intersect_plane(&plane).is_none());
+    let parallel = Ray::new(vec3(1., 2., 3.), vec3(1., 0., 0.));
+    assert!(parallel.intersect_plane(&plane).is_none());
+
+    let vs = values(6 * 20 + 4 * 20);
+    for (ray, v) in rays(20).iter().zip(vs[6 * 20..].chunks(4)) {
+        let plane = Vec4::from_array([v[0], v[1], v[2], v[3]]);
+        if let Some(hit) = ray.intersect_plane(&plane) {
+            let p = ray.at(hit.distance);
+            assert!(hit.distance >= 0.);
+            assert!((p[0] * v[0] + p[1] * v[1] + p[2] * v[2] + v[3]).abs() < 1E-12);
+        }
+    }
+}
+
+//ft test_ray_sphere
+#[test]
+fn test_ray_sphere() {
+    let centre = vec3(0.5, -0.5, 0.25);
+    for ray in rays(20) {
+        // Every ray passes through the sphere, entering it first
+        let hit = ray.intersect_sphere(&centre, 2.).unwrap();
+        let p = ray.at(hit.distance);
+        assert!((p.distance(&centre) - 2.).abs() < 1E-12);
+        assert_near(hit.normal.as_ref(), ((p - centre) / 2.).as_ref());
+        assert!(hit.normal.dot(&ray.direction()) < 0.);
+
+        // From the centre the ray leaves the sphere
+        let inside = Ray::new(centre, ray.direction());
+        let hit = inside.intersect_sphere(&centre, 2.).unwrap();
+        assert!((hit.distance * ray.direction().length() - 2.).abs() < 1E-12);
+        assert!(hit.normal.dot(&ray.direction()) > 0.);
+
+        // A ray pointing away from the sphere misses it
+        let away = Ray::new(ray.origin(), -ray.direction());
+        assert!(away.intersect_sphere(&centre, 2.).is_none());
+    }
+    let ray = Ray::new(vec3(0., 3., 0.), vec3(1., 0., 0.));
+    assert!(ray.intersect_sphere(&Vec3::zero(), 2.).is_none());
+    let hit = ray.intersect_sphere(&Vec3::zero(), 3.).unwrap();
+    assert_eq!(hit.distance, 0.);
+}
+
+//ft test_ray_triangle
+#[test]
+fn test_ray_triangle() {
+    let (a, b, c) = (vec3(0., 0., 0.), vec3(2., 0., 0.), vec3(0., 2., 0.));
+    let down = Ray::new(vec3(0.5, 0.25, 4.), vec3(0., 0., -2.));
+    let (hit, bary) = down.intersect_triangle(&a, &b, &c, true).unwrap();
+    assert_eq!(hit.distance, 2.);
+    assert_eq!(hit.normal.into_array(), [0., 0., 1.]);
+    assert_near(&bary, &[0.625, 0.25, 0.125]);
+
+    // From below the ray hits the back face, which may be culled
+    let up = Ray::new(vec3(0.5, 0.25, -4.), vec3(0., 0., 1.));
+    assert!(up.intersect_triangle(&a, &b, &c, true).is_none());
+    let (hit, bary) = up.intersect_triangle(&a, &b, &c, false).unwrap();
+    assert_eq!(hit.distance, 4.);
+    assert_eq!(hit.normal.into_array(), [0., 0., 1.]);
+    assert_near(&bary, &[0.625, 0.25, 0.125]);
+
+    // Rays that miss the triangle, or are parallel to it
+    let outside = Ray::new(vec3(1.5, 1.5, 4.), vec3(0., 0., -1.));
+    assert!(outside.intersect_triangle(&a, &b, &c, false).is_none());
+    let parallel = Ray::new(vec3(-1., 0.5, 0.), vec3(1., 0., 0.));
+    assert!(parallel.intersect_triangle(&a, &b, &c, false).is_none());
+    let away = Ray::new(vec3(0.5, 0.25, 4.), vec3(0., 0., 1.));
+    assert!(away.intersect_triangle(&a, &b, &c, false).is_none());
+
+    // A small triangle is hit, even in single precision, but not by a
+    // ray parallel to it
+    let small = |x: f32, y: f32, z: f32| FArray::<f32, 3>::from_array([x, y, z]);
+    let s = 2E-4_f32;
+    let (sa, sb, sc) = (small(0., 0., 0.), small(s, 0., 0.), small(0., s, 0.));
+    let down = Ray::new(small(s / 4., s / 4., 1.), small(0., 0., -1.));
+    let (hit, bary) = down.intersect_triangle(&sa, &sb, &sc, true).unwrap();
+    assert!((hit.distance - 1.).abs() < 1E-6);
+    assert!((bary[1] - 0.25).abs() < 1E-4 && (bary[2] - 0.25).abs() < 1E-4);
+    let parallel = Ray::new(small(-s, s / 4., 0.), small(1., 0., 0.));
+    assert!(parallel.intersect_triangle(&sa, &sb, &sc, false).is_none());
+
+    // Random triangles around a point on each ray, which the ray hits
+    // from one side or the other
+    let vs = values(6 * 20 + 6 * 20);
+    for (ray, v) in rays(20).iter().zip(vs[6 * 20..].chunks(6)) {
+        let t = ray.at(1.);
+        let a = t + vec3(v[0], v[1], v[2]);
+        let b = t + vec3(v[3], v[4], v[5]);
+        let c = t * 3. - a - b;
+        let (hit, bary) = ray.intersect_triangle(&a, &b, &c, false).unwrap();
+        let p = a * bary[0] + b * bary[1] + c * bary[2];
+        assert_near(ray.at(hit.distance).as_ref(), p.as_ref());
+        assert!(bary.iter().all(|w| *w >= 0.));
+        assert!(hit.normal.dot(&(b - a)).abs() < 1E-12);
+        assert!(hit.normal.dot(&(c - a)).abs() < 1E-12);
+        let front = hit.normal.dot(&ray.direction()) < 0.;
+        assert_eq!(ray.intersect_triangle(&a, &b, &c, true).is_some(), front);
+        assert_eq!(ray.intersect_triangle(&a, &c, &b, true).is_some(), !front);
+    }
+}
+
+//ft test_ray_aabb
+#[test]
+fn test_ray_aabb() {
+    let b = Box3::new(&vec3(-1., -1., -1.), &vec3(1., 1., 1.));
+    for ray in rays(20) {
+        // Every ray enters the box
+        let hit = ray.intersect_aabb(&b).unwrap();
+        let p = ray.at(hit.distance);
+        assert!(on_boundary(&b, &p));
+        assert!(hit.normal.dot(&ray.direction()) < 0.);
+        assert_eq!(hit.normal.length(), 1.);
+        assert!((p.dot(&hit.normal) - 1.).abs() < 1E-12);
+
+        // From the centre the ray leaves the box
+        let inside = Ray::new(Vec3::zero(), ray.direction());
+        let hit = inside.intersect_aabb(&b).unwrap();
+        assert!(on_boundary(&b, &inside.at(hit.distance)));
+        assert!(hit.normal.dot(&ray.direction()) > 0.);
+
+        // A ray pointing away from the box misses it
+        let away = Ray::new(ray.origin(), -ray.direction());
+        assert!(away.intersect_aabb(&b).is_none());
+    }
+
+    // Rays parallel to the faces of the box
+    let parallel = Ray::new(vec3(-3., 0.5, 0.5), vec3(1., 0., 0.));
+    let hit = parallel.intersect_aabb(&b).unwrap();
+    assert_eq!(hit.distance, 2.);
+    assert_eq!(hit.normal.into_array(), [-1., 0., 0.]);
+    let parallel = Ray::new(vec3(-3., 1.5, 0.5), vec3(1., 0., 0.));
+    assert!(parallel.intersect_aabb(&b).is_none());
+    let skew = Ray::new(vec3(-3., 0., 0.), vec3(1., 2., 0.));
+    assert!(skew.intersect_aabb(&b).is_none());
+}
+
+//ft test_ray_obb
+#[test]
+fn test_ray_obb() {
+    let b = Box3::new(&vec3(-1., -0.5, -0.25), &vec3(1., 0.5, 0.25));
+    let vs = values(6 * 20 + 8 * 20);
+    let mut hits = 0;
+    for (ray, v) in rays(20).iter().zip(vs[6 * 20..].chunks(8)) {
+        let q = Quat::of_rijk(v[0], v[1], v[2], v[3]).normalize();
+        let trans = FQArrayTrans::of_trs(vec3(v[4], v[5], v[6]) * 0.1, q, 1.5 + v[7]);
+
+        // A ray towards the centre of the box always hits it, on the
+        // same face as the ray in the space of the box
+        let centre = trans.translation();
+        let towards = Ray::new(ray.origin(), centre - ray.origin());
+        let hit = towards.intersect_obb(&b, &trans).unwrap();
+        let inverse = trans.inverse();
+        let local_ray = Ray::new(
+            inverse.transform_point(&towards.origin()),
+            inverse.transform_vector(&towards.direction()),
+        );
+        let local_hit = local_ray.intersect_aabb(&b).unwrap();
+        assert!((hit.distance - local_hit.distance).abs() < 1E-12);
+        assert_near(hit.normal.as_ref(), q.apply3(&local_hit.normal).as_ref());
+
+        // Other rays may miss the box; if they hit it then the hit is
+        // on its surface in its own space
+        let Some(hit) = ray.intersect_obb(&b, &trans) else {
+            continue;
+        };
+        hits += 1;
+        let local = inverse.transform_point(&ray.at(hit.distance));
+        assert!(on_boundary(&b, &local));
+        assert!((hit.normal.length() - 1.).abs() < 1E-12);
+        assert!(hit.normal.dot(&ray.direction()) < 0.);
+    }
+    assert!(hits > 5);
+
+    // A transformation with no scale is not inverted to the identity,
+    // so the box is not hit
+    let ray = Ray::new(vec3(0., 0., 0.), vec3(0., 0., 1.));
+    let moved = FQArrayTrans::of_trs(vec3(0., 0., 5.), Quat::unit(), 1.);
+    let flat = FQArrayTrans::of_trs(vec3(0., 0., 5.), Quat::unit(), 0.);
+    assert!((ray.intersect_obb(&b, &moved).unwrap().distance - 4.75).abs() < 1E-12);
+    assert!(ray.intersect_obb(&b, &flat).is_none());
+}
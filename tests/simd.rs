@@ -0,0 +1,202 @@
+//! Tests of the SIMD types against the equivalent FArray operations
+#![cfg(feature = "simd")]
+
+//a Imports
+use geo_nd::simd::{F32Mat3, F32Trans, F32x4Mat4, F32x4Quat, F32x4Vec2, F32x4Vec3, F32x4Vec4};
+use geo_nd::{FArray, FArray2, FQArrayTrans, QArray, Quaternion, SqMatrix, SqMatrix4};
+use geo_nd::{Geometry3D, Transform, Vector, Vector3};
+
+//a Useful functions
+//fi values
+/// A deterministic sequence of awkward values in the range -8 to 8
+fn values(n: usize) -> Vec<f32> {
+    let mut seed: u32 = 0x1234_5678;
+    (0..n)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            ((seed >> 8) as f32) / ((1 << 24) as f32) * 16.0 - 8.0
+        })
+        .collect()
+}
+
+//fi arrays
+fn arrays<const D: usize>(n: usize) -> Vec<[f32; D]> {
+    values(n * D)
+        .chunks(D)
+        .map(|c| c.try_into().unwrap())
+        .collect()
+}
+
+//fi assert_close
+fn assert_close(a: &[f32], b: &[f32]) {
+    assert_eq!(a.len(), b.len());
+    for (x, y) in a.iter().zip(b.iter()) {
+        assert!((x - y).abs() < 1E-4 * (1.0 + x.abs()), "{a:?} != {b:?}");
+    }
+}
+
+//fi test_ops
+/// Test the operations of a SIMD vector type against FArray
+fn test_ops<V: Vector<f32, D>, const D: usize>() {
+    let data = arrays::<D>(20);
+    for a in data.iter() {
+        for b in data.iter() {
+            let sa = V::from_array(*a);
+            let sb = V::from_array(*b);
+            let fa = FArray::<f32, D>::from_array(*a);
+            let fb = FArray::<f32, D>::from_array(*b);
+            assert_close((sa + sb).as_ref(), (fa + fb).as_ref());
+            assert_close((sa - sb).as_ref(), (fa - fb).as_ref());
+            assert_close((sa * sb).as_ref(), (fa * fb).as_ref());
+            assert_close((sa / sb).as_ref(), (fa / fb).as_ref());
+            assert_close((sa + b[0]).as_ref(), (fa + b[0]).as_ref());
+            assert_close((sa - b[0]).as_ref(), (fa - b[0]).as_ref());
+            assert_close((sa * b[0]).as_ref(), (fa * b[0]).as_ref());
+            assert_close((sa / b[0]).as_ref(), (fa / b[0]).as_ref());
+            assert_close((-sa).as_ref(), (-fa).as_ref());
+            assert_close(sa.mix(&sb, 0.3).as_ref(), fa.mix(&fb, 0.3).as_ref());
+            assert_close(sa.normalize().as_ref(), fa.normalize().as_ref());
+            assert_close(&[sa.dot(&sb)], &[fa.dot(&fb)]);
+            assert_close(&[sa.reduce_sum()], &[fa.reduce_sum()]);
+            assert_close(&[sa.distance(&sb)], &[fa.distance(&fb)]);
+
+            // Division must not pollute the unused lanes with NaN
+            assert_close(&[(sa / sb).dot(&sb)], &[(fa / fb).dot(&fb)]);
+            assert_close(&[(sa / b[1]).reduce_sum()], &[(fa / b[1]).reduce_sum()]);
+        }
+    }
+}
+
+//a Tests
+//ft test_vectors
+#[test]
+fn test_vectors() {
+    test_ops::<F32x4Vec2, 2>();
+    test_ops::<F32x4Vec3, 3>();
+    test_ops::<F32x4Vec4, 4>();
+    test_ops::<geo_nd::simd::F32x2Vec2, 2>();
+}
+
+//ft test_cross_product
+#[test]
+fn test_cross_product() {
+    let data = arrays::<3>(20);
+    for a in data.iter() {
+        for b in data.iter() {
+            let sa = F32x4Vec3::from_array(*a);
+            let sb = F32x4Vec3::from_array(*b);
+            let fa = FArray::<f32, 3>::from_array(*a);
+            let fb = FArray::<f32, 3>::from_array(*b);
+            let sx = sa.cross_product(&sb);
+            assert_close(sx.as_ref(), fa.cross_product(&fb).as_ref());
+            // The unused lane must still be zero
+            assert_close(&[sx.length_sq()], &[fa.cross_product(&fb).length_sq()]);
+        }
+    }
+}
+
+//ft test_matrices
+#[test]
+fn test_matrices() {
+    for (m, v) in arrays::<9>(10).iter().zip(arrays::<3>(10).iter()) {
+        let sm = F32Mat3::from_array(*m);
+        let fm = FArray2::<f32, 3, 9>::from_array(*m);
+        let sv = F32x4Vec3::from_array(*v);
+        let fv = FArray::<f32, 3>::from_array(*v);
+        assert_close(sm.transform(&sv).as_ref(), fm.transform(&fv).as_ref());
+        assert_close((sm * sm).as_ref(), (fm * fm).as_ref());
+        assert_close(sm.inverse().as_ref(), fm.inverse().as_ref());
+    }
+    for (m, v) in arrays::<16>(10).iter().zip(arrays::<4>(10).iter()) {
+        let sm = F32x4Mat4::from_array(*m);
+        let fm = FArray2::<f32, 4, 16>::from_array(*m);
+        let sv = F32x4Vec4::from_array(*v);
+        let fv = FArray::<f32, 4>::from_array(*v);
+        assert_close(sm.transform(&sv).as_ref(), fm.transform(&fv).as_ref());
+        assert_close((sm * sm).as_ref(), (fm * fm).as_ref());
+        assert_close((sm + sm * 0.5).as_ref(), (fm + fm * 0.5).as_ref());
+        assert_close((sm - sm / 4.).as_ref(), (fm - fm / 4.).as_ref());
+        assert_close(sm.transpose().as_ref(), fm.transpose().as_ref());
+        assert_close(&[sm.determinant()], &[fm.determinant()]);
+        assert_close(sm.inverse().as_ref(), fm.inverse().as_ref());
+        assert_close((sm * sm.inverse()).as_ref(), F32x4Mat4::identity().as_ref());
+    }
+    let eye = F32x4Vec3::from_array([1., 2., 3.]);
+    let centre = F32x4Vec3::from_array([-1., 0., 2.]);
+    let up = F32x4Vec3::from_array([0., 1., 0.]);
+    let m = F32x4Mat4::look_at(&eye, &centre, &up);
+    let fm = FArray2::<f32, 4, 16>::look_at(
+        &[1., 2., 3.].into(),
+        &[-1., 0., 2.].into(),
+        &[0., 1., 0.].into(),
+    );
+    assert_close(m.as_ref(), fm.as_ref());
+}
+
+//ft test_mat4_layout
+#[test]
+fn test_mat4_layout() {
+    assert_eq!(std::mem::size_of::<F32x4Mat4>(), 64);
+    assert_eq!(std::mem::align_of::<F32x4Mat4>(), 16);
+    let data: [f32; 16] = arrays::<16>(1)[0];
+    let m = F32x4Mat4::from_array(data);
+    let m_ref: &[f32; 16] = m.as_ref();
+    assert_eq!(m_ref, &data);
+    assert_eq!(m_ref.as_ptr() as usize % 16, 0);
+    assert_eq!(m[6], data[6]);
+
+    // A singular matrix inverts to zero, as for FArray2
+    let singular = [
+        1., 2., 3., 4., 5., 6., 7., 8., 2., 0., 1., 3., 1., 2., 3., 4.,
+    ];
+    assert!(F32x4Mat4::from_array(singular).inverse().is_zero());
+    assert!(F32x4Mat4::from_array(singular).try_inverse(0.).is_err());
+    assert!(F32Mat3::from_array([1., 3., 2., 0., 2., 3., 1., 5., 5.])
+        .try_inverse(0.)
+        .is_err());
+    assert!(m.try_inverse(1E-6).is_ok());
+    assert!(FArray2::<f32, 4, 16>::from_array(singular)
+        .inverse()
+        .is_zero());
+
+    let mut t = F32x4Mat4::identity();
+    t.translate3(&F32x4Vec3::from_array([1., 2., 3.]));
+    let p = t.transform(&F32x4Vec4::from_array([1., 1., 1., 1.]));
+    assert_eq!(p.into_array(), [2., 3., 4., 1.]);
+}
+
+//ft test_geometry3d
+#[test]
+fn test_geometry3d() {
+    type G = geo_nd::simd::VecF32A16;
+    type Quat = <G as Geometry3D<f32>>::Quat;
+    type Trans = <G as Geometry3D<f32>>::Trans;
+    type Vec3 = <G as Geometry3D<f32>>::Vec3;
+    type FQuat = QArray<f32, FArray<f32, 3>, FArray<f32, 4>>;
+    for (q, v) in arrays::<4>(10).iter().zip(arrays::<3>(10).iter()) {
+        let sq = Quat::from_array(*q).normalize();
+        let fq = FQuat::from_array(*q).normalize();
+        let sv = Vec3::from_array(*v);
+        let fv = FArray::<f32, 3>::from_array(*v);
+        assert_close(sq.apply3(&sv).as_ref(), fq.apply3(&fv).as_ref());
+
+        let st = Trans::of_trs(sv, sq, 2.5);
+        let ft = FQArrayTrans::of_trs(fv, fq, 2.5);
+        assert_close(st.as_mat().as_ref(), ft.as_mat().as_ref());
+        assert_close(
+            st.inverse().as_mat().as_ref(),
+            ft.inverse().as_mat().as_ref(),
+        );
+        assert_close(st.translation().as_ref(), ft.translation().as_ref());
+        assert_close(
+            (st * st * sq * 0.5 + sv).as_mat().as_ref(),
+            (ft * ft * fq * 0.5 + fv).as_mat().as_ref(),
+        );
+        assert_close(
+            st.transform_point(&sv).as_ref(),
+            ft.transform_point(&fv).as_ref(),
+        );
+        let _: F32x4Quat = st.rotation();
+        let _: F32Trans = st;
+    }
+}
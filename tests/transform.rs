@@ -0,0 +1,312 @@
+//a Imports
+use geo_nd::{FArray, FArray2, FQArrayTrans, QArray, TrsTransform};
+use geo_nd::{Quaternion, SqMatrix, Transform, Vector};
+
+mod common;
+use common::{assert_near, values};
+
+//a Types
+type Vec3 = FArray<f64, 3>;
+type Vec4 = FArray<f64, 4>;
+type Mat4 = FArray2<f64, 4, 16>;
+type Quat = QArray<f64, Vec3, Vec4>;
+type Trans = FQArrayTrans<f64>;
+type Trs = TrsTransform<f64>;
+
+//a Useful functions
+//fi transforms
+/// A set of random transformations, with their quaternions and vectors
+fn transforms(n: usize) -> Vec<(Trans, Quat, Vec3)> {
+    values(n * 8)
+        .chunks(8)
+        .map(|v| {
+            let q = Quat::of_rijk(v[0], v[1], v[2], v[3]).normalize();
+            let t = Vec3::from_array([v[4], v[5], v[6]]) * 5.;
+            let s = 1.5 + v[7];
+            (Trans::of_trs(t, q, s), q, t)
+        })
+        .collect()
+}
+
+//fi trs_transforms
+/// A set of random transformations with non-uniform scaling, with
+/// their quaternions, translations and scalings
+fn trs_transforms(n: usize) -> Vec<(Trs, Quat, Vec3, Vec3)> {
+    values(n * 10)
+        .chunks(10)
+        .map(|v| {
+            let q = Quat::of_rijk(v[0], v[1], v[2], v[3]).normalize();
+            let t = Vec3::from_array([v[4], v[5], v[6]]) * 5.;
+            let s = Vec3::from_array([1.5 + v[7], 1.5 + v[8], 1.5 + v[9]]);
+            (Trs::of_trs3(t, q, s), q, t, s)
+        })
+        .collect()
+}
+
+//fi point4
+fn point4(p: &Vec3, w: f64) -> Vec4 {
+    Vec4::from_array([p[0], p[1], p[2], w])
+}
+
+//a Tests
+//ft test_as_mat
+#[test]
+fn test_as_mat() {
+    for (trans, q, t) in transforms(10) {
+        let m = trans.as_mat();
+        let mut rotation = Mat4::default();
+        q.set_rotation4(&mut rotation);
+        let scale = Mat4::from_array([
+            trans.scale(),
+            0.,
+            0.,
+            0., //
+            0.,
+            trans.scale(),
+            0.,
+            0., //
+            0.,
+            0.,
+            trans.scale(),
+            0., //
+            0.,
+            0.,
+            0.,
+            1.,
+        ]);
+        let mut translation = Mat4::identity();
+        translation[3] = t[0];
+        translation[7] = t[1];
+        translation[11] = t[2];
+        assert_near(m.as_ref(), (translation * scale * rotation).as_ref());
+        assert_near(
+            (m * trans.inverse().as_mat()).as_ref(),
+            Mat4::identity().as_ref(),
+        );
+    }
+}
+
+//ft test_apply
+#[test]
+fn test_apply() {
+    let ps = values(30);
+    for ((trans, q, t), p) in transforms(10).into_iter().zip(ps.chunks(3)) {
+        let p = Vec3::from_array([p[0], p[1], p[2]]) * 3.;
+        let m = trans.as_mat();
+        let tp = trans.transform_point(&p);
+        assert_near(
+            tp.as_ref(),
+            &m.transform(&point4(&p, 1.)).into_array()[0..3],
+        );
+        assert_near(tp.as_ref(), (q.apply3(&p) * trans.scale() + t).as_ref());
+        let tv = trans.transform_vector(&p);
+        assert_near(
+            tv.as_ref(),
+            &m.transform(&point4(&p, 0.)).into_array()[0..3],
+        );
+        assert_near(trans.inverse().transform_point(&tp).as_ref(), p.as_ref());
+    }
+}
+
+//ft test_compose
+#[test]
+fn test_compose() {
+    let ts = transforms(12);
+    for pair in ts.chunks(2) {
+        let (a, qa, ta) = pair[0];
+        let (b, qb, _) = pair[1];
+
+        // a * b applies b then a
+        let ab = a * b;
+        assert_near(ab.as_mat().as_ref(), (a.as_mat() * b.as_mat()).as_ref());
+        let p = ta * 0.3;
+        assert_near(
+            ab.transform_point(&p).as_ref(),
+            a.transform_point(&b.transform_point(&p)).as_ref(),
+        );
+        let mut c = a;
+        c *= b;
+        assert_near(c.as_mat().as_ref(), ab.as_mat().as_ref());
+        assert_near(
+            (a * a.inverse()).as_mat().as_ref(),
+            Mat4::identity().as_ref(),
+        );
+
+        // Rotation, scaling and translation operators match composition
+        let rotation = Trans::of_trs(Vec3::zero(), qb, 1.);
+        assert_near((a * qb).as_mat().as_ref(), (a * rotation).as_mat().as_ref());
+        let scaling = Trans::of_trs(Vec3::zero(), Quat::unit(), 3.);
+        assert_near((a * 3.).as_mat().as_ref(), (a * scaling).as_mat().as_ref());
+        let translation = Trans::of_trs(ta, Quat::unit(), 1.);
+        assert_near(
+            (a + ta).as_mat().as_ref(),
+            (translation * a).as_mat().as_ref(),
+        );
+        let mut c = a;
+        c *= qb;
+        c *= 3.;
+        c += ta;
+        assert_near(
+            c.as_mat().as_ref(),
+            (translation * a * rotation * scaling).as_mat().as_ref(),
+        );
+        assert_near((a * qb).rotation().as_ref(), (qa * qb).as_ref());
+    }
+}
+
+//ft test_interpolate
+#[test]
+fn test_interpolate() {
+    let ts = transforms(2);
+    let (a, qa, ta) = ts[0];
+    let (b, qb, tb) = ts[1];
+    assert_near(a.interpolate(&b, 0.).as_mat().as_ref(), a.as_mat().as_ref());
+    assert_near(a.interpolate(&b, 1.).as_mat().as_ref(), b.as_mat().as_ref());
+
+    let mid = a.interpolate(&b, 0.25);
+    assert_near(mid.translation().as_ref(), (ta * 0.75 + tb * 0.25).as_ref());
+    assert_near(&[mid.scale()], &[a.scale() * 0.75 + b.scale() * 0.25]);
+    assert_near(mid.rotation().as_ref(), qa.slerp(&qb, 0.25).as_ref());
+    assert_near(a.lerp(&b, 0.25).as_mat().as_ref(), mid.as_mat().as_ref());
+}
+
+//ft test_trs_as_mat
+#[test]
+fn test_trs_as_mat() {
+    let ps = values(30);
+    for ((trs, q, t, s), p) in trs_transforms(10).into_iter().zip(ps.chunks(3)) {
+        let m = trs.as_mat();
+        let mut rotation = Mat4::default();
+        q.set_rotation4(&mut rotation);
+        let mut scale = Mat4::identity();
+        scale[0] = s[0];
+        scale[5] = s[1];
+        scale[10] = s[2];
+        let mut translation = Mat4::identity();
+        translation[3] = t[0];
+        translation[7] = t[1];
+        translation[11] = t[2];
+        assert_near(m.as_ref(), (translation * rotation * scale).as_ref());
+
+        let p = Vec3::from_array([p[0], p[1], p[2]]) * 3.;
+        let tp = trs.transform_point(&p);
+        assert_near(
+            tp.as_ref(),
+            &m.transform(&point4(&p, 1.)).into_array()[0..3],
+        );
+        assert_near(
+            trs.transform_vector(&p).as_ref(),
+            &m.transform(&point4(&p, 0.)).into_array()[0..3],
+        );
+        assert_near(&[trs.scale()], &[(s[0] * s[1] * s[2]).cbrt()]);
+    }
+}
+
+//ft test_trs_decompose
+#[test]
+fn test_trs_decompose() {
+    for (trs, _, _, s) in trs_transforms(10) {
+        let m = trs.as_mat();
+        let d = Trs::decompose(&m).unwrap();
+        assert_near(d.scale3().as_ref(), s.as_ref());
+        assert_near(d.translation().as_ref(), trs.translation().as_ref());
+        assert_near(d.as_mat().as_ref(), m.as_ref());
+
+        // A small scaling is recovered
+        let small = Trs::of_trs3(trs.translation(), trs.rotation(), s * 1E-6);
+        let d = Trs::decompose(&small.as_mat()).unwrap();
+        assert_near(d.as_mat().as_ref(), small.as_mat().as_ref());
+
+        // A negative scaling is recovered as a negative X scaling
+        let flipped = Trs::of_trs3(
+            trs.translation(),
+            trs.rotation(),
+            Vec3::from_array([s[0], -s[1], s[2]]),
+        );
+        let d = Trs::decompose(&flipped.as_mat()).unwrap();
+        assert!(d.scale3()[0] < 0.);
+        assert!(d.scale3()[1] > 0.);
+        assert_near(d.as_mat().as_ref(), flipped.as_mat().as_ref());
+
+        // A shear is flagged
+        let mut shear = Mat4::identity();
+        shear[1] = 0.25;
+        assert!(Trs::decompose(&(m * shear)).is_none());
+        let (d, sh) = Trs::decompose_with_shear(&(m * shear)).unwrap();
+        assert_near(&sh, &[0.25, 0., 0.]);
+        assert_near(d.as_mat().as_ref(), m.as_ref());
+
+        // Perspective and singular matrices are rejected
+        let mut perspective = m;
+        perspective[14] = 0.5;
+        assert!(Trs::decompose_with_shear(&perspective).is_none());
+        let mut singular = m;
+        for c in 0..3 {
+            singular[8 + c] = singular[c] * 2.;
+        }
+        assert!(Trs::decompose_with_shear(&singular).is_none());
+    }
+}
+
+//ft test_trs_interpolate
+#[test]
+fn test_trs_interpolate() {
+    let ts = trs_transforms(2);
+    let (a, qa, ta, sa) = ts[0];
+    let (b, qb, tb, sb) = ts[1];
+    assert_near(a.interpolate(&b, 0.).as_mat().as_ref(), a.as_mat().as_ref());
+    assert_near(a.interpolate(&b, 1.).as_mat().as_ref(), b.as_mat().as_ref());
+    assert_near(a.interpolate(&a, 0.5).scale3().as_ref(), sa.as_ref());
+    assert_near(a.lerp(&a, 0.).as_mat().as_ref(), a.as_mat().as_ref());
+
+    // The scaling along each axis is interpolated separately
+    let mid = a.interpolate(&b, 0.25);
+    assert_near(mid.translation().as_ref(), (ta * 0.75 + tb * 0.25).as_ref());
+    assert_near(mid.scale3().as_ref(), (sa * 0.75 + sb * 0.25).as_ref());
+    assert_near(mid.rotation().as_ref(), qa.slerp(&qb, 0.25).as_ref());
+    assert_near(a.lerp(&b, 0.25).as_mat().as_ref(), mid.as_mat().as_ref());
+}
+
+//ft test_trs_compose
+#[test]
+fn test_trs_compose() {
+    // With a uniform scaling the transformations match FQArrayTrans
+    for (trans, q, t) in transforms(6) {
+        let trs: Trs = trans.into();
+        assert_near(trs.as_mat().as_ref(), trans.as_mat().as_ref());
+        assert_near(
+            trs.inverse().as_mat().as_ref(),
+            trans.inverse().as_mat().as_ref(),
+        );
+        assert_near((trs * q).as_mat().as_ref(), (trans * q).as_mat().as_ref());
+        assert_near((trs + t).as_mat().as_ref(), (trans + t).as_mat().as_ref());
+        assert_near((trs * 2.).as_mat().as_ref(), (trans * 2.).as_mat().as_ref());
+    }
+
+    // A uniformly-scaled transformation applied after a non-uniform
+    // one has no shear, so composition is exact
+    for ((trans, _, _), (trs, _, _, _)) in transforms(6).into_iter().zip(trs_transforms(6)) {
+        let uniform: Trs = trans.into();
+        let c = uniform * trs;
+        assert_near(
+            c.as_mat().as_ref(),
+            (trans.as_mat() * trs.as_mat()).as_ref(),
+        );
+        let mut d = uniform;
+        d *= trs;
+        assert_near(d.as_mat().as_ref(), c.as_mat().as_ref());
+
+        // Without a rotation the inverse has no shear, so it is exact
+        let unrotated = Trs::of_trs3(trs.translation(), Quat::unit(), trs.scale3());
+        assert_near(
+            (unrotated * unrotated.inverse()).as_mat().as_ref(),
+            Mat4::identity().as_ref(),
+        );
+        assert_near(
+            unrotated.inverse().as_mat().as_ref(),
+            geo_nd::matrix::inverse4(unrotated.as_mat().as_ref()).as_ref(),
+        );
+    }
+    let singular = Trs::of_trs3(Vec3::zero(), Quat::unit(), Vec3::from_array([1., 0., 2.]));
+    assert!(singular.try_inverse(1E-10).is_err());
+}
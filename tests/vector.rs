@@ -158,15 +158,17 @@ fn test_fslice() {
     Banana::<f32>::test_vec4();
 }
 
-#[cfg(feature = "simd")]
-extern crate core_simd;
 #[cfg(feature = "simd")]
 mod test_simd {
-    use geometry::simd::VecF32A16;
+    use geo_nd::simd::{VecF32A16, VecF32A8};
     #[test]
     fn test_simd() {
         super::Banana::<VecF32A16>::test_vec2();
         super::Banana::<VecF32A16>::test_vec3();
         super::Banana::<VecF32A16>::test_vec4();
     }
+    #[test]
+    fn test_simd_a8() {
+        super::Banana::<VecF32A8>::test_vec2();
+    }
 }